@@ -0,0 +1,145 @@
+//! A small, read-only HTTP API over player data, for dashboards.
+//!
+//! Only runs if `API_ENABLED=true` is set, and refuses to start without an
+//! `API_TOKEN`. Every request must send that token as
+//! `Authorization: Bearer <API_TOKEN>`. Listens on `API_ADDR`
+//! (defaults to [`DEFAULT_API_ADDR`]).
+//!
+//! Endpoints:
+//! - `GET /players` - every player, sorted by rank.
+//! - `GET /players/:id` - a single player.
+//! - `GET /players/:id/achievements` - every achievement a player has received, newest first.
+
+use crate::{ Serialize, file_management, player_data::{self, Player}, achievement_data::AchievementRecord };
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+pub const DEFAULT_API_ADDR: &str = "127.0.0.1:8080";
+
+/// A player, along with everything calculated from their data.
+#[derive(Serialize)]
+pub struct PlayerView {
+    pub user_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    pub lvl: i64,
+    pub xp: i128,
+    pub xp_threshold: i128,
+    pub prestige: f64,
+    pub prestige_threshold: i64,
+    pub prestige_points: f64,
+    pub title: String,
+    pub title_segments: Vec<String>,
+}
+
+impl PlayerView {
+    pub fn new(p: &Player, rank: Option<usize>) -> PlayerView {
+        PlayerView {
+            user_id: p.user_id,
+            rank,
+            lvl: p.lvl,
+            xp: p.xp,
+            xp_threshold: p.xp_threshold(),
+            prestige: p.prestige,
+            prestige_threshold: p.prestige_threshold,
+            prestige_points: p.prestige_points(),
+            title: p.title().trim_end().to_string(),
+            title_segments: p.title_segments.clone(),
+        }
+    }
+}
+
+/// An error, sent back as `{"error": "..."}`.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<crate::Error> for ApiError {
+    fn from(e: crate::Error) -> ApiError {
+        tracing::error!(error = %e, "API request failed");
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load player data".to_string())
+    }
+}
+
+/// Every player, sorted best first, as `(rank, player)`.
+fn ranked_players() -> Result<Vec<(usize, Player)>, crate::Error> {
+    Ok(player_data::rank_players(file_management::load_players()?))
+}
+
+async fn list_players() -> Result<Json<Vec<PlayerView>>, ApiError> {
+    Ok(Json(ranked_players()?.iter().map(|(rank, p)| PlayerView::new(p, Some(*rank))).collect()))
+}
+
+async fn get_player(Path(id): Path<u64>) -> Result<Json<PlayerView>, ApiError> {
+    ranked_players()?
+        .iter()
+        .find(|(_, p)| p.user_id == id)
+        .map(|(rank, p)| Json(PlayerView::new(p, Some(*rank))))
+        .ok_or(ApiError(StatusCode::NOT_FOUND, format!("No player with ID {id}")))
+}
+
+async fn get_achievements(Path(id): Path<u64>) -> Result<Json<Vec<AchievementRecord>>, ApiError> {
+    let data = file_management::load()?;
+    if !data.player_list.iter().any(|x| x.user_id == id) {
+        return Err(ApiError(StatusCode::NOT_FOUND, format!("No player with ID {id}")))
+    }
+    Ok(Json(data.achievements.into_iter().filter(|x| x.recipient_id == id).rev().collect()))
+}
+
+/// Rejects any request without the right bearer token.
+async fn auth(State(token): State<Arc<String>>, request: Request, next: Next) -> Response {
+    let given = request.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.strip_prefix("Bearer "));
+
+    match given {
+        Some(given) if given == token.as_str() => next.run(request).await,
+        _ => ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()).into_response(),
+    }
+}
+
+/// Starts the API server, if `API_ENABLED` is set.
+///
+/// Runs in the background on the current tokio runtime.
+pub fn spawn_server() {
+    if !std::env::var("API_ENABLED").is_ok_and(|x| x == "true" || x == "1") {
+        return
+    }
+    let Some(token) = std::env::var("API_TOKEN").ok().filter(|x| !x.is_empty()) else {
+        tracing::error!("API_ENABLED is set, but API_TOKEN is not - refusing to start the API without authentication");
+        return
+    };
+    let addr = std::env::var("API_ADDR").unwrap_or_else(|_| DEFAULT_API_ADDR.to_string());
+
+    let app = Router::new()
+        .route("/players", get(list_players))
+        .route("/players/:id", get(get_player))
+        .route("/players/:id/achievements", get(get_achievements))
+        .layer(middleware::from_fn_with_state(Arc::new(token), auth));
+
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(&addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(addr, error = %e, "Failed to start API server");
+                return
+            }
+        };
+        tracing::info!(addr, "Serving API");
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "API server stopped");
+        }
+    });
+}
@@ -0,0 +1,392 @@
+//! An append-only log of every change made to player data.
+//!
+//! Each change is written as one line of JSON to [`AUDIT_FILENAME`],
+//! which sits next to `saved_data.json`. If `AUDIT_CHANNEL_ID` is set,
+//! a readable version of each event is also posted in that channel.
+//!
+//! Events carry the state of the [`Player`] *after* the change, so the
+//! log can be [replayed](replay) from the start to rebuild the save file
+//! if it is ever lost or corrupted:
+//!
+//! ```text
+//! cargo run -- rebuild-from-audit
+//! ```
+//!
+//! Posting a [digest](crate::digest) isn't a change to player data, so isn't
+//! logged. A rebuilt save file starts with no digests posted, and each server's
+//! digests carry on from the next one due, without catching up on any missed
+//! before the rebuild.
+
+use crate::{ Context, Serialize, Deserialize, serenity, webhooks, player_data::Player, achievement_data::AchievementRecord, admin_data::AdminAction, guild_config::GuildConfig, json_data::FileFormat, teams::{self, Team}, challenges::{self, Challenge}, bounties::{self, Bounty} };
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io::Write;
+
+pub const AUDIT_FILENAME: &str = "audit_log.jsonl";
+
+/// Something that changed a player's data.
+#[derive(Serialize,Deserialize,Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+
+    /// Somebody used [`/achievement`](crate::commands::achievement).
+    AchievementGranted {
+        record: AchievementRecord,
+        player: Player,
+    },
+
+    /// An achievement was undone or revoked, and its XP taken back.
+    AchievementRevoked {
+        achievement_id: u64,
+        revoked_by: u64,
+        player: Player,
+    },
+
+    /// A player went up or down a level.
+    ///
+    /// This is only informational - the player's state is carried by
+    /// whichever event caused the change. `player` is the state just after
+    /// the change, and is missing from events logged by older versions.
+    LevelChanged {
+        user_id: u64,
+        old_lvl: i64,
+        new_lvl: i64,
+        #[serde(default)]
+        player: Option<Player>,
+    },
+
+    /// A player used [`/prestige`](crate::commands::prestige).
+    Prestiged {
+        old_prestige: f64,
+        title: String,
+        player: Player,
+    },
+
+    /// A player changed a word in their title with [`/title`](crate::commands::update_title).
+    TitleChanged {
+        old_title: String,
+        player: Player,
+    },
+
+    /// A player bought a rank of a [perk](crate::perks).
+    PerkBought {
+        perk: String,
+        player: Player,
+    },
+
+    /// A player bought an item from the [shop](crate::shop).
+    ItemBought {
+        item: String,
+        price: u64,
+        player: Player,
+    },
+
+    /// A player equipped or unequipped a [shop](crate::shop) item.
+    ItemEquipped {
+        item: String,
+        equipped: bool,
+        player: Player,
+    },
+
+    /// Somebody created, joined or left a [team](crate::teams), or was invited to
+    /// or kicked from one. `team` is the state just after the change.
+    TeamChanged {
+        change: teams::Change,
+        user_id: u64,
+        team: Team,
+    },
+
+    /// The last member left a team, so it was disbanded.
+    TeamDisbanded {
+        team_id: u64,
+        name: String,
+        user_id: u64,
+    },
+
+    /// A [challenge](crate::challenges) was sent, accepted, or finished.
+    ///
+    /// `challenge` is the state just after the change, and `players` is
+    /// everybody whose XP it held or gave back.
+    ChallengeChanged {
+        challenge: Challenge,
+        players: Vec<Player>,
+    },
+
+    /// A [bounty](crate::bounties) was posted, claimed, paid or closed.
+    ///
+    /// `bounty` is the state just after the change, and `user_id` is whoever
+    /// made it. `player` is the poster, if the change held or gave back their
+    /// XP. Paying a bounty is also logged as an [`AchievementGranted`](Event::AchievementGranted),
+    /// which carries the claimant. `action` is the admin log entry for a minted bounty.
+    BountyChanged {
+        change: bounties::Change,
+        user_id: u64,
+        bounty: Bounty,
+        #[serde(default)]
+        player: Option<Player>,
+        #[serde(default)]
+        action: Option<AdminAction>,
+    },
+
+    /// An admin changed a player by hand.
+    ///
+    /// `player` is `None` if the player was deleted.
+    AdminOverride {
+        action: AdminAction,
+        player: Option<Player>,
+    },
+
+    /// An admin changed a server's settings.
+    ConfigChanged {
+        guild_id: u64,
+        action: AdminAction,
+        config: GuildConfig,
+    },
+
+    /// Somebody chose a language with [`/language`](crate::commands::language).
+    ///
+    /// `locale` is `None` if they went back to the automatic choice.
+    LocaleChanged {
+        user_id: u64,
+        locale: Option<String>,
+    },
+}
+
+impl Event {
+
+    /// A short, human readable description of the event.
+    pub fn describe(&self) -> String {
+        match self {
+            Event::AchievementGranted { record, .. } =>
+                format!("<@{}> granted <@{}> achievement #{} \"{}\" for {} XP ({} after multipliers).",
+                        record.granter_id, record.recipient_id, record.id, record.title, record.base_xp, record.effective_xp),
+            Event::AchievementRevoked { achievement_id, revoked_by, player } =>
+                format!("<@{revoked_by}> revoked achievement #{achievement_id} from <@{}>.", player.user_id),
+            Event::LevelChanged { user_id, old_lvl, new_lvl, .. } =>
+                format!("<@{user_id}> went from Lv. {old_lvl} to Lv. {new_lvl}."),
+            Event::Prestiged { old_prestige, title, player } =>
+                format!("<@{}> prestiged from {old_prestige:.2} to {:.2}, and gained the title \"{title}\".",
+                        player.user_id, player.prestige),
+            Event::TitleChanged { old_title, player } =>
+                format!("<@{}> changed their title from \"{}\" to \"{}\".",
+                        player.user_id, old_title.trim_end(), player.title().trim_end()),
+            Event::PerkBought { perk, player } =>
+                format!("<@{}> bought rank {} of the {} perk.",
+                        player.user_id, player.perks.get(perk).copied().unwrap_or(0), perk),
+            Event::ItemBought { item, price, player } =>
+                format!("<@{}> bought {} for {} coins, and has {} left.",
+                        player.user_id, item, price, player.coins),
+            Event::ItemEquipped { item, equipped, player } =>
+                format!("<@{}> {} {}.",
+                        player.user_id, if *equipped { "equipped" } else { "unequipped" }, item),
+            Event::TeamChanged { change, user_id, team } => match change {
+                teams::Change::Created => format!("<@{user_id}> created the team {}.", team.display_name()),
+                teams::Change::Invited => format!("<@{}> invited <@{user_id}> to {}.", team.owner_id, team.display_name()),
+                teams::Change::Joined => format!("<@{user_id}> joined {}.", team.display_name()),
+                teams::Change::Left => format!("<@{user_id}> left {}.", team.display_name()),
+                teams::Change::Kicked => format!("<@{}> kicked <@{user_id}> from {}.", team.owner_id, team.display_name()),
+            },
+            Event::TeamDisbanded { name, user_id, .. } =>
+                format!("<@{user_id}> left {name}, the last member, so it was disbanded."),
+            Event::ChallengeChanged { challenge: c, .. } => match c.state {
+                challenges::State::Pending =>
+                    format!("<@{}> challenged <@{}> to \"{}\" for {} XP each (challenge #{}).",
+                            c.challenger_id, c.target_id, c.description, c.stake, c.id),
+                challenges::State::Accepted =>
+                    format!("<@{}> accepted challenge #{} from <@{}>.", c.target_id, c.id, c.challenger_id),
+                challenges::State::Won => {
+                    let winner = format!("<@{}> won challenge #{}, and {} XP.", c.winner_id.unwrap_or_default(), c.id, c.pot());
+                    match c.referee_id {
+                        Some(referee_id) => format!("{winner} Decided by <@{referee_id}>."),
+                        None => winner,
+                    }
+                },
+                challenges::State::Declined =>
+                    format!("<@{}> declined challenge #{} from <@{}>.", c.target_id, c.id, c.challenger_id),
+                challenges::State::CalledOff =>
+                    format!("Challenge #{} between <@{}> and <@{}> was called off.", c.id, c.challenger_id, c.target_id),
+                challenges::State::Expired =>
+                    format!("Challenge #{} between <@{}> and <@{}> expired.", c.id, c.challenger_id, c.target_id),
+            },
+            Event::BountyChanged { change, user_id, bounty: b, .. } => match change {
+                bounties::Change::Posted => match b.funding {
+                    bounties::Funding::Poster =>
+                        format!("<@{user_id}> posted bounty #{} \"{}\" for {} XP.", b.id, b.title, b.reward),
+                    bounties::Funding::Minted =>
+                        format!("<@{user_id}> minted bounty #{} \"{}\" for {} XP.", b.id, b.title, b.reward),
+                },
+                bounties::Change::Claimed => format!("<@{user_id}> claimed bounty #{} from <@{}>.", b.id, b.poster_id),
+                bounties::Change::Paid =>
+                    format!("<@{}> paid bounty #{} to <@{user_id}>, as achievement #{}.",
+                            b.poster_id, b.id, b.achievement_id.unwrap_or_default()),
+                bounties::Change::Cancelled => format!("<@{user_id}> cancelled bounty #{}.", b.id),
+                bounties::Change::Expired => format!("Bounty #{} from <@{}> expired.", b.id, b.poster_id),
+            },
+            Event::AdminOverride { action, .. } =>
+                format!("<@{}> changed <@{}>: {} (reason: {})",
+                        action.actor_id, action.target_id, action.action, action.reason),
+            Event::ConfigChanged { action, .. } =>
+                format!("<@{}> changed the server settings: {} (reason: {})",
+                        action.actor_id, action.action, action.reason),
+            Event::LocaleChanged { user_id, locale } => match locale {
+                Some(locale) => format!("<@{user_id}> chose {locale} as their language."),
+                None => format!("<@{user_id}> went back to the automatic language."),
+            },
+        }
+    }
+}
+
+/// One line of the audit log.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Entry {
+    pub timestamp: DateTime<Utc>,
+    pub event: Event,
+}
+
+/// Records an event in the audit log, and mirrors it to the audit channel if there is one.
+///
+/// Failing to record an event never stops the command that caused it -
+/// the error is just printed instead.
+pub async fn record(ctx: Context<'_>, event: Event) {
+    record_http(ctx.http(), ctx.data().audit_channel, event).await
+}
+
+/// Like [`record()`], for when there's no command to take the audit channel
+/// from, like a button press or a background task.
+pub async fn record_http(http: &serenity::Http, audit_channel: Option<serenity::ChannelId>, event: Event) {
+    let entry = record_offline(event);
+
+    if let Some(channel) = audit_channel {
+        let message = serenity::CreateMessage::new()
+            .content(entry.event.describe())
+            .allowed_mentions(serenity::CreateAllowedMentions::new());
+        if let Err(e) = channel.send_message(http, message).await {
+            tracing::warn!(error = %e, "Failed to mirror audit event to channel");
+        }
+    }
+}
+
+/// Records an event in the audit log and queues its webhooks, without
+/// needing Discord. Used by [terminal commands](crate::cli).
+pub fn record_offline(event: Event) -> Entry {
+    let entry = Entry { timestamp: Utc::now(), event };
+
+    if let Err(e) = append(&entry) {
+        tracing::error!(error = %e, "Failed to write to audit log");
+    }
+
+    webhooks::dispatch(&entry);
+    entry
+}
+
+/// Appends a single entry to the end of the audit file.
+fn append(entry: &Entry) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(AUDIT_FILENAME)?;
+    let line = serde_json::to_string(entry)?;
+    writeln!(file, "{line}")
+}
+
+/// Reads every entry in the audit log, in order.
+///
+/// If the file doesn't exist, returns an empty vector.
+pub fn load() -> Vec<Entry> {
+    let Ok(data) = fs::read_to_string(AUDIT_FILENAME) else {
+        return vec![]
+    };
+
+    data.lines()
+        .filter(|x| !x.trim().is_empty())
+        .enumerate()
+        .filter_map(|(i, x)| match serde_json::from_str(x) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!(line = i + 1, error = %e, "Skipping unreadable audit log line");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Rebuilds the save file's state from the audit log.
+///
+/// Starts from an empty [`FileFormat`], and applies every event in order.
+/// Players who never had anything happen to them won't be present, but
+/// they would only have been brand new players anyway.
+pub fn replay(entries: &[Entry]) -> FileFormat {
+    let mut data = FileFormat::new();
+
+    for entry in entries {
+        match &entry.event {
+            Event::AchievementGranted { record, player } => {
+                teams::credit(&mut data.teams, record.team_id, record.effective_xp);
+                data.achievements.push(record.clone());
+                upsert(&mut data, player.clone());
+            },
+            Event::AchievementRevoked { achievement_id, player, .. } => {
+                if let Some(record) = data.achievements.iter_mut().find(|x| x.id == *achievement_id && !x.revoked) {
+                    record.revoked = true;
+                    teams::credit(&mut data.teams, record.team_id, -record.effective_xp);
+                }
+                upsert(&mut data, player.clone());
+            },
+            Event::LevelChanged { .. } => {},
+            Event::Prestiged { player, .. } | Event::TitleChanged { player, .. }
+            | Event::PerkBought { player, .. } | Event::ItemBought { player, .. }
+            | Event::ItemEquipped { player, .. } => upsert(&mut data, player.clone()),
+            Event::TeamChanged { team, .. } => match data.teams.iter_mut().find(|x| x.id == team.id) {
+                Some(x) => *x = team.clone(),
+                None => data.teams.push(team.clone()),
+            },
+            Event::TeamDisbanded { team_id, .. } => data.teams.retain(|x| x.id != *team_id),
+            Event::ChallengeChanged { challenge, players } => {
+                match data.challenges.iter_mut().find(|x| x.id == challenge.id) {
+                    Some(x) => *x = challenge.clone(),
+                    None => data.challenges.push(challenge.clone()),
+                }
+                for p in players {
+                    upsert(&mut data, p.clone());
+                }
+            },
+            Event::BountyChanged { bounty, player, action, .. } => {
+                match data.bounties.iter_mut().find(|x| x.id == bounty.id) {
+                    Some(x) => *x = bounty.clone(),
+                    None => data.bounties.push(bounty.clone()),
+                }
+                if let Some(p) = player {
+                    upsert(&mut data, p.clone());
+                }
+                if let Some(action) = action {
+                    data.admin_log.push(action.clone());
+                }
+            },
+            Event::AdminOverride { action, player } => {
+                data.admin_log.push(action.clone());
+                match player {
+                    Some(p) => upsert(&mut data, p.clone()),
+                    None => { data.remove_player(action.target_id); },
+                }
+            },
+            Event::ConfigChanged { guild_id, action, config } => {
+                data.admin_log.push(action.clone());
+                data.guild_configs.insert(*guild_id, config.clone());
+            },
+            Event::LocaleChanged { user_id, locale } => match locale {
+                Some(locale) => { data.user_locales.insert(*user_id, locale.clone()); },
+                None => { data.user_locales.remove(user_id); },
+            },
+        }
+    }
+
+    data
+}
+
+/// Replaces a player in the list with a newer version of them, or adds them if they're new.
+fn upsert(data: &mut FileFormat, player: Player) {
+    match data.player_mut(player.user_id) {
+        Some(p) => *p = player,
+        None => data.player_list.push(player),
+    }
+}
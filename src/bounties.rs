@@ -0,0 +1,454 @@
+//! Bounties: achievements that anybody in a server can claim.
+//!
+//! A member [posts](crate::commands::bounty_post) a bounty with a reward,
+//! which is [held](Player::hold_xp) from the XP in their current level
+//! straight away. Admins can also [mint](crate::commands::admin::bounty)
+//! bounties, whose reward doesn't come from anybody.
+//!
+//! Other members [claim](crate::commands::bounty_claim) a bounty with some
+//! proof, and the poster [approves](crate::commands::bounty_approve) one of
+//! the claims. The claimant is then granted an achievement for the reward, in
+//! exactly the same way as [`/achievement`](crate::commands::achievement),
+//! so multipliers, teams, medals, coins and the server's
+//! [limits](crate::guild_config::XpLimits) (with the poster as the granter)
+//! all apply.
+//!
+//! A bounty that nobody is paid for before it expires, or that its poster
+//! cancels, gives the held XP back to the poster. Bounties are saved in
+//! [`FileFormat::bounties`].
+
+use crate::{ Error, Serialize, Deserialize, serenity, audit, file_management, i18n::tr, player_data::Player, admin_data::AdminAction, json_data::FileFormat };
+use chrono::{DateTime, TimeDelta, Utc};
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How long a bounty lasts if the poster doesn't say.
+pub const DEFAULT_DAYS: i64 = 7;
+
+/// The longest a bounty can last.
+pub const MAX_DAYS: i64 = 30;
+
+/// The most bounties a member can have open at once in a server. Minted bounties don't count.
+pub const MAX_OPEN_PER_POSTER: usize = 5;
+
+/// The longest a bounty's title can be, in characters.
+pub const MAX_TITLE_LENGTH: usize = 100;
+
+/// The longest the proof for a claim can be, in characters.
+pub const MAX_PROOF_LENGTH: usize = 500;
+
+/// The most claims a single bounty can have.
+pub const MAX_CLAIMS: usize = 20;
+
+/// How many bounties are shown on each page of [`/bounties`](crate::commands::bounties).
+pub const PAGE_SIZE: usize = 10;
+
+/// How often to check for bounties that have expired.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Where a bounty's reward comes from.
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Funding {
+
+    /// Held from the poster's XP, and given back if nobody is paid.
+    Poster,
+
+    /// Created by an admin, out of nothing.
+    Minted,
+}
+
+/// Where a bounty is up to.
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+
+    /// Waiting for somebody to claim it, and the poster to approve them.
+    Open,
+
+    /// A claimant was approved, and granted the reward.
+    Paid,
+
+    /// The poster took it down before anybody was paid.
+    Cancelled,
+
+    /// Nobody was paid in time.
+    Expired,
+}
+
+/// What happened to a bounty, in an [audit event](crate::audit::Event::BountyChanged).
+#[derive(Serialize,Deserialize,Clone,Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Posted,
+    Claimed,
+    Paid,
+    Cancelled,
+    Expired,
+}
+
+/// Somebody saying they've done what a bounty asks.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Claim {
+    pub user_id: u64,
+
+    /// What the claimant says they did, as typed by them.
+    pub proof: String,
+
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A reward for doing something, which anybody in the server can claim.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Bounty {
+    pub id: u64,
+    pub guild_id: u64,
+
+    /// Who posted the bounty, and approves claims. For a minted bounty, the admin who minted it.
+    pub poster_id: u64,
+
+    /// What needs doing. Also the title of the achievement the claimant is granted.
+    pub title: String,
+
+    /// The XP granted to the claimant, before their multipliers.
+    pub reward: i128,
+
+    pub funding: Funding,
+    pub state: State,
+
+    /// Everybody who has claimed the bounty, in the order they last claimed it.
+    #[serde(default)]
+    pub claims: Vec<Claim>,
+
+    /// Who was paid, and the achievement they were granted, once the bounty is [paid](State::Paid).
+    #[serde(default)]
+    pub winner_id: Option<u64>,
+    #[serde(default)]
+    pub achievement_id: Option<u64>,
+
+    pub created: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    #[serde(default)]
+    pub finished: Option<DateTime<Utc>>,
+}
+
+impl Bounty {
+
+    /// A new, open bounty, lasting `days` days. Its ID is given by [`post()`].
+    pub fn new(guild_id: u64, poster_id: u64, title: String, reward: i128, funding: Funding, days: i64) -> Bounty {
+        let created = Utc::now();
+        Bounty {
+            id: 0,
+            guild_id,
+            poster_id,
+            title: title.trim().to_string(),
+            reward,
+            funding,
+            state: State::Open,
+            claims: vec![],
+            winner_id: None,
+            achievement_id: None,
+            created,
+            expires: created + TimeDelta::days(days),
+            finished: None,
+        }
+    }
+
+    /// Whether the bounty can still be claimed and paid.
+    pub fn is_open(&self) -> bool {
+        self.state == State::Open
+    }
+
+    /// Whether somebody has claimed the bounty.
+    pub fn claimed_by(&self, user_id: u64) -> bool {
+        self.claims.iter().any(|x| x.user_id == user_id)
+    }
+
+    /// Claims the bounty for `user_id`. Claiming it again replaces their proof,
+    /// and moves them to the end of the claims.
+    ///
+    /// Returns why they can't, in the language `lang`.
+    pub fn claim(&mut self, user_id: u64, proof: &str, lang: &str) -> Result<(), String> {
+        let proof = proof.trim();
+        if !self.is_open() {
+            return Err(tr!(lang, "bounty-closed", id = self.id))
+        }
+        if user_id == self.poster_id {
+            return Err(tr!(lang, "bounty-claim-own"))
+        }
+        if proof.is_empty() || proof.graphemes(true).count() > MAX_PROOF_LENGTH {
+            return Err(tr!(lang, "bounty-invalid-proof", max = MAX_PROOF_LENGTH))
+        }
+        if !self.claimed_by(user_id) && self.claims.len() >= MAX_CLAIMS {
+            return Err(tr!(lang, "bounty-too-many-claims", max = MAX_CLAIMS))
+        }
+        self.claims.retain(|x| x.user_id != user_id);
+        self.claims.push(Claim { user_id, proof: proof.to_string(), timestamp: Utc::now() });
+        tracing::info!(bounty_id = self.id, user_id, "Bounty claimed");
+        Ok(())
+    }
+
+    /// Where the bounty is up to, for [`embed()`](Bounty::embed).
+    fn status(&self, lang: &str) -> String {
+        match self.state {
+            State::Open => tr!(lang, "bounty-status-open", expires = format!("<t:{}:R>", self.expires.timestamp())),
+            State::Paid => tr!(lang, "bounty-status-paid", user = format!("<@{}>", self.winner_id.unwrap_or_default())),
+            State::Cancelled => tr!(lang, "bounty-status-cancelled"),
+            State::Expired => tr!(lang, "bounty-status-expired"),
+        }
+    }
+
+    /// A single line about the bounty, for [`/bounties`](crate::commands::bounties).
+    pub fn summary(&self, lang: &str) -> String {
+        tr!(lang, "bounty-summary", id = self.id, title = self.title.clone(), reward = self.reward.to_string(),
+            poster = format!("<@{}>", self.poster_id), claims = self.claims.len(),
+            expires = format!("<t:{}:R>", self.expires.timestamp()))
+    }
+
+    /// Everything about the bounty, including every claim.
+    pub fn embed(&self, lang: &str, colour: u32) -> serenity::CreateEmbed {
+        let poster = match self.funding {
+            Funding::Poster => format!("<@{}>", self.poster_id),
+            Funding::Minted => tr!(lang, "bounty-minted-by", user = format!("<@{}>", self.poster_id)),
+        };
+        let claims = self.claims.iter()
+            .map(|x| format!("<@{}>: {}", x.user_id, x.proof))
+            .collect::<Vec<_>>();
+        serenity::CreateEmbed::new()
+            .title(tr!(lang, "bounty-title", id = self.id))
+            .colour(colour)
+            .description(self.title.clone())
+            .fields([
+                (tr!(lang, "field-poster"), poster, true),
+                (tr!(lang, "field-reward"), tr!(lang, "bounty-reward", reward = self.reward.to_string()), true),
+                (tr!(lang, "field-status"), self.status(lang), false),
+                (tr!(lang, "field-claims", count = self.claims.len()),
+                 if claims.is_empty() { tr!(lang, "bounty-no-claims") } else { claims.join("\n") }, false),
+            ])
+    }
+}
+
+/// Every open bounty in a server, oldest first.
+pub fn open_in(bounties: &[Bounty], guild_id: u64) -> Vec<&Bounty> {
+    bounties.iter().filter(|x| x.guild_id == guild_id && x.is_open()).collect()
+}
+
+/// Finds an open bounty in a server by its ID, for commands that change it.
+///
+/// Returns its index in `bounties`, or why it can't be changed, in the language `lang`.
+pub fn find_open(bounties: &[Bounty], guild_id: u64, id: u64, lang: &str) -> Result<usize, String> {
+    match bounties.iter().position(|x| x.id == id && x.guild_id == guild_id) {
+        None => Err(tr!(lang, "bounty-unknown", id = id)),
+        Some(index) if !bounties[index].is_open() => Err(tr!(lang, "bounty-closed", id = id)),
+        Some(index) => Ok(index),
+    }
+}
+
+/// Posts a bounty, holding its reward from the poster if they're funding it.
+///
+/// Returns the bounty and (if their XP was held) the poster afterwards, or
+/// why it can't be posted, in the language `lang`.
+pub fn post(data: &mut FileFormat, mut bounty: Bounty, lang: &str) -> Result<(Bounty, Option<Player>), String> {
+    let length = bounty.title.graphemes(true).count();
+    if length == 0 || length > MAX_TITLE_LENGTH {
+        return Err(tr!(lang, "bounty-invalid-title", max = MAX_TITLE_LENGTH))
+    }
+    if bounty.expires <= bounty.created || bounty.expires > bounty.created + TimeDelta::days(MAX_DAYS) {
+        return Err(tr!(lang, "bounty-invalid-days", max = MAX_DAYS))
+    }
+    // Checked here as well as when it's approved, so a bounty can't hold XP it could never pay out.
+    let max_xp = data.guild_config(Some(bounty.guild_id)).limits.max_xp_per_achievement;
+    if bounty.reward < 1 || bounty.reward > max_xp {
+        return Err(tr!(lang, "bounty-invalid-reward", max = max_xp.to_string()))
+    }
+
+    let player = match bounty.funding {
+        Funding::Minted => None,
+        Funding::Poster => {
+            let open = data.bounties.iter()
+                .filter(|x| x.guild_id == bounty.guild_id && x.poster_id == bounty.poster_id && x.funding == Funding::Poster && x.is_open())
+                .count();
+            if open >= MAX_OPEN_PER_POSTER {
+                return Err(tr!(lang, "bounty-too-many", max = MAX_OPEN_PER_POSTER))
+            }
+            let Some(p) = data.player_mut(bounty.poster_id) else {
+                return Err(tr!(lang, "hold-not-enough-xp", user = format!("<@{}>", bounty.poster_id),
+                               held = bounty.reward.to_string(), xp = 0))
+            };
+            p.hold_xp(bounty.reward, lang)?;
+            Some(p.clone())
+        },
+    };
+
+    bounty.id = data.bounties.iter().map(|x| x.id + 1).max().unwrap_or(1);
+    tracing::info!(bounty_id = bounty.id, poster_id = bounty.poster_id, reward = %bounty.reward, funding = ?bounty.funding, "Bounty posted");
+    data.bounties.push(bounty.clone());
+    Ok((bounty, player))
+}
+
+/// Closes a bounty without paying anybody, giving its reward back to the
+/// poster if they funded it.
+///
+/// Runs [`lvl_check()`](Player::lvl_check) for the poster. Returns them
+/// afterwards, with the level they were at before.
+pub async fn close(data: &mut FileFormat, index: usize, state: State) -> Option<(Player, i64)> {
+    let bounty = &mut data.bounties[index];
+    bounty.state = state;
+    bounty.finished = Some(Utc::now());
+    tracing::info!(bounty_id = bounty.id, ?state, "Bounty closed");
+    if bounty.funding == Funding::Minted {
+        return None
+    }
+
+    let (poster_id, reward) = (bounty.poster_id, bounty.reward);
+    let Some(p) = data.player_mut(poster_id) else {
+        tracing::warn!(user_id = poster_id, xp = %reward, "Poster of a bounty no longer exists, so their XP was lost");
+        return None
+    };
+    let old_lvl = p.lvl;
+    p.xp += reward;
+    p.lvl_check(None).await;
+    Some((p.clone(), old_lvl))
+}
+
+/// Records a change to a bounty in the [`audit`] log, along with any level it changed.
+///
+/// `user_id` is whoever made the change: the poster, the claimant, or (when it
+/// expires) the poster again.
+pub async fn record(
+    http: &serenity::Http,
+    audit_channel: Option<serenity::ChannelId>,
+    change: Change,
+    user_id: u64,
+    bounty: &Bounty,
+    player: Option<(Player, i64)>,
+    action: Option<AdminAction>,
+) {
+    if let Some((p, old_lvl)) = &player
+        && p.lvl != *old_lvl {
+        audit::record_http(http, audit_channel, audit::Event::LevelChanged {
+            user_id: p.user_id,
+            old_lvl: *old_lvl,
+            new_lvl: p.lvl,
+            player: Some(p.clone()),
+        }).await;
+    }
+    audit::record_http(http, audit_channel, audit::Event::BountyChanged {
+        change,
+        user_id,
+        bounty: bounty.clone(),
+        player: player.map(|(p, _)| p),
+        action,
+    }).await;
+}
+
+/// Expires every open bounty that's run out of time, giving back the XP it held.
+///
+/// Everything is saved before anything is recorded.
+async fn expire_due(http: &serenity::Http, audit_channel: Option<serenity::ChannelId>, lock: &file_management::Lock) -> Result<(), Error> {
+    let guard = lock.lock().await;
+    let mut data = file_management::load()?;
+    let now = Utc::now();
+    let due = data.bounties.iter()
+        .enumerate()
+        .filter(|(_, x)| x.is_open() && x.expires <= now)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if due.is_empty() {
+        return Ok(())
+    }
+
+    let mut expired = vec![];
+    for index in due {
+        let player = close(&mut data, index, State::Expired).await;
+        expired.push((data.bounties[index].clone(), player));
+    }
+    file_management::save(&data)?;
+    drop(guard);
+
+    for (bounty, player) in expired {
+        record(http, audit_channel, Change::Expired, bounty.poster_id, &bounty, player, None).await;
+    }
+    Ok(())
+}
+
+/// Starts expiring bounties in the background, forever.
+pub fn spawn_expirer(http: Arc<serenity::Http>, audit_channel: Option<serenity::ChannelId>, lock: file_management::Lock) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = expire_due(&http, audit_channel, &lock).await {
+                tracing::error!(error = %e, "Failed to expire bounties");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::DEFAULT_LOCALE;
+
+    const GUILD: u64 = 1;
+    const POSTER: u64 = 1;
+
+    /// A poster with 40 XP, in a server with the default limits.
+    fn setup() -> FileFormat {
+        let mut data = FileFormat::new();
+        data.player_list.push(Player::with_xp(POSTER, 40));
+        data
+    }
+
+    fn bounty(reward: i128, funding: Funding) -> Bounty {
+        Bounty::new(GUILD, POSTER, "Test".to_string(), reward, funding, 7)
+    }
+
+    #[test]
+    fn posting_holds_the_reward_from_the_poster() {
+        let mut data = setup();
+        let (_, player) = post(&mut data, bounty(30, Funding::Poster), DEFAULT_LOCALE).unwrap();
+        assert_eq!(player.unwrap().xp, 10);
+        assert_eq!(data.xp_of(POSTER), 10);
+        assert_eq!(data.bounties.len(), 1);
+    }
+
+    #[test]
+    fn rewards_over_the_servers_cap_are_refused() {
+        let mut data = setup();
+        data.player_mut(POSTER).unwrap().xp = 1000;
+        let max = data.guild_config(Some(GUILD)).limits.max_xp_per_achievement;
+        assert!(post(&mut data, bounty(max + 1, Funding::Poster), DEFAULT_LOCALE).is_err());
+        assert!(post(&mut data, bounty(max + 1, Funding::Minted), DEFAULT_LOCALE).is_err());
+        assert_eq!(data.xp_of(POSTER), 1000);
+        assert!(data.bounties.is_empty());
+    }
+
+    #[test]
+    fn negative_rewards_are_refused() {
+        let mut data = setup();
+        assert!(post(&mut data, bounty(-30, Funding::Poster), DEFAULT_LOCALE).is_err());
+        assert!(post(&mut data, bounty(0, Funding::Poster), DEFAULT_LOCALE).is_err());
+        assert_eq!(data.xp_of(POSTER), 40);
+        assert!(data.bounties.is_empty());
+    }
+
+    #[tokio::test]
+    async fn closing_gives_the_reward_back() {
+        let mut data = setup();
+        post(&mut data, bounty(30, Funding::Poster), DEFAULT_LOCALE).unwrap();
+        let (player, _) = close(&mut data, 0, State::Cancelled).await.unwrap();
+        assert_eq!(player.xp, 40);
+        assert_eq!(data.xp_of(POSTER), 40);
+        assert!(!data.bounties[0].is_open());
+    }
+
+    #[tokio::test]
+    async fn minted_bounties_give_nobody_anything_back() {
+        let mut data = setup();
+        post(&mut data, bounty(30, Funding::Minted), DEFAULT_LOCALE).unwrap();
+        assert_eq!(data.xp_of(POSTER), 40);
+        assert!(close(&mut data, 0, State::Expired).await.is_none());
+        assert_eq!(data.xp_of(POSTER), 40);
+    }
+}
@@ -0,0 +1,533 @@
+//! XP wagers between two players, settled with buttons.
+//!
+//! [`/challenge`](crate::commands::challenge) takes the stake from the
+//! challenger straight away, and holds it on the [`Challenge`]. The target
+//! can accept (which holds the same stake from them) or decline, with the
+//! buttons on the challenge's message. Stakes are [held](Player::hold_xp)
+//! from the XP in each player's current level, so they never cost a level.
+//!
+//! Once accepted, either both players press the same result button, or
+//! somebody with the server's [referee role](crate::guild_config::GuildConfig::referee_role)
+//! decides. The winner gets both stakes, and then
+//! [`lvl_check()`](Player::lvl_check) is run for both players. Wagered XP is
+//! moved as it is, without any [multipliers](Player::xp_change), and doesn't
+//! count towards anybody's [team](crate::teams).
+//!
+//! Challenges are saved in [`FileFormat::challenges`], and their buttons are
+//! handled by [`handle_event()`] rather than by the command that sent them,
+//! so they keep working after the bot restarts. A challenge that isn't
+//! accepted within [`ACCEPT_TIMEOUT_SECS`], or settled within
+//! [`RESULT_TIMEOUT_SECS`] of being accepted, expires, and every stake is
+//! given back.
+//!
+//! Which result each player has pressed isn't in the [`audit`] log, so a
+//! challenge rebuilt from the log needs its result pressed again.
+
+use crate::{ Data, Error, Serialize, Deserialize, serenity, audit, file_management, i18n::{self, tr}, player_data::Player, json_data::FileFormat };
+use chrono::{DateTime, TimeDelta, Utc};
+use std::sync::Arc;
+
+/// How long the target has to accept a challenge.
+pub const ACCEPT_TIMEOUT_SECS: i64 = 60 * 60;
+
+/// How long a challenge can go without a result once it's been accepted.
+pub const RESULT_TIMEOUT_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// The longest a challenge's description can be, in characters.
+pub const MAX_DESCRIPTION_LENGTH: usize = 200;
+
+/// How often to check for challenges that have expired.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Where a challenge is up to.
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+
+    /// Waiting for the target to accept. Only the challenger's stake is held.
+    Pending,
+
+    /// Both stakes are held, waiting for a result.
+    Accepted,
+
+    /// Somebody won, and got both stakes.
+    Won,
+
+    /// The target declined, and the challenger got their stake back.
+    Declined,
+
+    /// The challenger withdrew it, or the players or a referee called it off.
+    /// Every stake was given back.
+    CalledOff,
+
+    /// Nothing happened in time, so every stake was given back.
+    Expired,
+}
+
+/// A result pressed by one of the players, or a referee.
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Challenger,
+    Target,
+    CallOff,
+}
+
+/// A wager of XP between two players.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Challenge {
+    pub id: u64,
+
+    /// Where the challenge was sent, so its message can be found again.
+    pub guild_id: Option<u64>,
+    pub channel_id: u64,
+    pub message_id: u64,
+
+    pub challenger_id: u64,
+    pub target_id: u64,
+
+    /// The XP each player puts in. The winner gets twice this.
+    pub stake: i128,
+
+    /// What the challenge is, as typed by the challenger.
+    pub description: String,
+
+    /// The language the challenge's message is written in.
+    pub locale: String,
+
+    pub state: State,
+
+    /// The result each player has pressed, if any.
+    #[serde(default)]
+    pub challenger_verdict: Option<Verdict>,
+    #[serde(default)]
+    pub target_verdict: Option<Verdict>,
+
+    /// Who won, once the challenge is [won](State::Won).
+    #[serde(default)]
+    pub winner_id: Option<u64>,
+
+    /// The referee who decided the result, if the players didn't agree on one themselves.
+    #[serde(default)]
+    pub referee_id: Option<u64>,
+
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub accepted: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub finished: Option<DateTime<Utc>>,
+}
+
+impl Challenge {
+
+    /// Whether the challenge is still holding anybody's stake.
+    pub fn is_open(&self) -> bool {
+        matches!(self.state, State::Pending | State::Accepted)
+    }
+
+    /// What the winner gets.
+    pub fn pot(&self) -> i128 {
+        self.stake.saturating_mul(2)
+    }
+
+    /// When the challenge expires, if it's still open.
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        match self.state {
+            State::Pending => Some(self.created + TimeDelta::seconds(ACCEPT_TIMEOUT_SECS)),
+            State::Accepted => Some(self.accepted.unwrap_or(self.created) + TimeDelta::seconds(RESULT_TIMEOUT_SECS)),
+            _ => None,
+        }
+    }
+
+    /// Describes a verdict, like "<@123> won".
+    fn verdict_text(&self, verdict: Verdict) -> String {
+        let lang = self.locale.as_str();
+        match verdict {
+            Verdict::Challenger => tr!(lang, "challenge-verdict-won", user = format!("<@{}>", self.challenger_id)),
+            Verdict::Target => tr!(lang, "challenge-verdict-won", user = format!("<@{}>", self.target_id)),
+            Verdict::CallOff => tr!(lang, "challenge-verdict-call-off"),
+        }
+    }
+
+    /// Where the challenge is up to, for its message.
+    fn status(&self) -> String {
+        let lang = self.locale.as_str();
+        let expires = self.expires().map(|x| format!("<t:{}:R>", x.timestamp())).unwrap_or_default();
+        match self.state {
+            State::Pending => tr!(lang, "challenge-status-pending", user = format!("<@{}>", self.target_id), expires = expires),
+            State::Accepted => {
+                let mut lines = vec![tr!(lang, "challenge-status-accepted", expires = expires)];
+                for (user_id, verdict) in [(self.challenger_id, self.challenger_verdict), (self.target_id, self.target_verdict)] {
+                    if let Some(verdict) = verdict {
+                        lines.push(tr!(lang, "challenge-status-verdict", user = format!("<@{user_id}>"), verdict = self.verdict_text(verdict)));
+                    }
+                }
+                if self.challenger_verdict.zip(self.target_verdict).is_some_and(|(a, b)| a != b) {
+                    lines.push(tr!(lang, "challenge-status-disputed"));
+                }
+                lines.join("\n")
+            },
+            State::Won => {
+                let mut status = tr!(lang, "challenge-status-won", user = format!("<@{}>", self.winner_id.unwrap_or_default()),
+                                     pot = self.pot().to_string());
+                if let Some(referee_id) = self.referee_id {
+                    status = format!("{status}\n{}", tr!(lang, "challenge-status-referee", user = format!("<@{referee_id}>")));
+                }
+                status
+            },
+            State::Declined => tr!(lang, "challenge-status-declined", user = format!("<@{}>", self.target_id)),
+            State::CalledOff => tr!(lang, "challenge-status-called-off"),
+            State::Expired => tr!(lang, "challenge-status-expired"),
+        }
+    }
+
+    /// The embed shown on the challenge's message.
+    pub fn embed(&self, colour: u32) -> serenity::CreateEmbed {
+        let lang = self.locale.as_str();
+        serenity::CreateEmbed::new()
+            .title(tr!(lang, "challenge-title", id = self.id))
+            .colour(colour)
+            .description(self.description.clone())
+            .fields([
+                (tr!(lang, "field-challenger"), format!("<@{}>", self.challenger_id), true),
+                (tr!(lang, "field-opponent"), format!("<@{}>", self.target_id), true),
+                (tr!(lang, "field-stake"), tr!(lang, "challenge-stake", stake = self.stake.to_string(), pot = self.pot().to_string()), true),
+                (tr!(lang, "field-status"), self.status(), false),
+            ])
+    }
+
+    /// The buttons shown on the challenge's message, which depend on where it's up to.
+    pub fn components(&self) -> Vec<serenity::CreateActionRow> {
+        let lang = self.locale.as_str();
+        let button = |action: &str, label: String, style: serenity::ButtonStyle| {
+            serenity::CreateButton::new(format!("challenge.{action}.{}", self.id)).label(label).style(style)
+        };
+        match self.state {
+            State::Pending => vec![serenity::CreateActionRow::Buttons(vec![
+                button("accept", tr!(lang, "challenge-button-accept"), serenity::ButtonStyle::Primary),
+                button("decline", tr!(lang, "challenge-button-decline"), serenity::ButtonStyle::Secondary),
+            ])],
+            State::Accepted => vec![serenity::CreateActionRow::Buttons(vec![
+                button("challenger", tr!(lang, "challenge-button-challenger"), serenity::ButtonStyle::Primary),
+                button("target", tr!(lang, "challenge-button-target"), serenity::ButtonStyle::Primary),
+                button("off", tr!(lang, "challenge-button-call-off"), serenity::ButtonStyle::Secondary),
+            ])],
+            _ => vec![],
+        }
+    }
+}
+
+/// Ends a challenge, paying out or giving back the stakes it held.
+///
+/// Runs [`lvl_check()`](Player::lvl_check) for everybody involved. Returns
+/// each of them afterwards, with the level they were at before.
+pub async fn finish(data: &mut FileFormat, index: usize, state: State, winner_id: Option<u64>) -> Vec<(Player, i64)> {
+    let challenge = &mut data.challenges[index];
+    let was_accepted = challenge.state == State::Accepted;
+    challenge.state = state;
+    challenge.winner_id = winner_id;
+    challenge.finished = Some(Utc::now());
+    tracing::info!(challenge_id = challenge.id, ?state, ?winner_id, "Challenge finished");
+
+    let (challenger_id, target_id, stake, pot) = (challenge.challenger_id, challenge.target_id, challenge.stake, challenge.pot());
+    let payouts = match winner_id {
+        Some(winner_id) if state == State::Won => {
+            let loser_id = if winner_id == challenger_id { target_id } else { challenger_id };
+            vec![(winner_id, pot), (loser_id, 0)]
+        },
+        _ if was_accepted => vec![(challenger_id, stake), (target_id, stake)],
+        _ => vec![(challenger_id, stake)],
+    };
+
+    let mut players = vec![];
+    for (user_id, xp) in payouts {
+        let Some(p) = data.player_mut(user_id) else {
+            tracing::warn!(user_id, xp = %xp, "Player from a challenge no longer exists, so their XP was lost");
+            continue
+        };
+        let old_lvl = p.lvl;
+        p.xp += xp;
+        p.lvl_check(None).await;
+        players.push((p.clone(), old_lvl));
+    }
+    players
+}
+
+/// Lines saying who went up or down a level, for the challenge's message.
+fn level_lines(players: &[(Player, i64)], lang: &str) -> Vec<String> {
+    players.iter()
+        .filter(|(p, old_lvl)| p.lvl != *old_lvl)
+        .map(|(p, _)| tr!(lang, "challenge-new-level", user = format!("<@{}>", p.user_id), lvl = p.lvl))
+        .collect()
+}
+
+/// Records a change to a challenge in the [`audit`] log, along with any levels it changed.
+pub async fn record(http: &serenity::Http, audit_channel: Option<serenity::ChannelId>, challenge: &Challenge, players: Vec<(Player, i64)>) {
+    for (p, old_lvl) in &players {
+        if p.lvl != *old_lvl {
+            audit::record_http(http, audit_channel, audit::Event::LevelChanged {
+                user_id: p.user_id,
+                old_lvl: *old_lvl,
+                new_lvl: p.lvl,
+                player: Some(p.clone()),
+            }).await;
+        }
+    }
+    audit::record_http(http, audit_channel, audit::Event::ChallengeChanged {
+        challenge: challenge.clone(),
+        players: players.into_iter().map(|(p, _)| p).collect(),
+    }).await;
+}
+
+/// Replies to a button press, once it's been deferred, with a message only the presser can see.
+async fn reply(ctx: &serenity::Context, interaction: &serenity::ComponentInteraction, content: String) -> Result<(), Error> {
+    interaction.create_followup(ctx, serenity::CreateInteractionResponseFollowup::new()
+        .content(content)
+        .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Handles the buttons on every challenge's message, including ones sent
+/// before the bot restarted. Every other event is ignored.
+///
+/// Used as Poise's `event_handler`.
+pub async fn handle_event(ctx: &serenity::Context, event: &serenity::FullEvent, data: &Data) -> Result<(), Error> {
+    let serenity::FullEvent::InteractionCreate { interaction: serenity::Interaction::Component(interaction) } = event else {
+        return Ok(())
+    };
+    let Some(button) = interaction.data.custom_id.strip_prefix("challenge.") else {
+        return Ok(())
+    };
+    let Some((action, id)) = button.split_once('.').and_then(|(action, id)| Some((action, id.parse::<u64>().ok()?))) else {
+        return Err(Error::UnknownButton(interaction.data.custom_id.clone()))
+    };
+
+    let user_id = interaction.user.id.get();
+    let lang = i18n::resolve(data, user_id, interaction.guild_id, Some(&interaction.locale));
+    tracing::info!(challenge_id = id, user_id, action, "Challenge button pressed");
+
+    // Buttons don't go through Poise's checks, so the feature is checked here.
+    if !data.guild_config(interaction.guild_id).features.levels {
+        interaction.create_response(ctx, serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(Error::FeatureDisabled.user_message(lang))
+                .ephemeral(true))).await?;
+        return Ok(())
+    }
+    // Discord only waits 3 seconds for a response, and waiting for the file,
+    // saving and recording can take longer than that.
+    interaction.defer(ctx).await?;
+
+    let lock = data.lock_file().await;
+    let mut file = file_management::load()?;
+    let Some(index) = file.challenges.iter().position(|x| x.id == id) else {
+        return reply(ctx, interaction, tr!(lang, "challenge-unknown")).await
+    };
+    let challenge = file.challenges[index].clone();
+    if !challenge.is_open() {
+        return reply(ctx, interaction, tr!(lang, "challenge-closed")).await
+    }
+    let config = file.guild_config(challenge.guild_id);
+    let is_challenger = user_id == challenge.challenger_id;
+    let is_target = user_id == challenge.target_id;
+
+    let players = match (action, challenge.state) {
+        ("accept", State::Pending) => {
+            if !is_target {
+                return reply(ctx, interaction, tr!(lang, "challenge-not-yours")).await
+            }
+            let Some(p) = file.player_mut(user_id) else {
+                return Err(Error::PlayerNotFound(user_id))
+            };
+            if let Err(message) = p.hold_xp(challenge.stake, lang) {
+                return reply(ctx, interaction, message).await
+            }
+            let player = p.clone();
+            let c = &mut file.challenges[index];
+            c.state = State::Accepted;
+            c.accepted = Some(Utc::now());
+            tracing::info!(challenge_id = id, "Challenge accepted");
+            vec![(player.clone(), player.lvl)]
+        },
+        ("decline", State::Pending) => match (is_challenger, is_target) {
+            (true, _) => finish(&mut file, index, State::CalledOff, None).await,
+            (_, true) => finish(&mut file, index, State::Declined, None).await,
+            _ => return reply(ctx, interaction, tr!(lang, "challenge-not-yours")).await,
+        },
+        ("challenger" | "target" | "off", State::Accepted) => {
+            let verdict = match action {
+                "challenger" => Verdict::Challenger,
+                "target" => Verdict::Target,
+                _ => Verdict::CallOff,
+            };
+            let is_referee = config.referee_role.is_some_and(|role| interaction.member.as_ref()
+                .is_some_and(|x| x.roles.contains(&serenity::RoleId::new(role))));
+
+            let c = &mut file.challenges[index];
+            let agreed = if is_challenger || is_target {
+                if is_challenger {
+                    c.challenger_verdict = Some(verdict);
+                } else {
+                    c.target_verdict = Some(verdict);
+                }
+                c.challenger_verdict.filter(|x| c.target_verdict == Some(*x))
+            } else if is_referee {
+                c.referee_id = Some(user_id);
+                Some(verdict)
+            } else {
+                return reply(ctx, interaction, tr!(lang, "challenge-not-yours")).await
+            };
+
+            match agreed {
+                None => vec![],
+                Some(Verdict::Challenger) => finish(&mut file, index, State::Won, Some(challenge.challenger_id)).await,
+                Some(Verdict::Target) => finish(&mut file, index, State::Won, Some(challenge.target_id)).await,
+                Some(Verdict::CallOff) => finish(&mut file, index, State::CalledOff, None).await,
+            }
+        },
+        ("accept" | "decline", _) => return reply(ctx, interaction, tr!(lang, "challenge-already-accepted")).await,
+        ("challenger" | "target" | "off", _) => return reply(ctx, interaction, tr!(lang, "challenge-not-accepted")).await,
+        _ => return Err(Error::UnknownButton(interaction.data.custom_id.clone())),
+    };
+
+    file_management::save(&file)?;
+    drop(lock);
+
+    let challenge = file.challenges[index].clone();
+    let levels = level_lines(&players, &challenge.locale);
+    // Votes don't change anybody's data, so only the rest is recorded.
+    if !players.is_empty() {
+        record(&ctx.http, data.audit_channel, &challenge, players).await;
+    }
+
+    let mut embed = challenge.embed(config.colours.achievement);
+    if !levels.is_empty() {
+        embed = embed.field(tr!(&challenge.locale, "field-levels"), levels.join("\n"), false);
+    }
+    interaction.edit_response(ctx, serenity::EditInteractionResponse::new()
+        .embed(embed)
+        .components(challenge.components())).await?;
+    Ok(())
+}
+
+/// Expires every challenge that's run out of time, giving back the stakes it held.
+///
+/// Everything is saved before anything is recorded or any message is edited.
+async fn expire_due(http: &serenity::Http, audit_channel: Option<serenity::ChannelId>, lock: &file_management::Lock) -> Result<(), Error> {
+    let guard = lock.lock().await;
+    let mut data = file_management::load()?;
+    let now = Utc::now();
+    let due = data.challenges.iter()
+        .enumerate()
+        .filter(|(_, x)| x.expires().is_some_and(|x| x <= now))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if due.is_empty() {
+        return Ok(())
+    }
+
+    let mut expired = vec![];
+    for index in due {
+        let players = finish(&mut data, index, State::Expired, None).await;
+        expired.push((data.challenges[index].clone(), players));
+    }
+    file_management::save(&data)?;
+    drop(guard);
+
+    for (challenge, players) in expired {
+        record(http, audit_channel, &challenge, players).await;
+        // It's 0 if the message was never sent.
+        if challenge.message_id == 0 {
+            continue
+        }
+
+        let colour = data.guild_config(challenge.guild_id).colours.achievement;
+        let message = serenity::EditMessage::new()
+            .embed(challenge.embed(colour))
+            .components(vec![]);
+        // The stakes are given back either way, so a message that's been deleted doesn't matter.
+        if let Err(e) = serenity::ChannelId::new(challenge.channel_id)
+            .edit_message(http, serenity::MessageId::new(challenge.message_id), message).await {
+            tracing::warn!(challenge_id = challenge.id, error = %e, "Failed to update expired challenge");
+        }
+    }
+    Ok(())
+}
+
+/// Starts expiring challenges in the background, forever.
+pub fn spawn_expirer(http: Arc<serenity::Http>, audit_channel: Option<serenity::ChannelId>, lock: file_management::Lock) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = expire_due(&http, audit_channel, &lock).await {
+                tracing::error!(error = %e, "Failed to expire challenges");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHALLENGER: u64 = 1;
+    const TARGET: u64 = 2;
+    const STAKE: i128 = 10;
+
+    /// Two players with 20 XP each (not enough to level up from winning), and a challenge between them in `state`,
+    /// with the stakes it would be holding already taken off them.
+    fn setup(state: State) -> FileFormat {
+        let mut data = FileFormat::new();
+        data.player_list.extend([CHALLENGER, TARGET].map(|id| Player::with_xp(id, 20)));
+        data.player_mut(CHALLENGER).unwrap().hold_xp(STAKE, i18n::DEFAULT_LOCALE).unwrap();
+        if state == State::Accepted {
+            data.player_mut(TARGET).unwrap().hold_xp(STAKE, i18n::DEFAULT_LOCALE).unwrap();
+        }
+        data.challenges.push(Challenge {
+            id: 1,
+            guild_id: None,
+            channel_id: 1,
+            message_id: 1,
+            challenger_id: CHALLENGER,
+            target_id: TARGET,
+            stake: STAKE,
+            description: "Test".to_string(),
+            locale: i18n::DEFAULT_LOCALE.to_string(),
+            state,
+            challenger_verdict: None,
+            target_verdict: None,
+            winner_id: None,
+            referee_id: None,
+            created: Utc::now(),
+            accepted: None,
+            finished: None,
+        });
+        data
+    }
+
+    #[tokio::test]
+    async fn winner_gets_both_stakes() {
+        let mut data = setup(State::Accepted);
+        finish(&mut data, 0, State::Won, Some(TARGET)).await;
+        assert_eq!(data.xp_of(CHALLENGER), 10);
+        assert_eq!(data.xp_of(TARGET), 30);
+        assert!(!data.challenges[0].is_open());
+    }
+
+    #[tokio::test]
+    async fn calling_off_gives_both_stakes_back() {
+        let mut data = setup(State::Accepted);
+        finish(&mut data, 0, State::CalledOff, None).await;
+        assert_eq!(data.xp_of(CHALLENGER), 20);
+        assert_eq!(data.xp_of(TARGET), 20);
+    }
+
+    #[tokio::test]
+    async fn declining_gives_only_the_challenger_back_their_stake() {
+        let mut data = setup(State::Pending);
+        finish(&mut data, 0, State::Declined, None).await;
+        assert_eq!(data.xp_of(CHALLENGER), 20);
+        assert_eq!(data.xp_of(TARGET), 20);
+    }
+}
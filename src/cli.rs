@@ -0,0 +1,196 @@
+//! Commands that can be run from the terminal, instead of starting the bot.
+//!
+//! ```text
+//! cargo run -- <command> [arguments]
+//! ```
+
+use crate::{ audit, file_management, migrate, site, transfer, json_data::FileFormat };
+use poise::ChoiceParameter;
+use std::path::Path;
+
+const HELP: &str = "Available commands:
+  rebuild-from-audit    Rebuild saved_data.json from the audit log
+  export-site [folder]  Export a static HTML leaderboard (default folder: site)
+  export-players <csv|json> [file]
+                        Export every player (default file: players.csv or players.json)
+  import-players <file> [overwrite|merge-max|skip] [--apply]
+                        Import players from a .csv or .json file (default: merge-max).
+                        Only shows what would change, unless --apply is given
+  migrate-players <file> [overwrite|merge-max|skip] [--apply]
+                        Bring players over from another leveling bot's .csv or .json
+                        leaderboard (default: skip). Only shows a preview, unless --apply is given";
+
+/// Runs a single command, given the arguments after the program name.
+pub async fn run(args: &[String]) {
+    match args[0].as_str() {
+        "rebuild-from-audit" => rebuild_from_audit(),
+        "export-site" => {
+            let dir = args.get(1).map(String::as_str).unwrap_or("site");
+            match site::export(Path::new(dir)) {
+                Ok(n) => println!("Exported {n} players to {dir}/index.html"),
+                Err(e) => println!("Failed to export site: {e}"),
+            }
+        },
+        "export-players" => export_players(&args[1..]),
+        "import-players" => import_players(&args[1..]),
+        "migrate-players" => migrate_players(&args[1..]).await,
+        "help" | "--help" | "-h" => println!("{HELP}"),
+        other => println!("Unknown command: {other}\n{HELP}"),
+    }
+}
+
+/// Rebuilds the save file from the audit log, backing up the old one first.
+fn rebuild_from_audit() {
+    let entries = audit::load();
+    let data = audit::replay(&entries);
+    if std::fs::exists(file_management::FILENAME).unwrap_or(false) {
+        let backup = format!("{}.bak", file_management::FILENAME);
+        if let Err(e) = std::fs::copy(file_management::FILENAME, &backup) {
+            println!("Failed to back up existing save file, so leaving it alone: {e}");
+            return
+        }
+        println!("Backed up existing save file to {backup}");
+    }
+    match file_management::save(&data) {
+        Ok(()) => println!("Rebuilt {} players and {} achievements from {} audit events.",
+                           data.player_list.len(), data.achievements.len(), entries.len()),
+        Err(e) => println!("Failed to save rebuilt data: {e}"),
+    }
+}
+
+/// Exports every player to a CSV or JSON file.
+fn export_players(args: &[String]) {
+    let Some(format) = args.first().and_then(|x| transfer::Format::from_name(x)) else {
+        println!("Usage: export-players <csv|json> [file]");
+        return
+    };
+    let file = args.get(1).cloned().unwrap_or_else(|| format!("players.{}", format.extension()));
+
+    let result = file_management::load_players()
+        .map_err(|e| e.to_string())
+        .and_then(|players| Ok((transfer::export(&players, format)?, players.len())))
+        .and_then(|(content, n)| std::fs::write(&file, content).map(|_| n).map_err(|e| e.to_string()));
+    match result {
+        Ok(n) => println!("Exported {n} players to {file}"),
+        Err(e) => println!("Failed to export players: {e}"),
+    }
+}
+
+/// The arguments shared by `import-players` and `migrate-players`.
+struct ImportArgs {
+    file: String,
+    format: transfer::Format,
+    strategy: transfer::Strategy,
+    apply: bool,
+}
+
+impl ImportArgs {
+
+    /// Parses `<file> [strategy] [--apply]`, printing why if they're wrong.
+    fn parse(args: &[String], usage: &str, default_strategy: transfer::Strategy) -> Option<ImportArgs> {
+        let apply = args.iter().any(|x| x == "--apply");
+        let args = args.iter().filter(|x| *x != "--apply").collect::<Vec<_>>();
+
+        let Some(file) = args.first() else {
+            println!("{usage}");
+            return None
+        };
+        let Some(format) = transfer::Format::from_filename(file) else {
+            println!("The file needs to end in .csv or .json");
+            return None
+        };
+        let strategy = match args.get(1) {
+            None => default_strategy,
+            Some(x) => match transfer::Strategy::from_name(x) {
+                Some(strategy) => strategy,
+                None => {
+                    println!("Unknown strategy: {x}\n{usage}");
+                    return None
+                },
+            },
+        };
+        Some(ImportArgs { file: file.to_string(), format, strategy, apply })
+    }
+}
+
+/// Saves planned changes, and records each one in the audit log.
+fn apply_changes(mut data: FileFormat, changes: &[transfer::Change], reason: &str) {
+    let applied = transfer::apply(&mut data, changes, 0, reason);
+    if let Err(e) = file_management::save(&data) {
+        println!("Failed to save changes: {e}");
+        return
+    }
+    for (action, player) in applied {
+        audit::record_offline(audit::Event::AdminOverride { action, player: Some(player) });
+    }
+    println!("Done: {}", transfer::summarise(changes));
+}
+
+/// Imports players from a CSV or JSON file, only applying the changes if `--apply` is given.
+fn import_players(args: &[String]) {
+    let usage = "Usage: import-players <file> [overwrite|merge-max|skip] [--apply]";
+    let Some(args) = ImportArgs::parse(args, usage, transfer::Strategy::MergeMax) else {
+        return
+    };
+
+    let rows = match std::fs::read_to_string(&args.file).map_err(|e| e.to_string()).and_then(|x| transfer::parse(&x, args.format)) {
+        Ok(rows) => rows,
+        Err(e) => {
+            println!("Nothing was imported. {e}");
+            return
+        },
+    };
+    let data = match file_management::load() {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Failed to load save file: {e}");
+            return
+        },
+    };
+
+    let changes = transfer::plan(&data.player_list, &rows, args.strategy);
+    for change in &changes {
+        println!("{}", change.describe());
+    }
+    if !args.apply {
+        println!("Dry run, so nothing was changed. Importing would give: {}\nRun again with --apply to import.",
+                 transfer::summarise(&changes));
+        return
+    }
+    apply_changes(data, &changes, &format!("imported from {} in the terminal", args.file));
+}
+
+/// Brings players over from another bot's leaderboard, only applying the changes if `--apply` is given.
+async fn migrate_players(args: &[String]) {
+    let usage = "Usage: migrate-players <file> [overwrite|merge-max|skip] [--apply]";
+    let Some(args) = ImportArgs::parse(args, usage, transfer::Strategy::Skip) else {
+        return
+    };
+
+    let old = match std::fs::read_to_string(&args.file).map_err(|e| e.to_string()).and_then(|x| migrate::parse(&x, args.format)) {
+        Ok(old) => old,
+        Err(e) => {
+            println!("Nothing was imported. {e}");
+            return
+        },
+    };
+    let data = match file_management::load() {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Failed to load save file: {e}");
+            return
+        },
+    };
+
+    let previews = migrate::plan(&data.player_list, old, args.strategy).await;
+    for preview in &previews {
+        println!("{}", preview.describe());
+    }
+    let changes = previews.into_iter().map(|x| x.change).collect::<Vec<_>>();
+    if !args.apply {
+        println!("Dry run, so nothing was changed. Migrating would give: {}\nRun again with --apply to migrate.",
+                 transfer::summarise(&changes));
+        return
+    }
+    apply_changes(data, &changes, &format!("migrated from {} in the terminal", args.file));
+}
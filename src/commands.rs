@@ -3,7 +3,94 @@
 //! back here. That'll allow you to document it separately.
 
 
-use crate::{ Context, Error, player_data, cmp, file_management, serenity, functions };
+use crate::{ Context, Error, player_data, achievement_data, json_data, file_management, serenity, audit, config, titles, perks, shop, teams, challenges, bounties, i18n::{self, tr}, metrics::METRICS };
+use unicode_segmentation::UnicodeSegmentation;
+
+pub mod admin;
+
+/// The base XP used to show how much faster XP comes in after prestiging.
+const PRESTIGE_PREVIEW_XP: i128 = 100;
+
+/// Extra levels to show in the "if you wait" part of a prestige preview.
+const PRESTIGE_PREVIEW_WAITS: [i64; 4] = [5, 10, 25, 50];
+
+/// An embed comparing a player now with how they'd be after prestiging.
+///
+/// Shows their prestige before and after, the next prestige threshold, the XP
+/// needed per level, their new title, and how much more XP achievements would
+/// give, worked out with [`xp_change()`](player_data::Player::xp_change).
+/// Also shows what waiting a few more levels would do to their
+/// [`prestige_points()`](player_data::Player::prestige_points).
+///
+/// If the player can't prestige yet, everything is shown as if they'd just
+/// reached their prestige threshold.
+fn prestige_embed(ctx: Context<'_>, p: &player_data::Player, title: Option<&str>) -> serenity::CreateEmbed {
+    let lang = i18n::locale(ctx);
+    let mut now = p.clone();
+    now.lvl = p.lvl.max(p.prestige_threshold);
+    let after = now.prestiged(Some(title.map_or_else(|| tr!(lang, "prestige-new-word"), str::to_string)));
+
+    let old_xp = p.xp_change(PRESTIGE_PREVIEW_XP);
+    let new_xp = after.xp_change(PRESTIGE_PREVIEW_XP);
+    let percent = if old_xp > 0 { (new_xp as f64 / old_xp as f64 - 1.0) * 100.0 } else { 0.0 };
+
+    let waiting = PRESTIGE_PREVIEW_WAITS.iter().map(|extra| {
+        let mut later = now.clone();
+        later.lvl += extra;
+        tr!(lang, "prestige-wait",
+            lvl = later.lvl,
+            points = format!("{:.2}", later.prestige_points()),
+            prestige = format!("{:.2}", later.prestiged(None).prestige))
+    }).collect::<Vec<_>>();
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(tr!(lang, "prestige-preview-title"))
+        .colour(ctx.data().guild_config(ctx.guild_id()).colours.prestige)
+        .fields([
+            (tr!(lang, "field-prestige"),
+             tr!(lang, "prestige-change",
+                 old = format!("{:.2}", p.prestige),
+                 new = format!("{:.2}", after.prestige),
+                 points = format!("{:.2}", now.prestige_points())), false),
+            (tr!(lang, "field-next-prestige"),
+             tr!(lang, "prestige-threshold-change", old = p.prestige_threshold, new = after.prestige_threshold), true),
+            (tr!(lang, "field-xp-per-level"),
+             tr!(lang, "prestige-xp-threshold-change", old = p.xp_threshold().to_string(), new = after.xp_threshold().to_string()), true),
+            (tr!(lang, "field-perk-points"),
+             tr!(lang, "prestige-perk-points-change", old = p.perk_points, new = after.perk_points), true),
+            (tr!(lang, "field-title"), after.title().trim_end().to_string(), false),
+            (tr!(lang, "field-xp-rate"),
+             tr!(lang, "prestige-xp-rate",
+                 base = PRESTIGE_PREVIEW_XP.to_string(),
+                 old = old_xp.to_string(),
+                 new = new_xp.to_string(),
+                 percent = format!("{percent:.0}")), false),
+            (tr!(lang, "field-prestige-waiting"), waiting.join("\n"), false),
+        ]);
+    if p.lvl < p.prestige_threshold {
+        embed = embed.description(tr!(lang, "prestige-preview-locked", lvl = p.prestige_threshold));
+    }
+    embed
+}
+
+/// See what prestiging would do, without doing it.
+///
+/// Shows the same [embed](prestige_embed) as the confirmation for
+/// [`prestige`], and works even if you can't prestige yet.
+pub async fn prestige_preview(
+    ctx: Context<'_>,
+    title: Option<String>,
+) -> Result<(), Error> {
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let players = file_management::load_players()?;
+    let p = players.iter().find(|x| x.user_id == ctx.author().id.get()).ok_or(Error::PlayerNotFound(ctx.author().id.get()))?;
+
+    ctx.send(poise::CreateReply::default()
+             .embed(prestige_embed(ctx, p, title.as_deref()))
+             .ephemeral(true)).await?;
+    Ok(())
+}
 
 /// Reset your progress, with an advantage.
 ///
@@ -13,7 +100,8 @@ use crate::{ Context, Error, player_data, cmp, file_management, serenity, functi
 /// cmp::max(prestige * player_data::PRESTIGE_THRESHOLD, player_data::PRESTIGE_MINIMUM)
 /// ```
 ///
-/// IF you have a high enough level, it asks for confirmation, with a
+/// IF you have a high enough level, it asks for confirmation, showing
+/// the [projected outcome](prestige_embed), with a
 /// confirm and deny button.
 /// If you deny, it deletes the message.
 ///
@@ -37,129 +125,130 @@ pub async fn prestige(
     ctx: Context<'_>,
     title: String,
 ) -> Result<(),Error> {
+    let lang = i18n::locale(ctx);
+    let author_id = ctx.author().id.get();
 
-
-    player_data::verify_player(ctx, Some(ctx.author().id.get()));
-
-    let mut players = file_management::load_players();
-    // Additional scope so that players can be edited. It will be saved upon the closing of the scope
-    {
-        let p: &mut player_data::Player = players.iter_mut().find(|x| x.user_id == ctx.author().id.get()).expect("User not present in Players despite verification");
-
-        if title.len() > 10 {
-            ctx.send(poise::CreateReply::default()
-                    .content("Your new title cannot be more than 10 characters long.")
-                    .ephemeral(true)
-            ).await?;
-            return Ok(())
-        } else if title.split(" ").collect::<Vec<_>>().len() > 1 {
+    let title = match titles::check(&title, &ctx.data().guild_config(ctx.guild_id()), lang) {
+        Ok(title) => title,
+        Err(message) => {
             ctx.send(poise::CreateReply::default()
-                    .content("Your new title can only be one word long.")
+                    .content(message)
                     .ephemeral(true)
             ).await?;
             return Ok(())
+        },
+    };
 
-        }
-        //ctx.say(format!("{:.1}",(p.lvl as f64 / p.prestige_threshold() as f64))).await.expect("Unknown error");
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    let p = file_management::load_players()?.into_iter().find(|x| x.user_id == author_id).ok_or(Error::PlayerNotFound(author_id))?;
+    // Not held while waiting for the buttons, which could be forever.
+    drop(lock);
 
-        let prestige_points = p.prestige_points();
-        if p.lvl < p.prestige_threshold {
-            ctx.send(poise::CreateReply::default()
-                    .content(format!("You need to be at least level {} to Prestige{}.",
-                                    p.prestige_threshold,
-                                    if p.prestige == 1.0 {
-                                        " for the first time"
-                                    } else { "" }
-                    ))
-                .ephemeral(true)).await?;
-            return Ok(())
-        }
+    if p.lvl < p.prestige_threshold {
+        ctx.send(poise::CreateReply::default()
+                .content(tr!(lang, "prestige-level-too-low",
+                             lvl = p.prestige_threshold,
+                             first_time = (p.prestige == 1.0).to_string()))
+            .ephemeral(true)).await?;
+        return Ok(())
+    }
 
-        let components = serenity::CreateActionRow::Buttons(vec![
-            serenity::CreateButton::new("prestige.accept")
-                .label("Prestige")
-                .style(serenity::ButtonStyle::Danger),
-            serenity::CreateButton::new("prestige.decline")
-                .label("Cancel Prestige")
-                .style(serenity::ButtonStyle::Primary),
-        ]);
+    let components = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new("prestige.accept")
+            .label(tr!(lang, "prestige-button-accept"))
+            .style(serenity::ButtonStyle::Danger),
+        serenity::CreateButton::new("prestige.decline")
+            .label(tr!(lang, "prestige-button-cancel"))
+            .style(serenity::ButtonStyle::Primary),
+    ]);
 
-        let builder = poise::CreateReply::default()
-            .content("test")
-            .components(vec![components]);
-
-        let reply = ctx.send(builder).await?;
-
-        let interaction = reply
-            .message()
-            .await?
-            .await_component_interaction(ctx)
-            .author_id(ctx.author().id)
-            .await;
-
-        reply
-            .edit(
-                ctx,
-                poise::CreateReply::default()
-                    .components(vec![])
-                    .content("Processing..."),
-                ).await?;
-
-        let pressed_button_id = match &interaction {
-            Some(m) => &m.data.custom_id,
-            None => {
-                ctx.say(":warning: You didn't react in time, sorry!").await?;
-                return Ok(())
-            }
-        };
+    let builder = poise::CreateReply::default()
+        .content(tr!(lang, "prestige-confirm"))
+        .embed(prestige_embed(ctx, &p, Some(&title)))
+        .components(vec![components]);
 
-        let acceptance = match &**pressed_button_id {
-            "prestige.accept" => true,
-            "prestige.decline" => false,
-            other => {
-                panic!("Unknown register button ID: {:?}",other);
-            }
-        };
+    let reply = ctx.send(builder).await?;
 
-        if acceptance {
-            let first_time = p.prestige == 1.0;
-            p.prestige = match functions::overflow_check(||p.prestige * prestige_points) {
-                functions::Overflows::Float | functions::Overflows::Panic => f64::MAX,
-                functions::Overflows::Safe => p.prestige * prestige_points,
-            };
-            if p.prestige == f64::MAX {
-                reply.edit(ctx, poise::CreateReply::default()
-                        .content(format!("Congratulations! {} has won the Achievements Game! It is literally impossible for your prestige to get any higher!",
-                                            ctx.author().display_name(),
-                ))).await?;
-            }
-            p.prestige_threshold = p.lvl;
-            p.lvl = 1;
-            p.xp = 0;
-            p.title_segments.push(title);
-
-            reply.edit(ctx, poise::CreateReply::default()
-                    .content(format!("{} has Prestiged{}, and now has {:.2} Prestige Points!",
-                                        ctx.author().display_name(),
-                                        if first_time { " for the first time" } else { "" },
-                                        p.prestige,
-            ))).await?;
+    let interaction = reply
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(ctx.author().id)
+        .await;
 
-        } else {
-            reply.delete(ctx).await?;
-            ctx.send(poise::CreateReply::default()
-                    .content("Cancelled :)")
-                    .ephemeral(true)).await?;
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .components(vec![])
+                .content(tr!(lang, "processing")),
+            ).await?;
 
+    let pressed_button_id = match &interaction {
+        Some(m) => &m.data.custom_id,
+        None => {
+            ctx.say(tr!(lang, "timed-out")).await?;
+            return Ok(())
         }
+    };
+
+    let acceptance = match &**pressed_button_id {
+        "prestige.accept" => true,
+        "prestige.decline" => false,
+        other => return Err(Error::UnknownButton(other.to_string())),
+    };
+
+    if !acceptance {
+        reply.delete(ctx).await?;
+        ctx.send(poise::CreateReply::default()
+                .content(tr!(lang, "cancelled"))
+                .ephemeral(true)).await?;
+        return Ok(())
+    }
+
+    // Reloaded, since anything could have changed while waiting for the buttons.
+    let lock = ctx.data().lock_file().await;
+    let mut players = file_management::load_players()?;
+    let p = players.iter_mut().find(|x| x.user_id == author_id).ok_or(Error::PlayerNotFound(author_id))?;
+    if p.lvl < p.prestige_threshold {
+        reply.edit(ctx, poise::CreateReply::default()
+                .content(tr!(lang, "prestige-level-too-low",
+                             lvl = p.prestige_threshold,
+                             first_time = (p.prestige == 1.0).to_string()))).await?;
+        return Ok(())
+    }
+    let first_time = p.prestige == 1.0;
+    let old_prestige = p.prestige;
+    *p = p.prestiged(Some(title.clone()));
+    let p = p.clone();
+    file_management::save_players(&players)?;
+    drop(lock);
+
+    if p.prestige == f64::MAX {
+        reply.edit(ctx, poise::CreateReply::default()
+                .content(tr!(lang, "prestige-max", name = ctx.author().display_name()))).await?;
     }
+    METRICS.prestiges.inc();
+
+    reply.edit(ctx, poise::CreateReply::default()
+            .content(tr!(lang, "prestige-done",
+                         name = ctx.author().display_name(),
+                         first_time = first_time.to_string(),
+                         prestige = format!("{:.2}", p.prestige)))).await?;
 
-    file_management::save_players(&players);
+    config::announce(ctx, serenity::CreateEmbed::new()
+                     .title(tr!(lang, "announce-prestige-title"))
+                     .colour(ctx.data().guild_config(ctx.guild_id()).colours.prestige)
+                     .description(tr!(lang, "announce-prestige",
+                                      user = format!("<@{}>", p.user_id),
+                                      prestige = format!("{:.2}", p.prestige),
+                                      title = p.title().trim_end().to_string()))).await;
+    audit::record(ctx, audit::Event::Prestiged { old_prestige, title, player: p }).await;
 
     Ok(())
 }
 
-
 /// Check your current XP, Level and Prestige.
 pub async fn level(
     ctx: Context<'_>,
@@ -169,26 +258,32 @@ pub async fn level(
 
     let current_id = u.id.get();
 
-    player_data::verify_player(ctx, Some(current_id));
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(current_id))?;
 
-    let mut players = file_management::load_players();
+    let mut players = file_management::load_players()?;
 
-    let p: &mut player_data::Player = players.iter_mut().find(|x| x.user_id == current_id).expect("User not present in Players despite verification");
+    let p: &mut player_data::Player = players.iter_mut().find(|x| x.user_id == current_id).ok_or(Error::PlayerNotFound(current_id))?;
+    let lang = i18n::locale(ctx);
 
     ctx.send(poise::CreateReply::default()
         .embed(serenity::CreateEmbed::new()
-               .title(format!("User Data"))
+               .title(tr!(lang, "level-title"))
+               .description(p.badges())
+               .colour(p.accent_colour().unwrap_or(ctx.data().guild_config(ctx.guild_id()).colours.level))
                .author(
-                    serenity::CreateEmbedAuthor::new(format!("Lv. {} {} {}", p.lvl, p.title(), u.display_name()))
+                    serenity::CreateEmbedAuthor::new(p.name_line(u.display_name()))
                         .icon_url(u.static_avatar_url().unwrap_or_else(|| u.default_avatar_url())))
                .fields([
-                   ("Level", p.lvl.to_string(), true),
+                   (tr!(lang, "field-level"), p.lvl.to_string(), true),
                    if p.prestige > 1.0 {
-                       ("Prestige", format!("{:.2}",p.prestige), true)
+                       (tr!(lang, "field-prestige"), format!("{:.2}",p.prestige), true)
                    } else {
-                       ("","".to_string(),true)
+                       (String::new(),"".to_string(),true)
                    },
-                   ("XP", format!("{} _({} / {})_",p.xp_bar(), p.xp, p.xp_threshold()), false)
+                   (tr!(lang, "field-coins"), tr!(lang, "coins", coins = p.coins.to_string()), true),
+                   (tr!(lang, "field-medals"), p.medals.to_string(), true),
+                   (tr!(lang, "field-xp"), format!("{} _{}_",p.xp_bar(), p.xp_progress()), false)
                ])
             )).await?;
 
@@ -197,104 +292,1411 @@ pub async fn level(
     Ok(())
 }
 
+/// Grants an achievement to `recipient`, and records it in `data`.
+///
+/// Shared by [`achievement`] and [`bounty_approve`], so XP from either goes
+/// through exactly the same steps: [multipliers](player_data::Player::add_xp),
+/// the recipient's [team](teams), their medal and coins, and
+/// [`lvl_check()`](player_data::Player::lvl_check). Doesn't check any
+/// [limits](crate::guild_config::XpLimits), or save `data`.
+///
+/// Returns the record, the [`audit`] events to record once `data` is saved,
+/// and the embed to show it with.
+async fn grant_achievement(
+    ctx: Context<'_>,
+    data: &mut json_data::FileFormat,
+    granter_id: u64,
+    recipient: &serenity::User,
+    title: String,
+    xp: i128,
+    category: Option<String>,
+) -> Result<(achievement_data::AchievementRecord, Vec<audit::Event>, serenity::CreateEmbed), Error> {
+    let lang = i18n::locale(ctx);
+    let guild_config = ctx.data().guild_config(ctx.guild_id());
+    let current_id = recipient.id.get();
+    let achievement_id = data.next_achievement_id();
+
+    // XP is credited to whichever team the recipient is on right now.
+    let team_id = teams::team_of(&data.teams, current_id).map(|x| x.id);
+
+    let p: &mut player_data::Player = data.player_mut(current_id).ok_or(Error::PlayerNotFound(current_id))?;
+    let effective_xp = p.add_xp(xp);
+    if effective_xp > 0 {
+        METRICS.xp_granted.inc_by(u64::try_from(effective_xp).unwrap_or(u64::MAX));
+    }
+    let medal = guild_config.medals.medal(xp);
+    p.medals.add(medal);
+    let coins = shop::catalog().achievement_coins(xp);
+    let old_coins = p.coins;
+    p.coins = p.coins.saturating_add(coins);
+
+    let old_lvl = p.lvl;
+    let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+    if p.lvl > old_lvl {
+        config::announce(ctx, serenity::CreateEmbed::new()
+                         .title(tr!(lang, "announce-level-title"))
+                         .colour(guild_config.colours.level)
+                         .description(tr!(lang, "announce-level", user = format!("<@{current_id}>"), lvl = p.lvl))).await;
+    }
+
+    let mut embed = serenity::CreateEmbed::new()
+             .colour(p.accent_colour().unwrap_or(guild_config.colours.achievement))
+             .title(tr!(lang, "achievement-unlocked", medal = medal.emoji()))
+             .author(
+                serenity::CreateEmbedAuthor::new(p.name_line(recipient.display_name()))
+                    .icon_url(recipient.static_avatar_url().unwrap_or_else(|| recipient.default_avatar_url())))
+            .fields([
+                (tr!(lang, "field-achievement"), match &category {
+                    Some(category) => format!("{title} _({category})_"),
+                    None => title.clone(),
+                }, false),
+                (tr!(lang, "field-xp-gained"), effective_xp.to_string(), false),
+                (tr!(lang, "field-xp-total"), format!("{} _{}_",p.xp_bar(), p.xp_progress()), false)
+            ])
+            .footer(serenity::CreateEmbedFooter::new(tr!(lang, "achievement-footer", id = achievement_id)))
+            .description(lvl_output.join("\n\n"));
+    if p.coins > old_coins {
+        embed = embed.field(tr!(lang, "field-coins-earned"),
+                            tr!(lang, "coins-earned", earned = (p.coins - old_coins).to_string(), coins = p.coins.to_string()),
+                            false);
+    }
+
+    let player = p.clone();
+    let mut record = achievement_data::AchievementRecord::new(
+        achievement_id, granter_id, current_id, ctx.guild_id().map(|x| x.get()), title, xp, effective_xp,
+    );
+    record.coins = coins;
+    record.medal = Some(medal);
+    record.category = category;
+    record.team_id = team_id;
+    teams::credit(&mut data.teams, team_id, effective_xp);
+    data.achievements.push(record.clone());
+    let events = std::iter::once(audit::Event::AchievementGranted { record: record.clone(), player })
+        .chain(level_changed)
+        .collect();
+
+    Ok((record, events, embed))
+}
+
 /// Complete an Achievement, and gain XP.
 ///
-/// Accepts a title, an XP number, and a recipient (optional).
+/// Accepts a title, an XP number, a recipient (optional), and one of the
+/// server's [categories](crate::guild_config::GuildConfig::categories) (optional).
 ///
 /// Loads the information of the recipient from the JSON file,
 /// edits it to update the xp, then runs
 /// [`lvl_check()`](player_data::lvl_check).
+///
+/// The achievement wins a [medal](achievement_data::Medal), using the
+/// server's [medal thresholds](crate::guild_config::MedalThresholds), which
+/// is added to the recipient's [medal tally](player_data::Player::medals).
+///
+/// If the recipient is on a [team](crate::teams), the XP they actually
+/// gained is added to the team's XP too.
+///
+/// The achievement is sent with an "Undo" button, which the granter can
+/// use for [`undo_window`](crate::Data::undo_window) after sending it.
+/// Undoing takes away exactly the XP that was added (from the team too), and runs
+/// [`lvl_check()`](player_data::lvl_check) again to restore the old level.
 pub async fn achievement(
     ctx: Context<'_>,
     title: String,
     xp: i128,
     recipient: Option<serenity::User>,
+    category: Option<String>,
 ) -> Result<(),Error> {
 
     let u = recipient.as_ref().unwrap_or_else(|| ctx.author());
     let author = ctx.author();
+    let lang = i18n::locale(ctx);
 
-    player_data::verify_player(ctx, Some(u.id.get()));
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(u.id.get()))?;
 
-    let mut players = file_management::load_players();
+    let mut data = file_management::load()?;
 
     let current_id = u.id.get();
 
-
     if xp < 0 && !std::ptr::eq(u, author) {
         ctx.send(poise::CreateReply::default()
-        .content("You cannot remove points from somebody else...")
+        .content(tr!(lang, "achievement-negative-other"))
         .ephemeral(true)).await?;
         return Ok(())
     }
 
-    // new scope with which to access a player from `players`.
-    {
-        println!("Adding XP");
-        let p: &mut player_data::Player = players.iter_mut().find(|x| x.user_id == current_id).expect("User not present in Players despite verification");
-        p.add_xp(xp);
+    let guild_config = ctx.data().guild_config(ctx.guild_id());
+    let category = match category {
+        None => None,
+        Some(x) => match guild_config.category(&x) {
+            Some(x) => Some(x.to_string()),
+            None => {
+                ctx.send(poise::CreateReply::default()
+                .content(tr!(lang, "category-unknown", category = x.trim()))
+                .ephemeral(true)).await?;
+                return Ok(())
+            },
+        },
+    };
+    let granter = data.player_list.iter()
+        .find(|x| x.user_id == author.id.get())
+        .cloned()
+        .unwrap_or_else(|| player_data::Player::new(author.id.get()));
+    if let Err(message) = guild_config.limits.check(&data.achievements, ctx.guild_id().map(|x| x.get()), &granter, current_id, xp, chrono::Utc::now(), lang) {
+        ctx.send(poise::CreateReply::default()
+        .content(message)
+        .ephemeral(true)).await?;
+        return Ok(())
+    }
 
-        println!("XP added");
+    let (record, events, embed) = grant_achievement(ctx, &mut data, author.id.get(), u, title.clone(), xp, category).await?;
+    let achievement_id = record.id;
+    file_management::save(&data)?;
+    // Not held while waiting to see if it's undone.
+    drop(lock);
+    for event in events {
+        audit::record(ctx, event).await;
+    }
 
-        println!("Checking level");
-        let lvl_output = p.lvl_check(Some(ctx)).await;
+    let undo_button = serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(format!("achievement.undo.{achievement_id}"))
+            .label(tr!(lang, "achievement-undo"))
+            .style(serenity::ButtonStyle::Secondary),
+    ]);
+    let reply = ctx.send(poise::CreateReply::default()
+                         .embed(embed.clone())
+                         .components(vec![undo_button])
+    ).await?;
 
-        println!("Sending Message");
-        ctx.send(poise::CreateReply::default()
-                 .embed(serenity::CreateEmbed::new()
-                 .title(format!("{} | Achievement Unlocked!",
-                                if xp <= 0 { "💩" }
-                                else if xp < 25 { "🥉" }
-                                else if xp < 50 { "🥈" }
-                                else { "🥇" }
-                 ))
-                 .author(
-                    serenity::CreateEmbedAuthor::new(format!("Lv. {} {} {}", p.lvl, p.title(), u.display_name()))
-                        .icon_url(u.static_avatar_url().expect("No avatar image?")))
-                .fields([
-                    ("Achievement",title,false),
-                    ("XP Gained", p.xp_change(xp).to_string(), false),
-                    ("XP Total", format!("{} _({} / {})_",p.xp_bar(), p.xp, p.xp_threshold()), false)
-                ])
-                .description(lvl_output.join("\n\n")))
+    // Wait to see if the granter changes their mind.
+    let interaction = reply
+        .message()
+        .await?
+        .await_component_interaction(ctx)
+        .author_id(author.id)
+        .timeout(ctx.data().undo_window)
+        .await;
+
+    let Some(interaction) = interaction else {
+        tracing::debug!(achievement_id, "Undo window passed");
+        // Window has passed - remove the button, and leave the achievement as it is.
+        reply.edit(ctx, poise::CreateReply::default()
+                   .embed(embed)
+                   .components(vec![])).await?;
+        return Ok(())
+    };
+    interaction.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge).await?;
+
+    // Reload, in case anything has changed while waiting.
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let Some(index) = data.achievements.iter().position(|x| x.id == achievement_id && !x.revoked) else {
+        reply.edit(ctx, poise::CreateReply::default()
+                   .embed(embed)
+                   .components(vec![])).await?;
+        return Ok(())
+    };
+    let Some(p) = data.player_list.iter_mut().find(|x| x.user_id == current_id) else {
+        reply.edit(ctx, poise::CreateReply::default()
+                   .embed(embed)
+                   .components(vec![])).await?;
+        return Ok(())
+    };
+
+    let record = &mut data.achievements[index];
+    record.revoke(p);
+    teams::credit(&mut data.teams, record.team_id, -record.effective_xp);
+    tracing::info!(achievement_id, effective_xp = %record.effective_xp, "Achievement undone");
+    let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+    let player = p.clone();
+    let effective_xp = record.effective_xp;
+    file_management::save(&data)?;
+
+    reply.edit(ctx, poise::CreateReply::default()
+               .embed(serenity::CreateEmbed::new()
+                      .title(tr!(lang, "achievement-retracted"))
+                      .colour(player.accent_colour().unwrap_or(guild_config.colours.achievement))
+                      .author(
+                          serenity::CreateEmbedAuthor::new(player.name_line(u.display_name()))
+                              .icon_url(u.static_avatar_url().unwrap_or_else(|| u.default_avatar_url())))
+                      .fields([
+                          (tr!(lang, "field-achievement"), format!("~~{title}~~"), false),
+                          (tr!(lang, "field-xp-removed"), effective_xp.to_string(), false),
+                          (tr!(lang, "field-xp-total"), format!("{} _{}_",player.xp_bar(), player.xp_progress()), false)
+                      ])
+                      .footer(serenity::CreateEmbedFooter::new(tr!(lang, "achievement-retracted-footer",
+                                                                   id = achievement_id, name = author.display_name())))
+                      .description(lvl_output.join("\n\n")))
+               .components(vec![])).await?;
+
+    audit::record(ctx, audit::Event::AchievementRevoked {
+        achievement_id,
+        revoked_by: author.id.get(),
+        player,
+    }).await;
+    if let Some(event) = level_changed {
+        audit::record(ctx, event).await;
+    }
+
+    Ok(())
+}
+
+/// See somebody's XP and achievements, split up by category.
+///
+/// Only achievements from this server are counted, since categories belong to
+/// a server. Outside of a server, every achievement is counted. Achievements
+/// without a category are counted as "Uncategorised".
+pub async fn stats(
+    ctx: Context<'_>,
+    user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let u = user.as_ref().unwrap_or_else(|| ctx.author());
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(u.id.get()))?;
+    let data = file_management::load()?;
+    let p = data.player_list.iter().find(|x| x.user_id == u.id.get()).ok_or(Error::PlayerNotFound(u.id.get()))?;
+
+    let records = data.achievements.iter().filter(|x| x.recipient_id == u.id.get());
+    let mut totals = achievement_data::totals_by(records, ctx.guild_id().map(|x| x.get()), |x| x.category.clone())
+        .into_iter()
+        .collect::<Vec<_>>();
+    // Biggest first, with uncategorised achievements last.
+    totals.sort_by(|a, b| a.0.is_none().cmp(&b.0.is_none()).then(b.1.xp.cmp(&a.1.xp)).then(a.0.cmp(&b.0)));
+
+    let achievements: usize = totals.iter().map(|(_, x)| x.achievements).sum();
+    let xp: i128 = totals.iter().map(|(_, x)| x.xp).sum();
+    let fields = totals.iter().take(25).map(|(category, x)| (
+        category.clone().unwrap_or_else(|| tr!(lang, "stats-uncategorised")),
+        tr!(lang, "stats-category", achievements = x.achievements, xp = x.xp.to_string(), medals = x.medals.to_string()),
+        true,
+    ));
+
+    ctx.send(poise::CreateReply::default()
+        .embed(serenity::CreateEmbed::new()
+               .title(tr!(lang, "stats-title"))
+               .colour(p.accent_colour().unwrap_or(ctx.data().guild_config(ctx.guild_id()).colours.level))
+               .author(
+                    serenity::CreateEmbedAuthor::new(p.name_line(u.display_name()))
+                        .icon_url(u.static_avatar_url().unwrap_or_else(|| u.default_avatar_url())))
+               .description(if achievements == 0 {
+                   tr!(lang, "stats-none")
+               } else {
+                   tr!(lang, "stats-total", achievements = achievements, xp = xp.to_string())
+               })
+               .fields(fields))
         ).await?;
+    Ok(())
+}
+
+/// The base XP used to show how much each player gets from an achievement in [`compare`].
+const COMPARE_RATE_XP: i128 = 100;
+
+/// Compare two players side by side. Compares `user_a` with yourself if `user_b` isn't given.
+///
+/// Ranks come from [`rank_players()`](player_data::rank_players), like the
+/// [`leaderboard`]. Lifetime XP and achievement counts include every
+/// achievement they've been granted, in every server, that hasn't been revoked.
+///
+/// Also shows how much XP the lower-ranked player needs to overtake the other,
+/// with [`xp_to_overtake()`](player_data::Player::xp_to_overtake), and how
+/// big an achievement that would take with their multipliers, with
+/// [`base_xp_for()`](player_data::Player::base_xp_for).
+pub async fn compare(
+    ctx: Context<'_>,
+    user_a: serenity::User,
+    user_b: Option<serenity::User>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let user_b = user_b.unwrap_or_else(|| ctx.author().clone());
+    if user_a.id == user_b.id {
+        ctx.send(poise::CreateReply::default()
+                 .content(tr!(lang, "compare-self"))
+                 .ephemeral(true)).await?;
+        return Ok(())
     }
-    // scope exited. `players` can now be saved to file.
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user_a.id.get()))?;
+    player_data::verify_player(ctx, Some(user_b.id.get()))?;
+    let data = file_management::load()?;
+
+    let ranked = player_data::rank_players(data.player_list);
+    let find = |u: &serenity::User| ranked.iter()
+        .find(|(_, p)| p.user_id == u.id.get())
+        .ok_or(Error::PlayerNotFound(u.id.get()));
+    // The higher-ranked player goes first.
+    let mut pair = [(find(&user_a)?, &user_a), (find(&user_b)?, &user_b)];
+    pair.sort_by_key(|((rank, _), _)| *rank);
+    let [((leader_rank, leader), leader_user), ((chaser_rank, chaser), chaser_user)] = pair;
+
+    let totals = achievement_data::totals_by(&data.achievements, None, |x| x.recipient_id);
+    let field = |rank: usize, p: &player_data::Player, u: &serenity::User| {
+        let total = totals.get(&p.user_id).copied().unwrap_or_default();
+        let title = p.title().trim_end().to_string();
+        (p.name_line(u.display_name()),
+         tr!(lang, "compare-player",
+             rank = rank,
+             bar = format!("{} _{}_", p.xp_bar(), p.xp_progress()),
+             prestige = format!("{:.2}", p.prestige),
+             title = if title.is_empty() { tr!(lang, "compare-no-title") } else { title },
+             lifetime = total.xp.to_string(),
+             achievements = total.achievements,
+             medals = p.medals.to_string(),
+             base = COMPARE_RATE_XP.to_string(),
+             xp = p.xp_change(COMPARE_RATE_XP).to_string()),
+         true)
+    };
 
-    file_management::save_players(&players);
+    let mut embed = serenity::CreateEmbed::new()
+        .title(tr!(lang, "compare-title"))
+        .colour(ctx.data().guild_config(ctx.guild_id()).colours.level)
+        .fields([field(*leader_rank, leader, leader_user), field(*chaser_rank, chaser, chaser_user)]);
+    if let Some(xp) = chaser.xp_to_overtake(leader) {
+        let mut lines = vec![];
+        if chaser.rank_score() == leader.rank_score() {
+            lines.push(tr!(lang, "compare-tied"));
+        }
+        lines.push(tr!(lang, "compare-overtake",
+                       chaser = format!("<@{}>", chaser.user_id),
+                       leader = format!("<@{}>", leader.user_id),
+                       xp = xp.to_string(),
+                       base = chaser.base_xp_for(xp).to_string()));
+        embed = embed.field(tr!(lang, "field-catching-up"), lines.join("\n"), false);
+    }
 
+    ctx.send(poise::CreateReply::default()
+             .embed(embed)
+             ).await?;
     Ok(())
 }
 
+/// How many players are shown on each page of [`leaderboard`].
+const LEADERBOARD_PAGE_SIZE: usize = 10;
+
+/// How to sort the [`leaderboard`].
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum LeaderboardSort {
+    /// By level and XP, like everywhere else.
+    #[name = "level"]
+    Level,
+
+    /// Like an Olympic medal table: gold, then silver, then bronze.
+    #[name = "medals"]
+    Medals,
+}
+
+/// Every player with a score in `category` in this server, best first, as `(user ID, score)`.
+///
+/// Sorted by the XP earned from achievements in the category, or as a medal
+/// table of just those achievements.
+fn category_scores(data: &json_data::FileFormat, guild_id: Option<u64>, category: &str, sort: LeaderboardSort, lang: &str) -> Vec<(u64, String)> {
+    let records = data.achievements.iter().filter(|x| x.category.as_deref() == Some(category));
+    let mut totals = achievement_data::totals_by(records, guild_id, |x| x.recipient_id).into_iter().collect::<Vec<_>>();
+    match sort {
+        LeaderboardSort::Level => {
+            totals.retain(|(_, x)| x.xp > 0);
+            totals.sort_by(|a, b| b.1.xp.cmp(&a.1.xp).then(a.0.cmp(&b.0)));
+        },
+        LeaderboardSort::Medals => {
+            totals.retain(|(_, x)| x.medals.total() > 0);
+            totals.sort_by(|a, b| b.1.medals.cmp(&a.1.medals).then(b.1.xp.cmp(&a.1.xp)).then(a.0.cmp(&b.0)));
+        },
+    }
+    totals.into_iter().map(|(id, x)| (id, match sort {
+        LeaderboardSort::Level => tr!(lang, "leaderboard-category-xp", xp = x.xp.to_string(), achievements = x.achievements),
+        LeaderboardSort::Medals => x.medals.to_string(),
+    })).collect()
+}
+
 /// See who's at the top.
-pub async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
-    // Formatted as a list of the top 5 players.
-    let mut players = file_management::load_players();
+///
+/// Shows [`LEADERBOARD_PAGE_SIZE`] players at a time, starting from `page`
+/// (the first page is 1). Sorted by level with
+/// [`rank_players()`](player_data::rank_players), or as a medal table with
+/// [`rank_players_by_medals()`](player_data::rank_players_by_medals).
+///
+/// With a `category`, only achievements in that category in this server are
+/// counted, using their XP instead of players' levels.
+pub async fn leaderboard(
+    ctx: Context<'_>,
+    sort: LeaderboardSort,
+    page: usize,
+    category: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let guild_config = ctx.data().guild_config(ctx.guild_id());
+    let category = match category {
+        None => None,
+        Some(x) => match guild_config.category(&x) {
+            Some(x) => Some(x.to_string()),
+            None => {
+                ctx.send(poise::CreateReply::default()
+                         .content(tr!(lang, "category-unknown", category = x.trim()))
+                         .ephemeral(true)).await?;
+                return Ok(())
+            },
+        },
+    };
 
-    let pages = players.len() / 5;
+    let data = file_management::load()?;
+    let scores = match &category {
+        Some(category) => category_scores(&data, ctx.guild_id().map(|x| x.get()), category, sort, lang),
+        None => {
+            let ranked = match sort {
+                LeaderboardSort::Level => player_data::rank_players(data.player_list),
+                LeaderboardSort::Medals => player_data::rank_players_by_medals(data.player_list),
+            };
+            ranked.into_iter().map(|(_, p)| (p.user_id, match sort {
+                LeaderboardSort::Level => tr!(lang, "leaderboard-level", lvl = p.lvl, progress = p.xp_progress()),
+                LeaderboardSort::Medals => p.medals.to_string(),
+            })).collect()
+        },
+    };
 
-    players.sort();
+    let pages = scores.len().div_ceil(LEADERBOARD_PAGE_SIZE).max(1);
+    let page = page.clamp(1, pages);
+    let lines = scores.iter()
+        .enumerate()
+        .skip((page - 1) * LEADERBOARD_PAGE_SIZE)
+        .take(LEADERBOARD_PAGE_SIZE)
+        .map(|(i, (id, score))| format!("**#{}** <@{id}> - {score}", i + 1))
+        .collect::<Vec<_>>();
+    let description = if lines.is_empty() {
+        tr!(lang, "leaderboard-empty")
+    } else {
+        lines.join("\n")
+    };
 
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(match (sort, &category) {
+                        (LeaderboardSort::Level, None) => tr!(lang, "leaderboard-title"),
+                        (LeaderboardSort::Medals, None) => tr!(lang, "leaderboard-medals-title"),
+                        (LeaderboardSort::Level, Some(category)) => tr!(lang, "leaderboard-category-title", category = category.as_str()),
+                        (LeaderboardSort::Medals, Some(category)) => tr!(lang, "leaderboard-category-medals-title", category = category.as_str()),
+                    })
+                    .colour(guild_config.colours.level)
+                    .description(description)
+                    .footer(serenity::CreateEmbedFooter::new(tr!(lang, "leaderboard-page", page = page, pages = pages))))
+             ).await?;
     Ok(())
 }
 
 
 /// Edit your existing titles
-pub async fn update_title(ctx: Context<'_>) -> Result<(),Error> {
+///
+/// Replaces one word of your title, which you earned by prestiging. `index`
+/// starts at 1, for the first word. The new word is checked by
+/// [`titles::check()`], just like a word added by [`prestige`].
+pub async fn update_title(
+    ctx: Context<'_>,
+    index: usize,
+    word: String,
+) -> Result<(),Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let mut players = file_management::load_players()?;
+    let Some(p) = players.iter_mut().find(|x| x.user_id == ctx.author().id.get()) else {
+        return Err(Error::PlayerNotFound(ctx.author().id.get()))
+    };
+
+    let content = if p.title_segments.is_empty() {
+        tr!(lang, "title-none")
+    } else if index == 0 || index > p.title_segments.len() {
+        tr!(lang, "title-bad-index", words = p.title_segments.len())
+    } else {
+        match titles::check(&word, &ctx.data().guild_config(ctx.guild_id()), lang) {
+            Err(message) => message,
+            Ok(word) => {
+                let old_title = p.title();
+                p.title_segments[index - 1] = word;
+                let title = p.title().trim_end().to_string();
+                let player = p.clone();
+                file_management::save_players(&players)?;
+                audit::record(ctx, audit::Event::TitleChanged { old_title, player }).await;
+                tr!(lang, "title-changed", title = title)
+            },
+        }
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+
+/// See your perk points, and every perk in the [perk tree](perks).
+///
+/// Shows how many ranks of each perk you have, what the next rank costs,
+/// and which perks have to be bought first.
+pub async fn perks_view(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let players = file_management::load_players()?;
+    let p = players.iter().find(|x| x.user_id == ctx.author().id.get()).ok_or(Error::PlayerNotFound(ctx.author().id.get()))?;
+    let tree = perks::tree();
+
+    let fields = tree.perks.iter().map(|perk| {
+        let rank = p.perks.get(&perk.id).copied().unwrap_or(0);
+        let mut details = vec![perk.description.clone()];
+        if rank < perk.max_rank {
+            details.push(tr!(lang, "perk-cost", cost = perk.cost));
+        }
+        if !perk.requires.is_empty() {
+            let requires = perk.requires.iter()
+                .map(|x| tree.get(x).map_or(x.as_str(), |x| x.name.as_str()))
+                .collect::<Vec<_>>();
+            details.push(tr!(lang, "perk-requires", requires = requires.join(", ")));
+        }
+        (tr!(lang, "perk-rank", perk = perk.name.as_str(), id = perk.id.as_str(), rank = rank, max = perk.max_rank),
+         details.join("\n"), false)
+    });
+
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(tr!(lang, "perks-title"))
+                    .colour(ctx.data().guild_config(ctx.guild_id()).colours.prestige)
+                    .description(tr!(lang, "perks-points",
+                                     points = p.perk_points,
+                                     per_prestige = tree.points_per_prestige))
+                    .fields(fields))
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Spend perk points on the next rank of a perk.
+///
+/// Checked by [`PerkTree::buy()`](perks::PerkTree::buy), which makes sure
+/// you have enough points, and already have any perks it requires.
+pub async fn perks_buy(
+    ctx: Context<'_>,
+    perk: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let mut players = file_management::load_players()?;
+    let Some(p) = players.iter_mut().find(|x| x.user_id == ctx.author().id.get()) else {
+        return Err(Error::PlayerNotFound(ctx.author().id.get()))
+    };
+
+    let content = match perks::tree().buy(p, perk.trim(), lang) {
+        Err(message) => message,
+        Ok(bought) => {
+            let rank = p.perks.get(&bought.id).copied().unwrap_or(0);
+            // Perks that lower the XP needed to level up can leave the player with more than that.
+            let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+            let content = format!("{}\n{}", tr!(lang, "perk-bought", perk = bought.name.as_str(), rank = rank, points = p.perk_points),
+                                  lvl_output.join("\n"));
+            let event = audit::Event::PerkBought { perk: bought.id.clone(), player: p.clone() };
+            file_management::save_players(&players)?;
+            audit::record(ctx, event).await;
+            if let Some(level_changed) = level_changed {
+                audit::record(ctx, level_changed).await;
+            }
+            content
+        },
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Suggests perks for [`perks_buy`], matching what's been typed so far by name or ID.
+pub async fn autocomplete_perk(
+    _ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    perks::tree().perks.iter()
+        .filter(|x| x.id.contains(&partial) || x.name.to_lowercase().contains(&partial))
+        .map(|x| serenity::AutocompleteChoice::new(x.name.clone(), x.id.clone()))
+        .collect()
+}
+
+
+/// See your coins, and every item in the [shop](shop).
+///
+/// Shows what each item costs, and which ones you already own or have equipped.
+pub async fn shop_view(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let players = file_management::load_players()?;
+    let p = players.iter().find(|x| x.user_id == ctx.author().id.get()).ok_or(Error::PlayerNotFound(ctx.author().id.get()))?;
+
+    let fields = shop::catalog().items.iter().map(|item| {
+        let status = if p.equipped.contains(&item.id) {
+            tr!(lang, "shop-status-equipped")
+        } else if p.inventory.contains(&item.id) {
+            tr!(lang, "shop-status-owned")
+        } else {
+            tr!(lang, "coins", coins = item.price.to_string())
+        };
+        (tr!(lang, "shop-item", item = item.name.as_str(), id = item.id.as_str(), status = status),
+         item.description.clone(), false)
+    });
+
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(tr!(lang, "shop-title"))
+                    .colour(p.accent_colour().unwrap_or(ctx.data().guild_config(ctx.guild_id()).colours.level))
+                    .description(tr!(lang, "shop-coins", coins = p.coins.to_string()))
+                    .fields(fields))
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Spend coins on an item from the [shop](shop), and equip it.
+///
+/// Checked by [`Catalog::buy()`](shop::Catalog::buy). Every purchase is
+/// recorded in the [audit log](audit).
+pub async fn shop_buy(
+    ctx: Context<'_>,
+    item: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let mut players = file_management::load_players()?;
+    let Some(p) = players.iter_mut().find(|x| x.user_id == ctx.author().id.get()) else {
+        return Err(Error::PlayerNotFound(ctx.author().id.get()))
+    };
+
+    let content = match shop::catalog().buy(p, item.trim(), lang) {
+        Err(message) => message,
+        Ok(bought) => {
+            let content = if p.equipped.contains(&bought.id) {
+                tr!(lang, "shop-bought", item = bought.name.as_str(), coins = p.coins.to_string())
+            } else {
+                tr!(lang, "shop-bought-unequipped", item = bought.name.as_str(), coins = p.coins.to_string())
+            };
+            let event = audit::Event::ItemBought { item: bought.id.clone(), price: bought.price, player: p.clone() };
+            file_management::save_players(&players)?;
+            audit::record(ctx, event).await;
+            content
+        },
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Equip an item you own, or unequip it if it's already equipped.
+///
+/// Equipping an accent colour, XP bar or title frame replaces the one you
+/// had before. Badges can be worn [a few at a time](shop::MAX_BADGES).
+pub async fn shop_equip(
+    ctx: Context<'_>,
+    item: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(ctx.author().id.get()))?;
+    let mut players = file_management::load_players()?;
+    let Some(p) = players.iter_mut().find(|x| x.user_id == ctx.author().id.get()) else {
+        return Err(Error::PlayerNotFound(ctx.author().id.get()))
+    };
+
+    let content = match shop::catalog().equip(p, item.trim(), lang) {
+        Err(message) => message,
+        Ok((changed, equipped)) => {
+            let content = if equipped {
+                tr!(lang, "shop-equipped", item = changed.name.as_str())
+            } else {
+                tr!(lang, "shop-unequipped", item = changed.name.as_str())
+            };
+            let event = audit::Event::ItemEquipped { item: changed.id.clone(), equipped, player: p.clone() };
+            file_management::save_players(&players)?;
+            audit::record(ctx, event).await;
+            content
+        },
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Suggests the server's [categories](crate::guild_config::GuildConfig::categories),
+/// matching what's been typed so far.
+pub async fn autocomplete_category(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    ctx.data().guild_config(ctx.guild_id()).categories.into_iter()
+        .filter(|x| x.to_lowercase().contains(&partial))
+        .collect()
+}
+
+/// Suggests items for [`shop_buy`], matching what's been typed so far by name or ID.
+pub async fn autocomplete_item(
+    _ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    shop::catalog().items.iter()
+        .filter(|x| x.id.contains(&partial) || x.name.to_lowercase().contains(&partial))
+        .map(|x| serenity::AutocompleteChoice::new(x.name.clone(), x.id.clone()))
+        .collect()
+}
 
-    let players = file_management::load_players();
-    let p = players.iter().find(|x| x.user_id == ctx.author().id.get()).expect("User not present in Players despite verification").clone();
+/// Suggests items for [`shop_equip`], from the ones the user owns.
+pub async fn autocomplete_owned_item(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let Ok(players) = file_management::load_players() else {
+        return vec![]
+    };
+    let Some(p) = players.iter().find(|x| x.user_id == ctx.author().id.get()) else {
+        return vec![]
+    };
+    let partial = partial.to_lowercase();
+    shop::catalog().items.iter()
+        .filter(|x| p.inventory.contains(&x.id))
+        .filter(|x| x.id.contains(&partial) || x.name.to_lowercase().contains(&partial))
+        .map(|x| serenity::AutocompleteChoice::new(x.name.clone(), x.id.clone()))
+        .collect()
+}
+
+/// Sends an ephemeral reply, saying why a `/team` command didn't work.
+async fn team_error(ctx: Context<'_>, message: String) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default()
+             .content(message)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Saves the file, and records a change to a team in the [`audit`] log.
+async fn save_team(ctx: Context<'_>, data: &json_data::FileFormat, change: teams::Change, user_id: u64, team: &teams::Team) -> Result<(), Error> {
+    file_management::save(data)?;
+    audit::record(ctx, audit::Event::TeamChanged { change, user_id, team: team.clone() }).await;
+    Ok(())
+}
+
+/// Create a new [team](teams), with yourself as its owner.
+///
+/// Names and tags have to be unique. You can only be on one team at a time.
+pub async fn team_create(
+    ctx: Context<'_>,
+    name: String,
+    tag: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let author_id = ctx.author().id.get();
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    let mut data = file_management::load()?;
+
+    let team = match teams::create(&mut data.teams, author_id, &name, &tag, lang).cloned() {
+        Ok(team) => team,
+        Err(message) => return team_error(ctx, message).await,
+    };
+    save_team(ctx, &data, teams::Change::Created, author_id, &team).await?;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "team-created", team = team.display_name()))).await?;
+    Ok(())
+}
 
-    if p.title_segments.len() == 0 {
+/// Invite somebody to the team you own. They accept with [`team_join`].
+pub async fn team_invite(
+    ctx: Context<'_>,
+    user: serenity::User,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    if user.bot {
+        return team_error(ctx, tr!(lang, "team-invite-bot")).await
+    }
+    let team = match teams::invite(&mut data.teams, ctx.author().id.get(), user.id.get(), lang).cloned() {
+        Ok(team) => team,
+        Err(message) => return team_error(ctx, message).await,
+    };
+    save_team(ctx, &data, teams::Change::Invited, user.id.get(), &team).await?;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "team-invited", user = format!("<@{}>", user.id.get()), team = team.display_name()))).await?;
+    Ok(())
+}
+
+/// Join a team you've been invited to, by its name or tag.
+///
+/// Only XP you earn from now on counts towards the team.
+pub async fn team_join(
+    ctx: Context<'_>,
+    team: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let author_id = ctx.author().id.get();
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    let mut data = file_management::load()?;
+
+    let team = match teams::join(&mut data.teams, author_id, &team, lang).cloned() {
+        Ok(team) => team,
+        Err(message) => return team_error(ctx, message).await,
+    };
+    save_team(ctx, &data, teams::Change::Joined, author_id, &team).await?;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "team-joined", user = format!("<@{author_id}>"), team = team.display_name()))).await?;
+    Ok(())
+}
+
+/// Leave your team.
+///
+/// The XP you earned for the team stays with it. If you owned the team, the
+/// member who has been on it longest takes over, and if you were the last
+/// member, the team is disbanded.
+pub async fn team_leave(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let author_id = ctx.author().id.get();
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let was_owner = teams::team_of(&data.teams, author_id).is_some_and(|x| x.owner_id == author_id);
+    let content = match teams::leave(&mut data.teams, author_id, lang) {
+        Err(message) => return team_error(ctx, message).await,
+        Ok((team, true)) => {
+            file_management::save(&data)?;
+            audit::record(ctx, audit::Event::TeamDisbanded {
+                team_id: team.id,
+                name: team.display_name(),
+                user_id: author_id,
+            }).await;
+            tr!(lang, "team-disbanded", team = team.display_name())
+        },
+        Ok((team, false)) => {
+            save_team(ctx, &data, teams::Change::Left, author_id, &team).await?;
+            if was_owner {
+                tr!(lang, "team-left-owner", user = format!("<@{author_id}>"), team = team.display_name(),
+                    owner = format!("<@{}>", team.owner_id))
+            } else {
+                tr!(lang, "team-left", user = format!("<@{author_id}>"), team = team.display_name())
+            }
+        },
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .content(content)).await?;
+    Ok(())
+}
+
+/// Remove somebody from the team you own, or cancel their invite.
+///
+/// The XP they earned for the team stays with it.
+pub async fn team_kick(
+    ctx: Context<'_>,
+    user: serenity::User,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let team = match teams::kick(&mut data.teams, ctx.author().id.get(), user.id.get(), lang).cloned() {
+        Ok(team) => team,
+        Err(message) => return team_error(ctx, message).await,
+    };
+    save_team(ctx, &data, teams::Change::Kicked, user.id.get(), &team).await?;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "team-kicked", user = format!("<@{}>", user.id.get()), team = team.display_name()))
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// See a team's level, XP and members. Shows your own team if none is given.
+pub async fn team_info(
+    ctx: Context<'_>,
+    team: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let data = file_management::load()?;
+
+    let found = match &team {
+        Some(name) => teams::find(&data.teams, name),
+        None => teams::team_of(&data.teams, ctx.author().id.get()),
+    };
+    let Some(team) = found else {
+        return team_error(ctx, match team {
+            Some(name) => tr!(lang, "team-unknown", team = name.trim()),
+            None => tr!(lang, "team-not-member"),
+        }).await
+    };
+
+    let members = team.members.iter()
+        .map(|x| if *x == team.owner_id { format!("<@{x}> 👑") } else { format!("<@{x}>") })
+        .collect::<Vec<_>>();
+
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(team.display_name())
+                    .colour(ctx.data().guild_config(ctx.guild_id()).colours.level)
+                    .fields([
+                        (tr!(lang, "field-team-level"), tr!(lang, "leaderboard-level", lvl = team.level(), progress = team.xp_progress()), false),
+                        (tr!(lang, "field-team-xp"), team.xp.to_string(), false),
+                        (tr!(lang, "field-team-members", count = team.members.len(), max = teams::MAX_MEMBERS), members.join("\n"), false),
+                    ])
+                    .footer(serenity::CreateEmbedFooter::new(tr!(lang, "team-founded", date = team.created.format("%Y-%m-%d").to_string()))))
+             ).await?;
+    Ok(())
+}
+
+/// See which teams have earned the most XP.
+pub async fn team_leaderboard(
+    ctx: Context<'_>,
+    page: usize,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let data = file_management::load()?;
+    let ranked = teams::rank_teams(data.teams);
+
+    let pages = ranked.len().div_ceil(LEADERBOARD_PAGE_SIZE).max(1);
+    let page = page.clamp(1, pages);
+    let lines = ranked.iter()
+        .enumerate()
+        .skip((page - 1) * LEADERBOARD_PAGE_SIZE)
+        .take(LEADERBOARD_PAGE_SIZE)
+        .map(|(i, team)| format!("**#{}** {} - {}", i + 1, team.display_name(),
+                                 tr!(lang, "leaderboard-level", lvl = team.level(), progress = team.xp_progress())))
+        .collect::<Vec<_>>();
+    let description = if lines.is_empty() {
+        tr!(lang, "team-leaderboard-empty")
+    } else {
+        lines.join("\n")
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(tr!(lang, "team-leaderboard-title"))
+                    .colour(ctx.data().guild_config(ctx.guild_id()).colours.level)
+                    .description(description)
+                    .footer(serenity::CreateEmbedFooter::new(tr!(lang, "leaderboard-page", page = page, pages = pages))))
+             ).await?;
+    Ok(())
+}
+
+/// Suggests teams for [`team_join`] and [`team_info`], matching what's been typed so far by name or tag.
+pub async fn autocomplete_team(
+    _ctx: Context<'_>,
+    partial: &str,
+) -> Vec<String> {
+    let Ok(data) = file_management::load() else {
+        return vec![]
+    };
+    let partial = partial.to_lowercase();
+    data.teams.into_iter()
+        .filter(|x| x.name.to_lowercase().contains(&partial) || x.tag.to_lowercase().contains(&partial))
+        .map(|x| x.name)
+        .collect()
+}
+
+
+/// Challenge somebody to a wager of XP.
+///
+/// Your stake is taken (and held) straight away. The [buttons](challenges::handle_event)
+/// on the challenge let them accept, putting in the same stake, or decline.
+/// Stakes come out of the XP in your current level, so you can only wager
+/// what you've earned since your last level-up.
+pub async fn challenge(
+    ctx: Context<'_>,
+    user: serenity::User,
+    xp: i128,
+    description: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let author_id = ctx.author().id.get();
+    let target_id = user.id.get();
+
+    let description = description.trim().to_string();
+    let problem = if target_id == author_id {
+        Some(tr!(lang, "challenge-self"))
+    } else if user.bot {
+        Some(tr!(lang, "challenge-bot"))
+    } else if description.is_empty() || description.graphemes(true).count() > challenges::MAX_DESCRIPTION_LENGTH {
+        Some(tr!(lang, "challenge-invalid-description", max = challenges::MAX_DESCRIPTION_LENGTH))
+    } else {
+        None
+    };
+    if let Some(message) = problem {
+        ctx.send(poise::CreateReply::default()
+                 .content(message)
+                 .ephemeral(true)).await?;
+        return Ok(())
+    }
+
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    player_data::verify_player(ctx, Some(target_id))?;
+    let mut data = file_management::load()?;
+
+    // Checked now as well as when they accept, so there's no point sending a challenge they can't take.
+    let target_xp = data.player_mut(target_id).ok_or(Error::PlayerNotFound(target_id))?.xp;
+    let p = data.player_mut(author_id).ok_or(Error::PlayerNotFound(author_id))?;
+    let held = if target_xp < xp {
+        Err(tr!(lang, "hold-not-enough-xp", user = format!("<@{target_id}>"),
+                held = xp.to_string(), xp = target_xp.to_string()))
+    } else {
+        p.hold_xp(xp, lang)
+    };
+    if let Err(message) = held {
         ctx.send(poise::CreateReply::default()
-                 .content("You do not have a title to edit.")
+                 .content(message)
                  .ephemeral(true)).await?;
         return Ok(())
     }
-    // let menu = serenity::CreateActionRow::SelectMenu(
-    //     CreateSelectMenu::new("selected_title"),
+    let player = p.clone();
+
+    let challenge = challenges::Challenge {
+        id: data.challenges.iter().map(|x| x.id + 1).max().unwrap_or(1),
+        guild_id: ctx.guild_id().map(|x| x.get()),
+        channel_id: ctx.channel_id().get(),
+        message_id: 0,
+        challenger_id: author_id,
+        target_id,
+        stake: xp,
+        description,
+        locale: lang.to_string(),
+        state: challenges::State::Pending,
+        challenger_verdict: None,
+        target_verdict: None,
+        winner_id: None,
+        referee_id: None,
+        created: chrono::Utc::now(),
+        accepted: None,
+        finished: None,
+    };
+    // Saved before it's sent, so the stake is never held by a challenge that
+    // isn't in the file. If sending fails, it just expires and is given back.
+    data.challenges.push(challenge.clone());
+    file_management::save(&data)?;
+    drop(lock);
+    challenges::record(ctx.http(), ctx.data().audit_channel, &challenge, vec![(player.clone(), player.lvl)]).await;
+
+    let reply = ctx.send(poise::CreateReply::default()
+                         .content(tr!(lang, "challenge-sent", user = format!("<@{target_id}>"), challenger = format!("<@{author_id}>")))
+                         .embed(challenge.embed(ctx.data().guild_config(ctx.guild_id()).colours.achievement))
+                         .components(challenge.components())).await?;
+    let message_id = reply.message().await?.id.get();
+    tracing::info!(challenge_id = challenge.id, target_id, stake = %xp, "Challenge sent");
+
+    // The message ID is only needed to update the message when the challenge expires.
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+    if let Some(c) = data.challenges.iter_mut().find(|x| x.id == challenge.id) {
+        c.message_id = message_id;
+        file_management::save(&data)?;
+    }
+    Ok(())
+}
+
+/// Sends an ephemeral reply, saying why a `/bounty` command didn't work.
+async fn bounty_error(ctx: Context<'_>, message: String) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default()
+             .content(message)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// See every open [bounty](crate::bounties) in this server, oldest first.
+///
+/// Shows [`bounties::PAGE_SIZE`] bounties at a time, starting from `page`
+/// (the first page is 1).
+pub async fn bounties(
+    ctx: Context<'_>,
+    page: usize,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let data = file_management::load()?;
+    let open = bounties::open_in(&data.bounties, guild_id);
+
+    let pages = open.len().div_ceil(bounties::PAGE_SIZE).max(1);
+    let page = page.clamp(1, pages);
+    let lines = open.iter()
+        .skip((page - 1) * bounties::PAGE_SIZE)
+        .take(bounties::PAGE_SIZE)
+        .map(|x| x.summary(lang))
+        .collect::<Vec<_>>();
+    let description = if lines.is_empty() {
+        tr!(lang, "bounty-list-empty")
+    } else {
+        lines.join("\n")
+    };
+
+    ctx.send(poise::CreateReply::default()
+             .embed(serenity::CreateEmbed::new()
+                    .title(tr!(lang, "bounty-list-title"))
+                    .colour(ctx.data().guild_config(ctx.guild_id()).colours.achievement)
+                    .description(description)
+                    .footer(serenity::CreateEmbedFooter::new(tr!(lang, "leaderboard-page", page = page, pages = pages))))
+             ).await?;
+    Ok(())
+}
+
+/// Post a [bounty](crate::bounties), paid for with your own XP.
+///
+/// The reward is held from the XP in your current level straight away, and
+/// given back if nobody is paid before the bounty expires, or you cancel it.
+pub async fn bounty_post(
+    ctx: Context<'_>,
+    title: String,
+    xp: i128,
+    days: i64,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let author_id = ctx.author().id.get();
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    let mut data = file_management::load()?;
+
+    let bounty = bounties::Bounty::new(guild_id, author_id, title, xp, bounties::Funding::Poster, days);
+    let (bounty, player) = match bounties::post(&mut data, bounty, lang) {
+        Ok(x) => x,
+        Err(message) => return bounty_error(ctx, message).await,
+    };
+    let player = player.map(|p| {
+        let lvl = p.lvl;
+        (p, lvl)
+    });
+    file_management::save(&data)?;
+    drop(lock);
+    bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Posted, author_id, &bounty, player, None).await;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "bounty-posted", user = format!("<@{author_id}>")))
+             .embed(bounty.embed(lang, ctx.data().guild_config(ctx.guild_id()).colours.achievement))
+             ).await?;
+    Ok(())
+}
+
+/// Claim a [bounty](crate::bounties), saying how you did what it asks.
+///
+/// The poster then decides whether to [approve](bounty_approve) your claim.
+/// Claiming a bounty again replaces your proof.
+pub async fn bounty_claim(
+    ctx: Context<'_>,
+    id: u64,
+    proof: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let author_id = ctx.author().id.get();
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(author_id))?;
+    let mut data = file_management::load()?;
+
+    let claimed = bounties::find_open(&data.bounties, guild_id, id, lang).and_then(|index| {
+        let bounty = &mut data.bounties[index];
+        bounty.claim(author_id, &proof, lang).map(|_| bounty.clone())
+    });
+    let bounty = match claimed {
+        Ok(bounty) => bounty,
+        Err(message) => return bounty_error(ctx, message).await,
+    };
+    file_management::save(&data)?;
+    drop(lock);
+    bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Claimed, author_id, &bounty, None, None).await;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "bounty-claimed", user = format!("<@{author_id}>"), id = id,
+                          poster = format!("<@{}>", bounty.poster_id)))
+             ).await?;
+    Ok(())
+}
+
+/// See a [bounty](crate::bounties), and everybody who has claimed it.
+pub async fn bounty_view(
+    ctx: Context<'_>,
+    id: u64,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let data = file_management::load()?;
+
+    let Some(bounty) = data.bounties.iter().find(|x| x.id == id && x.guild_id == guild_id) else {
+        return bounty_error(ctx, tr!(lang, "bounty-unknown", id = id)).await
+    };
+    ctx.send(poise::CreateReply::default()
+             .embed(bounty.embed(lang, ctx.data().guild_config(ctx.guild_id()).colours.achievement))
+             ).await?;
+    Ok(())
+}
+
+/// Approve somebody's claim on a [bounty](crate::bounties) you posted, and pay them.
+///
+/// They're granted an achievement for the reward by [`grant_achievement()`],
+/// just like [`achievement`], with you as the granter. The server's
+/// [limits](crate::guild_config::XpLimits) on granting XP apply as well.
+pub async fn bounty_approve(
+    ctx: Context<'_>,
+    id: u64,
+    user: serenity::User,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let author_id = ctx.author().id.get();
+    let user_id = user.id.get();
+    let lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user_id))?;
+    let mut data = file_management::load()?;
+
+    let index = match bounties::find_open(&data.bounties, guild_id, id, lang) {
+        Ok(index) => index,
+        Err(message) => return bounty_error(ctx, message).await,
+    };
+    let bounty = data.bounties[index].clone();
+    if bounty.poster_id != author_id {
+        return bounty_error(ctx, tr!(lang, "bounty-not-poster", id = id)).await
+    }
+    if !bounty.claimed_by(user_id) {
+        return bounty_error(ctx, tr!(lang, "bounty-not-claimed", user = format!("<@{user_id}>"), id = id)).await
+    }
+    let granter = data.player_list.iter()
+        .find(|x| x.user_id == author_id)
+        .cloned()
+        .unwrap_or_else(|| player_data::Player::new(author_id));
+    let limits = ctx.data().guild_config(ctx.guild_id()).limits;
+    if let Err(message) = limits.check(&data.achievements, Some(guild_id), &granter, user_id, bounty.reward, chrono::Utc::now(), lang) {
+        return bounty_error(ctx, message).await
+    }
 
-    // );
+    let (record, events, embed) = grant_achievement(ctx, &mut data, author_id, &user, bounty.title.clone(), bounty.reward, None).await?;
+    let b = &mut data.bounties[index];
+    b.state = bounties::State::Paid;
+    b.winner_id = Some(user_id);
+    b.achievement_id = Some(record.id);
+    b.finished = Some(chrono::Utc::now());
+    let bounty = b.clone();
+    tracing::info!(bounty_id = id, user_id, achievement_id = record.id, "Bounty paid");
+    file_management::save(&data)?;
+    drop(lock);
+    for event in events {
+        audit::record(ctx, event).await;
+    }
+    bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Paid, user_id, &bounty, None, None).await;
 
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "bounty-paid", user = format!("<@{user_id}>"), id = id))
+             .embed(embed)
+             ).await?;
+    Ok(())
+}
+
+/// Take down a [bounty](crate::bounties) you posted, before anybody is paid.
+///
+/// If you paid for it, the reward is given back to you.
+pub async fn bounty_cancel(
+    ctx: Context<'_>,
+    id: u64,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return bounty_error(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let author_id = ctx.author().id.get();
+    let lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let index = match bounties::find_open(&data.bounties, guild_id, id, lang) {
+        Err(message) => return bounty_error(ctx, message).await,
+        Ok(index) if data.bounties[index].poster_id != author_id => return bounty_error(ctx, tr!(lang, "bounty-not-poster", id = id)).await,
+        Ok(index) => index,
+    };
+    let player = bounties::close(&mut data, index, bounties::State::Cancelled).await;
+    let bounty = data.bounties[index].clone();
+    file_management::save(&data)?;
+    drop(lock);
+    bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Cancelled, author_id, &bounty, player, None).await;
+
+    let content = match bounty.funding {
+        bounties::Funding::Poster => tr!(lang, "bounty-cancelled-refund", id = id, reward = bounty.reward.to_string()),
+        bounties::Funding::Minted => tr!(lang, "bounty-cancelled", id = id),
+    };
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Suggests open bounties in this server for the [`/bounty`](crate::bounties) commands, matching what's been typed so far by ID or title.
+pub async fn autocomplete_bounty(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let (Some(guild_id), Ok(data)) = (ctx.guild_id(), file_management::load()) else {
+        return vec![]
+    };
+    let lang = i18n::locale(ctx);
+    let partial = partial.to_lowercase();
+    bounties::open_in(&data.bounties, guild_id.get()).into_iter()
+        .filter(|x| x.id.to_string().contains(&partial) || x.title.to_lowercase().contains(&partial))
+        .map(|x| {
+            // Discord only shows the first 100 characters of a choice.
+            let name = tr!(lang, "bounty-choice", id = x.id, title = x.title.clone(), reward = x.reward.to_string());
+            serenity::AutocompleteChoice::new(name.chars().take(100).collect::<String>(), x.id)
+        })
+        .collect()
+}
+
+
+/// Choose the language the bot replies to you in.
+///
+/// Overrides the server's language, and the language your Discord is set to.
+/// Choosing `auto` goes back to using those.
+pub async fn language(
+    ctx: Context<'_>,
+    language: &str,
+) -> Result<(), Error> {
+    let Ok(locale) = i18n::parse_choice(language) else {
+        ctx.send(poise::CreateReply::default()
+                 .content(tr!(i18n::locale(ctx), "language-unknown", options = i18n::LOCALES.join(", ")))
+                 .ephemeral(true)).await?;
+        return Ok(())
+    };
+
+    let user_id = ctx.author().id.get();
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+    match locale {
+        Some(locale) => data.user_locales.insert(user_id, locale.to_string()),
+        None => data.user_locales.remove(&user_id),
+    };
+    file_management::save(&data)?;
+    ctx.data().cache_user_locale(user_id, locale.map(str::to_string));
+    audit::record(ctx, audit::Event::LocaleChanged { user_id, locale: locale.map(str::to_string) }).await;
+
+    // Looked up again, so the reply is in the new language.
+    let lang = i18n::locale(ctx);
+    ctx.send(poise::CreateReply::default()
+             .content(match locale {
+                 Some(_) => tr!(lang, "language-set"),
+                 None => tr!(lang, "language-auto"),
+             })
+             .ephemeral(true)).await?;
     Ok(())
 }
@@ -0,0 +1,803 @@
+//! Moderation commands, for fixing mistakes in somebody's data
+//! without having to hand-edit `saved_data.json`.
+//!
+//! Every command here takes a `reason`, and records an
+//! [`AdminAction`](admin_data::AdminAction) in
+//! [`FileFormat::admin_log`](crate::json_data::FileFormat::admin_log),
+//! so that changes can always be traced back to who made them.
+//!
+//! Access is restricted to anybody with Manage Server, or the server's
+//! admin role, by [`config::is_admin()`]. Players' data is shared by every
+//! server, so the commands that change someone's XP, level or prestige, or
+//! reset, delete, export, import or migrate players, are also restricted to the bot's
+//! owners, by [`config::is_owner()`]. Title moderation isn't, so that any
+//! server can deal with a title it finds offensive. Revoking and listing
+//! achievements only sees ones granted in the server the command is used in,
+//! except that owners can also see ones from before servers were recorded.
+//!
+//! Replies are translated, but the admin log and any files sent back are
+//! always in English, so they read the same for every admin.
+
+use crate::{ Context, Error, player_data, achievement_data, admin_data, file_management, serenity, audit, bounties, challenges, config, i18n::{self, tr}, migrate, teams, titles, transfer, json_data::FileFormat, guild_config::{DigestPeriod, DigestSchedule, MAX_CATEGORIES, MAX_CATEGORY_LENGTH} };
+
+/// The largest file [`import`] and [`migrate`] will accept.
+const MAX_IMPORT_BYTES: u32 = 8 * 1024 * 1024;
+
+/// Records an admin action in the log and the [`audit`] log, and saves the file.
+async fn log_and_save(ctx: Context<'_>, mut data: FileFormat, target_id: u64, action: String, reason: String) -> Result<(), Error> {
+    let action = admin_data::AdminAction::new(ctx.author().id.get(), target_id, action, reason);
+    data.admin_log.push(action.clone());
+    file_management::save(&data)?;
+
+    let player = data.player_list.iter().find(|x| x.user_id == target_id).cloned();
+    audit::record(ctx, audit::Event::AdminOverride { action, player }).await;
+    Ok(())
+}
+
+/// Whether [`revoke`] and [`history`] can see an achievement in this server.
+///
+/// Achievements from before servers were recorded don't belong to any, so
+/// only the bot's owners can see those, from any server.
+fn in_this_server(ctx: Context<'_>, record: &achievement_data::AchievementRecord) -> bool {
+    match record.guild_id {
+        Some(guild_id) => ctx.guild_id().is_some_and(|x| x.get() == guild_id),
+        None => ctx.framework().options().owners.contains(&ctx.author().id),
+    }
+}
+
+/// Sends an ephemeral reply to the admin who used the command.
+async fn reply(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Set a player's XP to an exact amount.
+///
+/// Runs [`lvl_check()`](player_data::Player::lvl_check) afterwards,
+/// so setting XP past the threshold will level the player up.
+pub async fn set_xp(
+    ctx: Context<'_>,
+    user: serenity::User,
+    xp: i128,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user.id.get()))?;
+    let mut data = file_management::load()?;
+
+    let p = data.player_mut(user.id.get()).ok_or(Error::PlayerNotFound(user.id.get()))?;
+    let old_xp = p.xp;
+    p.xp = xp;
+    let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+    let summary = tr!(lang, "admin-set-xp", name = user.display_name(), old = old_xp.to_string(),
+                      new = xp.to_string(), lvl = p.lvl, xp = p.xp.to_string());
+
+    log_and_save(ctx, data, user.id.get(), format!("set xp {old_xp} -> {xp}"), reason).await?;
+    if let Some(event) = level_changed {
+        audit::record(ctx, event).await;
+    }
+    reply(ctx, format!("{summary}\n{}", lvl_output.join("\n"))).await
+}
+
+/// Add or remove XP from a player directly.
+///
+/// Unlike [`/achievement`](crate::commands::achievement), this ignores the
+/// player's prestige multiplier - the exact amount given is added.
+pub async fn adjust_xp(
+    ctx: Context<'_>,
+    user: serenity::User,
+    amount: i128,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user.id.get()))?;
+    let mut data = file_management::load()?;
+
+    let p = data.player_mut(user.id.get()).ok_or(Error::PlayerNotFound(user.id.get()))?;
+    p.xp += amount;
+    let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+    let summary = tr!(lang, "admin-adjust-xp", name = user.display_name(), amount = amount.to_string(),
+                      lvl = p.lvl, xp = p.xp.to_string());
+
+    log_and_save(ctx, data, user.id.get(), format!("adjust xp {amount:+}"), reason).await?;
+    if let Some(event) = level_changed {
+        audit::record(ctx, event).await;
+    }
+    reply(ctx, format!("{summary}\n{}", lvl_output.join("\n"))).await
+}
+
+/// Set a player's level.
+///
+/// Their XP is kept, unless it no longer fits inside the level.
+pub async fn set_level(
+    ctx: Context<'_>,
+    user: serenity::User,
+    level: i64,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    if level < 1 {
+        return reply(ctx, tr!(lang, "admin-level-too-low")).await
+    }
+
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user.id.get()))?;
+    let mut data = file_management::load()?;
+
+    let p = data.player_mut(user.id.get()).ok_or(Error::PlayerNotFound(user.id.get()))?;
+    let old_lvl = p.lvl;
+    p.lvl = level;
+    p.xp = p.xp.clamp(0, p.xp_threshold() - 1);
+
+    log_and_save(ctx, data, user.id.get(), format!("set level {old_lvl} -> {level}"), reason).await?;
+    reply(ctx, tr!(lang, "admin-set-level", name = user.display_name(), old = old_lvl, new = level)).await
+}
+
+/// Set a player's prestige multiplier.
+pub async fn set_prestige(
+    ctx: Context<'_>,
+    user: serenity::User,
+    prestige: f64,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    if !prestige.is_finite() || prestige < 1.0 {
+        return reply(ctx, tr!(lang, "admin-prestige-too-low")).await
+    }
+
+    let _lock = ctx.data().lock_file().await;
+    player_data::verify_player(ctx, Some(user.id.get()))?;
+    let mut data = file_management::load()?;
+
+    let p = data.player_mut(user.id.get()).ok_or(Error::PlayerNotFound(user.id.get()))?;
+    let old_prestige = p.prestige;
+    p.prestige = prestige;
+
+    log_and_save(ctx, data, user.id.get(), format!("set prestige {old_prestige:.2} -> {prestige:.2}"), reason).await?;
+    reply(ctx, tr!(lang, "admin-set-prestige", name = user.display_name(),
+                   old = format!("{old_prestige:.2}"), new = format!("{prestige:.2}"))).await
+}
+
+/// Reset a player back to a brand new [`Player`](player_data::Player).
+///
+/// Their achievement history is kept.
+pub async fn reset(
+    ctx: Context<'_>,
+    user: serenity::User,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let Some(p) = data.player_mut(user.id.get()) else {
+        return reply(ctx, tr!(lang, "admin-no-data", name = user.display_name())).await
+    };
+    *p = player_data::Player::new(user.id.get());
+
+    log_and_save(ctx, data, user.id.get(), "reset".to_string(), reason).await?;
+    reply(ctx, tr!(lang, "admin-reset", name = user.display_name())).await
+}
+
+/// Remove one word from a player's title.
+///
+/// `index` starts at 1, for the first word in the title.
+pub async fn remove_title(
+    ctx: Context<'_>,
+    user: serenity::User,
+    index: usize,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let Some(p) = data.player_mut(user.id.get()) else {
+        return reply(ctx, tr!(lang, "admin-no-data", name = user.display_name())).await
+    };
+    if index == 0 || index > p.title_segments.len() {
+        return reply(ctx, tr!(lang, "admin-title-too-short", name = user.display_name(), words = p.title_segments.len())).await
+    }
+    let removed = p.title_segments.remove(index - 1);
+
+    log_and_save(ctx, data, user.id.get(), format!("remove title segment {index} ({removed})"), reason).await?;
+    reply(ctx, tr!(lang, "admin-remove-title", name = user.display_name(), removed = removed)).await
+}
+
+/// Set one word of a player's title, or add a new one.
+///
+/// The moderator override for [`titles`]: the word still has to be a valid
+/// title word, but the server's banned words are ignored. `index` starts at 1,
+/// for the first word, and if it isn't given, the word is added to the end.
+pub async fn set_title(
+    ctx: Context<'_>,
+    user: serenity::User,
+    index: Option<usize>,
+    word: String,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let word = match titles::check_format(&word, lang) {
+        Ok(x) => x,
+        Err(message) => return reply(ctx, message).await,
+    };
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let Some(p) = data.player_mut(user.id.get()) else {
+        return reply(ctx, tr!(lang, "admin-no-data", name = user.display_name())).await
+    };
+    let action = match index {
+        None => {
+            p.title_segments.push(word.clone());
+            format!("add title segment ({word})")
+        },
+        Some(index) if index == 0 || index > p.title_segments.len() =>
+            return reply(ctx, tr!(lang, "admin-title-too-short", name = user.display_name(), words = p.title_segments.len())).await,
+        Some(index) => {
+            let old = std::mem::replace(&mut p.title_segments[index - 1], word.clone());
+            format!("set title segment {index} ({old} -> {word})")
+        },
+    };
+    let title = p.title().trim_end().to_string();
+
+    log_and_save(ctx, data, user.id.get(), action, reason).await?;
+    reply(ctx, tr!(lang, "admin-set-title", name = user.display_name(), title = title)).await
+}
+
+/// View or change the words that can't be used in titles in this server.
+///
+/// See [`titles`] for how they're matched. With no `word`, the list is shown.
+pub async fn title_filter(
+    ctx: Context<'_>,
+    action: FilterAction,
+    word: Option<String>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+
+    let word = match (action, word) {
+        (FilterAction::List, _) | (_, None) => {
+            let words = &config.banned_title_words;
+            return reply(ctx, match words.is_empty() {
+                true => tr!(lang, "admin-filter-empty"),
+                false => tr!(lang, "admin-filter-list", words = words.iter().map(|x| format!("||{x}||")).collect::<Vec<_>>().join(", ")),
+            }).await
+        },
+        (_, Some(word)) => word.trim().to_lowercase(),
+    };
+    if titles::fold(&word).is_empty() {
+        return reply(ctx, tr!(lang, "admin-filter-invalid")).await
+    }
+    let Some(reason) = reason else {
+        return reply(ctx, tr!(lang, "admin-reason-needed")).await
+    };
+
+    let exists = config.banned_title_words.contains(&word);
+    let (content, log) = match action {
+        FilterAction::Add if exists => return reply(ctx, tr!(lang, "admin-filter-exists")).await,
+        FilterAction::Add if config.banned_title_words.len() >= titles::MAX_BANNED_WORDS =>
+            return reply(ctx, tr!(lang, "admin-filter-full", max = titles::MAX_BANNED_WORDS)).await,
+        FilterAction::Add => {
+            config.banned_title_words.push(word.clone());
+            (tr!(lang, "admin-filter-added"), format!("ban title word ({word})"))
+        },
+        FilterAction::Remove if !exists => return reply(ctx, tr!(lang, "admin-filter-missing")).await,
+        FilterAction::Remove | FilterAction::List => {
+            config.banned_title_words.retain(|x| *x != word);
+            (tr!(lang, "admin-filter-removed"), format!("unban title word ({word})"))
+        },
+    };
+
+    config::save(ctx, data, guild_id, config, log, reason).await?;
+    reply(ctx, content).await
+}
+
+/// View or change the categories that achievements can be granted under in this server.
+///
+/// With no `name`, the list is shown. Removing a category doesn't change the
+/// achievements that were already granted under it.
+pub async fn categories(
+    ctx: Context<'_>,
+    action: FilterAction,
+    name: Option<String>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+
+    let name = match (action, name) {
+        (FilterAction::List, _) | (_, None) => {
+            return reply(ctx, match config.categories.is_empty() {
+                true => tr!(lang, "admin-categories-empty"),
+                false => tr!(lang, "admin-categories-list", categories = config.categories.join(", ")),
+            }).await
+        },
+        (_, Some(name)) => name.trim().to_string(),
+    };
+    if name.is_empty() || name.chars().count() > MAX_CATEGORY_LENGTH || name.chars().any(char::is_control)
+        || name.eq_ignore_ascii_case("none") {
+        return reply(ctx, tr!(lang, "admin-category-invalid", max = MAX_CATEGORY_LENGTH)).await
+    }
+    let Some(reason) = reason else {
+        return reply(ctx, tr!(lang, "admin-reason-needed")).await
+    };
+
+    let existing = config.category(&name).map(str::to_string);
+    let (content, log) = match (action, existing) {
+        (FilterAction::Add, Some(_)) => return reply(ctx, tr!(lang, "admin-category-exists")).await,
+        (FilterAction::Add, None) if config.categories.len() >= MAX_CATEGORIES =>
+            return reply(ctx, tr!(lang, "admin-categories-full", max = MAX_CATEGORIES)).await,
+        (FilterAction::Add, None) => {
+            config.categories.push(name.clone());
+            (tr!(lang, "admin-category-added", category = name.as_str()), format!("add category ({name})"))
+        },
+        (_, None) => return reply(ctx, tr!(lang, "admin-category-missing")).await,
+        (_, Some(existing)) => {
+            config.categories.retain(|x| *x != existing);
+            (tr!(lang, "admin-category-removed", category = existing.as_str()), format!("remove category ({existing})"))
+        },
+    };
+
+    config::save(ctx, data, guild_id, config, log, reason).await?;
+    reply(ctx, content).await
+}
+
+/// The choices for [`title_filter`]'s and [`categories`]' `action`.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum FilterAction {
+    #[name = "add"]
+    Add,
+    #[name = "remove"]
+    Remove,
+    #[name = "list"]
+    List,
+}
+
+/// Delete a player's data entirely.
+///
+/// Their achievement history is kept, so that it can still be audited. Their
+/// open [challenges](crate::challenges) are called off and their open
+/// [bounties](crate::bounties) cancelled first, so that anybody else's XP
+/// held by them is given back. Then they're taken off their team, and out of
+/// any invites and claims, by [`FileFormat::remove_player()`].
+pub async fn delete(
+    ctx: Context<'_>,
+    user: serenity::User,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let user_id = user.id.get();
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    if !data.player_list.iter().any(|x| x.user_id == user_id) {
+        return reply(ctx, tr!(lang, "admin-no-data", name = user.display_name())).await
+    }
+
+    let mut called_off = vec![];
+    for index in 0..data.challenges.len() {
+        let c = &data.challenges[index];
+        if c.is_open() && (c.challenger_id == user_id || c.target_id == user_id) {
+            let players = challenges::finish(&mut data, index, challenges::State::CalledOff, None).await;
+            called_off.push((data.challenges[index].clone(), players));
+        }
+    }
+    let mut cancelled = vec![];
+    for index in 0..data.bounties.len() {
+        let b = &data.bounties[index];
+        if b.is_open() && b.poster_id == user_id {
+            let player = bounties::close(&mut data, index, bounties::State::Cancelled).await;
+            cancelled.push((data.bounties[index].clone(), player));
+        }
+    }
+    data.remove_player(user_id);
+
+    let action = admin_data::AdminAction::new(ctx.author().id.get(), user_id, "delete player".to_string(), reason);
+    data.admin_log.push(action.clone());
+    file_management::save(&data)?;
+
+    // Recorded before the player is deleted, so that rebuilding from the log doesn't bring them back.
+    for (challenge, players) in called_off {
+        challenges::record(ctx.http(), ctx.data().audit_channel, &challenge, players).await;
+    }
+    for (bounty, player) in cancelled {
+        bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Cancelled, ctx.author().id.get(), &bounty, player, None).await;
+    }
+    audit::record(ctx, audit::Event::AdminOverride { action, player: None }).await;
+    reply(ctx, tr!(lang, "admin-delete", name = user.display_name())).await
+}
+
+/// Revoke a past achievement that was granted in this server.
+///
+/// Takes back exactly the XP that the achievement gave, then runs
+/// [`lvl_check()`](player_data::Player::lvl_check), so that the player
+/// loses any levels that they shouldn't have.
+pub async fn revoke(
+    ctx: Context<'_>,
+    achievement_id: u64,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let Some(index) = data.achievements.iter().position(|x| x.id == achievement_id && in_this_server(ctx, x)) else {
+        return reply(ctx, tr!(lang, "admin-no-achievement", id = achievement_id.to_string())).await
+    };
+    if data.achievements[index].revoked {
+        return reply(ctx, tr!(lang, "admin-already-revoked", id = achievement_id.to_string())).await
+    }
+    let recipient_id = data.achievements[index].recipient_id;
+    let Some(p) = data.player_list.iter_mut().find(|x| x.user_id == recipient_id) else {
+        return reply(ctx, tr!(lang, "admin-recipient-gone")).await
+    };
+
+    let record = &mut data.achievements[index];
+    record.revoke(p);
+    teams::credit(&mut data.teams, record.team_id, -record.effective_xp);
+    let (lvl_output, level_changed) = p.lvl_check(Some(ctx)).await;
+    let summary = tr!(lang, "admin-revoke", id = achievement_id.to_string(), title = record.title.as_str(),
+                      xp = record.effective_xp.to_string(), user = format!("<@{recipient_id}>"));
+    let action = format!("revoke achievement #{achievement_id} ({} xp)", record.effective_xp);
+    let player = p.clone();
+
+    log_and_save(ctx, data, recipient_id, action, reason).await?;
+    audit::record(ctx, audit::Event::AchievementRevoked {
+        achievement_id,
+        revoked_by: ctx.author().id.get(),
+        player,
+    }).await;
+    if let Some(event) = level_changed {
+        audit::record(ctx, event).await;
+    }
+    reply(ctx, format!("{summary}\n{}", lvl_output.join("\n"))).await
+}
+
+/// List a player's most recent achievements in this server, along with their IDs.
+///
+/// Used to find the ID to pass to [`revoke`].
+pub async fn history(
+    ctx: Context<'_>,
+    user: serenity::User,
+) -> Result<(), Error> {
+    let data = file_management::load()?;
+
+    let lines = data.achievements.iter()
+        .filter(|x| x.recipient_id == user.id.get() && in_this_server(ctx, x))
+        .rev()
+        .take(10)
+        .map(|x| format!("{}#{} | {}{} | {} XP (from <@{}>, {}){}",
+                         if x.revoked { "~~" } else { "" },
+                         x.id, x.title, x.category.as_ref().map_or(String::new(), |c| format!(" ({c})")),
+                         x.effective_xp, x.granter_id,
+                         x.timestamp.format("%Y-%m-%d %H:%M"),
+                         if x.revoked { "~~" } else { "" }))
+        .collect::<Vec<_>>();
+
+    let lang = i18n::locale(ctx);
+    if lines.is_empty() {
+        return reply(ctx, tr!(lang, "admin-no-achievements", name = user.display_name())).await
+    }
+    reply(ctx, format!("{}\n{}", tr!(lang, "admin-history", name = user.display_name()), lines.join("\n"))).await
+}
+
+/// View or change the limits on granting XP in this server.
+///
+/// Any limit that isn't given is left as it is. If none are given,
+/// the current limits are shown instead.
+pub async fn limits(
+    ctx: Context<'_>,
+    cooldown_secs: Option<i64>,
+    daily_granted_cap: Option<i128>,
+    daily_received_cap: Option<i128>,
+    max_xp_per_achievement: Option<i128>,
+    reciprocal_limit: Option<usize>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+    let l = &mut config.limits;
+
+    let mut changes = vec![];
+    if let Some(x) = cooldown_secs { changes.push(format!("cooldown_secs {} -> {x}", l.cooldown_secs)); l.cooldown_secs = x.max(0); }
+    if let Some(x) = daily_granted_cap { changes.push(format!("daily_granted_cap {} -> {x}", l.daily_granted_cap)); l.daily_granted_cap = x.max(0); }
+    if let Some(x) = daily_received_cap { changes.push(format!("daily_received_cap {} -> {x}", l.daily_received_cap)); l.daily_received_cap = x.max(0); }
+    if let Some(x) = max_xp_per_achievement { changes.push(format!("max_xp_per_achievement {} -> {x}", l.max_xp_per_achievement)); l.max_xp_per_achievement = x.max(0); }
+    if let Some(x) = reciprocal_limit { changes.push(format!("reciprocal_limit {} -> {x}", l.reciprocal_limit)); l.reciprocal_limit = x; }
+
+    let current = tr!(lang, "admin-limits",
+                      cooldown = l.cooldown_secs,
+                      granted = l.daily_granted_cap.to_string(),
+                      received = l.daily_received_cap.to_string(),
+                      max = l.max_xp_per_achievement.to_string(),
+                      reciprocal = l.reciprocal_limit);
+
+    if changes.is_empty() {
+        return reply(ctx, format!("{}\n{current}", tr!(lang, "admin-limits-current"))).await
+    }
+    let Some(reason) = reason else {
+        return reply(ctx, tr!(lang, "admin-reason-needed")).await
+    };
+
+    config::save(ctx, data, guild_id, config, format!("set limits: {}", changes.join(", ")), reason).await?;
+    reply(ctx, format!("{}\n{current}", tr!(lang, "admin-limits-updated"))).await
+}
+
+/// View or change when this server's [digest](crate::digest) is posted.
+///
+/// Works like [`limits`]: anything not given is left as it is, and if nothing
+/// is given, the current schedule is shown. Changing the schedule never posts
+/// a digest straight away - the first one is posted at the next scheduled time.
+pub async fn digest(
+    ctx: Context<'_>,
+    period: Option<DigestSetting>,
+    channel: Option<serenity::ChannelId>,
+    hour: Option<u32>,
+    weekday: Option<String>,
+    category: Option<String>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+    let old = config.digest.as_ref().map_or("Off".to_string(), DigestSchedule::describe);
+
+    if period.is_none() && channel.is_none() && hour.is_none() && weekday.is_none() && category.is_none() {
+        return reply(ctx, tr!(lang, "admin-digest-current", schedule = old)).await
+    }
+    if hour.is_some_and(|x| x > 23) {
+        return reply(ctx, tr!(lang, "admin-digest-bad-hour")).await
+    }
+    let weekday = match weekday.map(|x| x.parse::<chrono::Weekday>()) {
+        None => None,
+        Some(Ok(x)) => Some(x),
+        Some(Err(_)) => return reply(ctx, tr!(lang, "admin-digest-bad-weekday")).await,
+    };
+    // `None` leaves the category as it is, and `Some(None)` clears it.
+    let category = match category {
+        None => None,
+        Some(x) if x.trim().eq_ignore_ascii_case("none") => Some(None),
+        Some(x) => match config.category(&x) {
+            Some(x) => Some(Some(x.to_string())),
+            None => return reply(ctx, tr!(lang, "category-unknown", category = x.trim())).await,
+        },
+    };
+
+    config.digest = match (period, config.digest) {
+        (Some(DigestSetting::Off), _) => None,
+        (None, None) => return reply(ctx, tr!(lang, "admin-digest-none")).await,
+        (period, existing) => {
+            let Some(channel_id) = channel.map(|x| x.get()).or(existing.as_ref().map(|x| x.channel_id)) else {
+                return reply(ctx, tr!(lang, "admin-digest-no-channel")).await
+            };
+            let period = match period {
+                Some(DigestSetting::Daily) => DigestPeriod::Daily,
+                Some(DigestSetting::Weekly) => DigestPeriod::Weekly,
+                _ => existing.as_ref().map_or(DigestPeriod::Daily, |x| x.period),
+            };
+            Some(DigestSchedule {
+                channel_id,
+                period,
+                hour: hour.or(existing.as_ref().map(|x| x.hour)).unwrap_or(0),
+                weekday: weekday.or(existing.as_ref().map(|x| x.weekday)).unwrap_or(chrono::Weekday::Mon),
+                category: category.unwrap_or(existing.and_then(|x| x.category)),
+            })
+        },
+    };
+    let new = config.digest.as_ref().map_or("Off".to_string(), DigestSchedule::describe);
+
+    let Some(reason) = reason else {
+        return reply(ctx, tr!(lang, "admin-reason-needed")).await
+    };
+    match &config.digest {
+        Some(schedule) => data.digests_posted.insert(guild_id, schedule.latest_slot(chrono::Utc::now())),
+        None => data.digests_posted.remove(&guild_id),
+    };
+
+    config::save(ctx, data, guild_id, config, format!("set digest: {old} -> {new}"), reason).await?;
+    reply(ctx, tr!(lang, "admin-digest-updated", schedule = new)).await
+}
+
+/// The choices for [`digest`]'s `period`.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum DigestSetting {
+    #[name = "daily"]
+    Daily,
+    #[name = "weekly"]
+    Weekly,
+    #[name = "off"]
+    Off,
+}
+
+/// Export every player's data as a file, as CSV or JSON.
+///
+/// See [`transfer`] for the fields included.
+pub async fn export(
+    ctx: Context<'_>,
+    format: transfer::Format,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let players = file_management::load_players()?;
+    let content = match transfer::export(&players, format) {
+        Ok(x) => x,
+        Err(e) => return reply(ctx, tr!(lang, "admin-export-failed", error = e)).await,
+    };
+
+    let filename = format!("players.{}", format.extension());
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "admin-exported", count = players.len()))
+             .attachment(serenity::CreateAttachment::bytes(content.into_bytes(), filename))
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Import players from a CSV or JSON file, made by [`export`].
+///
+/// With `dry_run` set, only shows what would change. Otherwise, every added
+/// or changed player is recorded as its own admin action.
+pub async fn import(
+    ctx: Context<'_>,
+    file: serenity::Attachment,
+    strategy: transfer::Strategy,
+    dry_run: bool,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(format) = transfer::Format::from_filename(&file.filename) else {
+        return reply(ctx, tr!(lang, "admin-file-type")).await
+    };
+    if file.size > MAX_IMPORT_BYTES {
+        return reply(ctx, tr!(lang, "admin-file-too-big", limit = MAX_IMPORT_BYTES / 1024)).await
+    }
+    ctx.defer_ephemeral().await?;
+
+    let content = file.download().await?;
+    let rows = match transfer::parse(&String::from_utf8_lossy(&content), format) {
+        Ok(x) => x,
+        Err(e) => return reply(ctx, tr!(lang, "admin-import-failed", error = e)).await,
+    };
+
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+    let changes = transfer::plan(&data.player_list, &rows, strategy);
+    let summary = transfer::summarise(&changes);
+    let diff = changes.iter().map(transfer::Change::describe).collect::<Vec<_>>().join("\n");
+    let diff = serenity::CreateAttachment::bytes(diff.into_bytes(), "import.diff");
+
+    if dry_run {
+        ctx.send(poise::CreateReply::default()
+                 .content(tr!(lang, "admin-import-dry-run", summary = summary))
+                 .attachment(diff)
+                 .ephemeral(true)).await?;
+        return Ok(())
+    }
+
+    let applied = transfer::apply(&mut data, &changes, ctx.author().id.get(), &reason);
+    file_management::save(&data)?;
+    for (action, player) in applied {
+        audit::record(ctx, audit::Event::AdminOverride { action, player: Some(player) }).await;
+    }
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "admin-imported", summary = summary))
+             .attachment(diff)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Bring players over from another leveling bot's leaderboard export.
+///
+/// See [`migrate`](crate::migrate) for the formats understood. With `dry_run`
+/// set, only shows every user's level before and after.
+pub async fn migrate(
+    ctx: Context<'_>,
+    file: serenity::Attachment,
+    strategy: transfer::Strategy,
+    dry_run: bool,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(format) = transfer::Format::from_filename(&file.filename) else {
+        return reply(ctx, tr!(lang, "admin-file-type")).await
+    };
+    if file.size > MAX_IMPORT_BYTES {
+        return reply(ctx, tr!(lang, "admin-file-too-big", limit = MAX_IMPORT_BYTES / 1024)).await
+    }
+    ctx.defer_ephemeral().await?;
+
+    let content = file.download().await?;
+    let old = match migrate::parse(&String::from_utf8_lossy(&content), format) {
+        Ok(x) => x,
+        Err(e) => return reply(ctx, tr!(lang, "admin-import-failed", error = e)).await,
+    };
+
+    let _lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+    let previews = migrate::plan(&data.player_list, old, strategy).await;
+    let preview = previews.iter().map(migrate::Preview::describe).collect::<Vec<_>>().join("\n");
+    let preview = serenity::CreateAttachment::bytes(preview.into_bytes(), "migration.txt");
+    let changes = previews.into_iter().map(|x| x.change).collect::<Vec<_>>();
+    let summary = transfer::summarise(&changes);
+
+    if dry_run {
+        ctx.send(poise::CreateReply::default()
+                 .content(tr!(lang, "admin-migrate-dry-run", summary = summary))
+                 .attachment(preview)
+                 .ephemeral(true)).await?;
+        return Ok(())
+    }
+
+    let applied = transfer::apply(&mut data, &changes, ctx.author().id.get(), &reason);
+    file_management::save(&data)?;
+    for (action, player) in applied {
+        audit::record(ctx, audit::Event::AdminOverride { action, player: Some(player) }).await;
+    }
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "admin-migrated", summary = summary))
+             .attachment(preview)
+             .ephemeral(true)).await?;
+    Ok(())
+}
+
+/// Mint a [bounty](crate::bounties), whose reward doesn't come from anybody's XP.
+///
+/// The admin who mints it approves its claims, just like a bounty a member
+/// posted. Cancelling it, or letting it expire, gives nobody anything back.
+pub async fn bounty(
+    ctx: Context<'_>,
+    title: String,
+    xp: i128,
+    days: i64,
+    reason: String,
+) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "bounty-server-only")).await
+    };
+    let author_id = ctx.author().id.get();
+    let lock = ctx.data().lock_file().await;
+    let mut data = file_management::load()?;
+
+    let bounty = bounties::Bounty::new(guild_id, author_id, title, xp, bounties::Funding::Minted, days);
+    let bounty = match bounties::post(&mut data, bounty, lang) {
+        Ok((bounty, _)) => bounty,
+        Err(message) => return reply(ctx, message).await,
+    };
+    let action = admin_data::AdminAction::new(author_id, guild_id, format!("mint bounty #{} ({} xp)", bounty.id, bounty.reward), reason);
+    data.admin_log.push(action.clone());
+    file_management::save(&data)?;
+    drop(lock);
+    bounties::record(ctx.http(), ctx.data().audit_channel, bounties::Change::Posted, author_id, &bounty, None, Some(action)).await;
+
+    ctx.send(poise::CreateReply::default()
+             .content(tr!(lang, "bounty-minted", user = format!("<@{author_id}>")))
+             .embed(bounty.embed(lang, ctx.data().guild_config(ctx.guild_id()).colours.achievement))
+             ).await?;
+    Ok(())
+}
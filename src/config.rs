@@ -0,0 +1,344 @@
+//! Per-server settings, changed with `/config get`, `/config set` and `/config reset`.
+//!
+//! Settings are stored in
+//! [`FileFormat::guild_configs`](crate::json_data::FileFormat::guild_configs),
+//! and cached in [`Data`] so any command can read them with
+//! [`ctx.data().guild_config()`](Data::guild_config). Every change goes through
+//! [`save()`], which keeps the two in step and logs the change.
+
+use crate::{ Context, Data, Error, serenity, admin_data, audit, file_management, i18n::{self, tr}, guild_config::GuildConfig, json_data::FileFormat };
+use poise::ChoiceParameter;
+
+/// A single setting that can be changed with `/config set`.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum Key {
+    #[name = "announcement_channel"]
+    AnnouncementChannel,
+    #[name = "admin_role"]
+    AdminRole,
+    #[name = "referee_role"]
+    RefereeRole,
+    #[name = "cooldown_secs"]
+    CooldownSecs,
+    #[name = "daily_granted_cap"]
+    DailyGrantedCap,
+    #[name = "daily_received_cap"]
+    DailyReceivedCap,
+    #[name = "max_xp_per_achievement"]
+    MaxXpPerAchievement,
+    #[name = "reciprocal_limit"]
+    ReciprocalLimit,
+    #[name = "features.achievements"]
+    AchievementsEnabled,
+    #[name = "features.prestige"]
+    PrestigeEnabled,
+    #[name = "features.levels"]
+    LevelsEnabled,
+    #[name = "locale"]
+    Locale,
+    #[name = "colours.level"]
+    LevelColour,
+    #[name = "colours.achievement"]
+    AchievementColour,
+    #[name = "colours.prestige"]
+    PrestigeColour,
+    #[name = "medals.silver"]
+    SilverMedalXp,
+    #[name = "medals.gold"]
+    GoldMedalXp,
+}
+
+/// Every key, in the order they're listed by `/config get`.
+pub const KEYS: [Key; 17] = [
+    Key::AnnouncementChannel, Key::AdminRole, Key::RefereeRole,
+    Key::CooldownSecs, Key::DailyGrantedCap, Key::DailyReceivedCap, Key::MaxXpPerAchievement, Key::ReciprocalLimit,
+    Key::AchievementsEnabled, Key::PrestigeEnabled, Key::LevelsEnabled,
+    Key::Locale,
+    Key::LevelColour, Key::AchievementColour, Key::PrestigeColour,
+    Key::SilverMedalXp, Key::GoldMedalXp,
+];
+
+/// Reads a Discord ID, as either a plain number or a mention like `<#123>` or `<@&123>`.
+/// `none` clears it.
+fn parse_id(value: &str, lang: &str) -> Result<Option<u64>, String> {
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(None)
+    }
+    value.trim_start_matches(['<', '#', '@', '&']).trim_end_matches('>')
+        .parse()
+        .map(Some)
+        .map_err(|_| tr!(lang, "config-invalid-id", value = value))
+}
+
+fn parse_number<T: std::str::FromStr + PartialOrd + Default>(value: &str, lang: &str) -> Result<T, String> {
+    match value.parse::<T>() {
+        Ok(x) if x >= T::default() => Ok(x),
+        _ => Err(tr!(lang, "config-invalid-number", value = value)),
+    }
+}
+
+fn parse_bool(value: &str, lang: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "true" | "on" | "yes" | "enabled" => Ok(true),
+        "false" | "off" | "no" | "disabled" => Ok(false),
+        _ => Err(tr!(lang, "config-invalid-bool", value = value)),
+    }
+}
+
+fn parse_colour(value: &str, lang: &str) -> Result<u32, String> {
+    let hex = value.trim_start_matches('#').trim_start_matches("0x");
+    match u32::from_str_radix(hex, 16) {
+        Ok(x) if hex.len() == 6 => Ok(x),
+        _ => Err(tr!(lang, "config-invalid-colour", value = value)),
+    }
+}
+
+impl Key {
+
+    /// The current value of this setting, ready to show to a user.
+    pub fn get(self, config: &GuildConfig, lang: &str) -> String {
+        let id = |x: Option<u64>, mention: &str| x.map_or(tr!(lang, "config-none"), |x| format!("<{mention}{x}>"));
+        let on_off = |x: bool| if x { tr!(lang, "config-on") } else { tr!(lang, "config-off") };
+        let colour = |x: u32| format!("#{x:06X}");
+        match self {
+            Key::AnnouncementChannel => id(config.announcement_channel, "#"),
+            Key::AdminRole => id(config.admin_role, "@&"),
+            Key::RefereeRole => id(config.referee_role, "@&"),
+            Key::CooldownSecs => config.limits.cooldown_secs.to_string(),
+            Key::DailyGrantedCap => config.limits.daily_granted_cap.to_string(),
+            Key::DailyReceivedCap => config.limits.daily_received_cap.to_string(),
+            Key::MaxXpPerAchievement => config.limits.max_xp_per_achievement.to_string(),
+            Key::ReciprocalLimit => config.limits.reciprocal_limit.to_string(),
+            Key::AchievementsEnabled => on_off(config.features.achievements),
+            Key::PrestigeEnabled => on_off(config.features.prestige),
+            Key::LevelsEnabled => on_off(config.features.levels),
+            Key::Locale => config.locale.clone().unwrap_or_else(|| tr!(lang, "config-auto")),
+            Key::LevelColour => colour(config.colours.level),
+            Key::AchievementColour => colour(config.colours.achievement),
+            Key::PrestigeColour => colour(config.colours.prestige),
+            Key::SilverMedalXp => config.medals.silver.to_string(),
+            Key::GoldMedalXp => config.medals.gold.to_string(),
+        }
+    }
+
+    /// Changes this setting, checking the new value first.
+    ///
+    /// If the value isn't valid, returns why, in the language `lang`.
+    pub fn set(self, config: &mut GuildConfig, value: &str, lang: &str) -> Result<(), String> {
+        let value = value.trim();
+        match self {
+            Key::AnnouncementChannel => config.announcement_channel = parse_id(value, lang)?,
+            Key::AdminRole => config.admin_role = parse_id(value, lang)?,
+            Key::RefereeRole => config.referee_role = parse_id(value, lang)?,
+            Key::CooldownSecs => config.limits.cooldown_secs = parse_number(value, lang)?,
+            Key::DailyGrantedCap => config.limits.daily_granted_cap = parse_number(value, lang)?,
+            Key::DailyReceivedCap => config.limits.daily_received_cap = parse_number(value, lang)?,
+            Key::MaxXpPerAchievement => config.limits.max_xp_per_achievement = parse_number(value, lang)?,
+            Key::ReciprocalLimit => config.limits.reciprocal_limit = parse_number(value, lang)?,
+            Key::AchievementsEnabled => config.features.achievements = parse_bool(value, lang)?,
+            Key::PrestigeEnabled => config.features.prestige = parse_bool(value, lang)?,
+            Key::LevelsEnabled => config.features.levels = parse_bool(value, lang)?,
+            Key::Locale => {
+                config.locale = i18n::parse_choice(value)
+                    .map_err(|value| tr!(lang, "language-unknown-value", value = value, options = i18n::LOCALES.join(", ")))?
+                    .map(str::to_string);
+            },
+            Key::LevelColour => config.colours.level = parse_colour(value, lang)?,
+            Key::AchievementColour => config.colours.achievement = parse_colour(value, lang)?,
+            Key::PrestigeColour => config.colours.prestige = parse_colour(value, lang)?,
+            Key::SilverMedalXp => config.medals.silver = parse_number(value, lang)?,
+            Key::GoldMedalXp => config.medals.gold = parse_number(value, lang)?,
+        }
+        if config.medals.silver > config.medals.gold {
+            return Err(tr!(lang, "config-medal-order",
+                           silver = config.medals.silver.to_string(), gold = config.medals.gold.to_string()))
+        }
+        Ok(())
+    }
+
+    /// Puts this setting back to its default.
+    pub fn reset(self, config: &mut GuildConfig) {
+        let d = GuildConfig::default();
+        match self {
+            Key::AnnouncementChannel => config.announcement_channel = d.announcement_channel,
+            Key::AdminRole => config.admin_role = d.admin_role,
+            Key::RefereeRole => config.referee_role = d.referee_role,
+            Key::CooldownSecs => config.limits.cooldown_secs = d.limits.cooldown_secs,
+            Key::DailyGrantedCap => config.limits.daily_granted_cap = d.limits.daily_granted_cap,
+            Key::DailyReceivedCap => config.limits.daily_received_cap = d.limits.daily_received_cap,
+            Key::MaxXpPerAchievement => config.limits.max_xp_per_achievement = d.limits.max_xp_per_achievement,
+            Key::ReciprocalLimit => config.limits.reciprocal_limit = d.limits.reciprocal_limit,
+            Key::AchievementsEnabled => config.features.achievements = d.features.achievements,
+            Key::PrestigeEnabled => config.features.prestige = d.features.prestige,
+            Key::LevelsEnabled => config.features.levels = d.features.levels,
+            Key::Locale => config.locale = d.locale,
+            Key::LevelColour => config.colours.level = d.colours.level,
+            Key::AchievementColour => config.colours.achievement = d.colours.achievement,
+            Key::PrestigeColour => config.colours.prestige = d.colours.prestige,
+            // Silver can never need more XP than gold.
+            Key::SilverMedalXp => config.medals.silver = d.medals.silver.min(config.medals.gold),
+            Key::GoldMedalXp => config.medals.gold = d.medals.gold.max(config.medals.silver),
+        }
+    }
+}
+
+impl Data {
+
+    /// The settings for a server, or the defaults if it hasn't changed any (or isn't a server).
+    pub fn guild_config(&self, guild_id: Option<serenity::GuildId>) -> GuildConfig {
+        let configs = self.guild_configs.read().unwrap_or_else(|e| e.into_inner());
+        guild_id.and_then(|x| configs.get(&x.get()).cloned()).unwrap_or_default()
+    }
+
+    /// Updates the cached settings for a server. Use [`save()`] to change them properly.
+    fn cache_guild_config(&self, guild_id: u64, config: GuildConfig) {
+        self.guild_configs.write().unwrap_or_else(|e| e.into_inner()).insert(guild_id, config);
+    }
+}
+
+/// Saves a server's new settings, and records the change in the admin log and the [`audit`] log.
+pub async fn save(ctx: Context<'_>, mut data: FileFormat, guild_id: u64, config: GuildConfig, action: String, reason: String) -> Result<(), Error> {
+    let action = admin_data::AdminAction::new(ctx.author().id.get(), guild_id, action, reason);
+    data.admin_log.push(action.clone());
+    data.guild_configs.insert(guild_id, config.clone());
+    file_management::save(&data)?;
+    ctx.data().cache_guild_config(guild_id, config.clone());
+    audit::record(ctx, audit::Event::ConfigChanged { guild_id, action, config }).await;
+    Ok(())
+}
+
+/// Refuses commands for [features](crate::guild_config::Features) that a server has turned off.
+///
+/// Used as Poise's `command_check`, so it runs before every command.
+pub async fn check_feature(ctx: Context<'_>) -> Result<bool, Error> {
+    let features = ctx.data().guild_config(ctx.guild_id()).features;
+    let enabled = match ctx.command().qualified_name.split(' ').next().unwrap_or_default() {
+        "achievement" | "bounty" | "bounties" => features.achievements,
+        "prestige" => features.prestige,
+        "level" | "leaderboard" | "compare" | "challenge" => features.levels,
+        _ => true,
+    };
+    if enabled { Ok(true) } else { Err(Error::FeatureDisabled) }
+}
+
+/// Allows anybody with Manage Server, or the server's [admin role](GuildConfig::admin_role).
+pub async fn is_admin(ctx: Context<'_>) -> Result<bool, Error> {
+    let Some(member) = ctx.author_member().await else {
+        return Ok(false)
+    };
+    // Slash commands come with the member's permissions. Otherwise, they're worked out from the cache.
+    let permissions = member.permissions.or_else(|| {
+        let guild = ctx.guild()?;
+        let channel = guild.channels.get(&ctx.channel_id())?;
+        Some(guild.user_permissions_in(channel, &member))
+    });
+    if permissions.is_some_and(|x| x.manage_guild()) {
+        return Ok(true)
+    }
+
+    let admin_role = ctx.data().guild_config(ctx.guild_id()).admin_role;
+    if admin_role.is_some_and(|x| member.roles.contains(&serenity::RoleId::new(x))) {
+        return Ok(true)
+    }
+    Err(Error::NotAdmin)
+}
+
+/// Allows only the bot's owners, as set up in the Discord developer portal.
+///
+/// Used on the [`/admin`](crate::commands::admin) commands that change
+/// players' data, since it's shared by every server.
+pub async fn is_owner(ctx: Context<'_>) -> Result<bool, Error> {
+    if ctx.framework().options().owners.contains(&ctx.author().id) {
+        return Ok(true)
+    }
+    Err(Error::NotOwner)
+}
+
+/// Posts an embed in the server's announcement channel, if it has one.
+///
+/// Failing to announce never stops the command - the error is just logged.
+pub async fn announce(ctx: Context<'_>, embed: serenity::CreateEmbed) {
+    let Some(channel) = ctx.data().guild_config(ctx.guild_id()).announcement_channel else {
+        return
+    };
+    let message = serenity::CreateMessage::new()
+        .embed(embed)
+        .allowed_mentions(serenity::CreateAllowedMentions::new());
+    if let Err(e) = serenity::ChannelId::new(channel).send_message(ctx.http(), message).await {
+        tracing::warn!(channel_id = channel, error = %e, "Failed to post announcement");
+    }
+}
+
+/// Show this server's settings.
+pub async fn get(ctx: Context<'_>, key: Option<Key>) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let config = ctx.data().guild_config(ctx.guild_id());
+    let lines = match key {
+        Some(key) => vec![format!("`{}`: {}", key.name(), key.get(&config, lang))],
+        None => KEYS.iter().map(|key| format!("`{}`: {}", key.name(), key.get(&config, lang))).collect(),
+    };
+    reply(ctx, lines.join("\n")).await
+}
+
+/// Change one of this server's settings.
+pub async fn set(ctx: Context<'_>, key: Key, value: String, reason: String) -> Result<(), Error> {
+    let lang = i18n::locale(ctx);
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(lang, "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+    // The admin log is always in English, so it reads the same for everybody.
+    let old = key.get(&config, i18n::DEFAULT_LOCALE);
+    if let Err(message) = key.set(&mut config, &value, lang) {
+        return reply(ctx, message).await
+    }
+    let new = key.get(&config, i18n::DEFAULT_LOCALE);
+
+    save(ctx, data, guild_id, config.clone(), format!("set {} {old} -> {new}", key.name()), reason).await?;
+    // Looked up again, in case the server's language was just changed.
+    let lang = i18n::locale(ctx);
+    reply(ctx, tr!(lang, "config-set", key = key.name(), value = key.get(&config, lang))).await
+}
+
+/// Put one, or all, of this server's settings back to the defaults.
+pub async fn reset(ctx: Context<'_>, key: Option<Key>, reason: String) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id().map(|x| x.get()) else {
+        return reply(ctx, tr!(i18n::locale(ctx), "config-server-only")).await
+    };
+    let _lock = ctx.data().lock_file().await;
+    let data = file_management::load()?;
+    let mut config = data.guild_config(Some(guild_id));
+
+    let action = match key {
+        Some(key) => {
+            key.reset(&mut config);
+            format!("reset {}", key.name())
+        },
+        None => {
+            // The digest schedule, banned title words and categories aren't keys, so they're kept.
+            config = GuildConfig {
+                digest: config.digest,
+                banned_title_words: config.banned_title_words,
+                categories: config.categories,
+                ..GuildConfig::default()
+            };
+            "reset all settings".to_string()
+        },
+    };
+
+    save(ctx, data, guild_id, config, action, reason).await?;
+    let lang = i18n::locale(ctx);
+    match key {
+        Some(key) => reply(ctx, tr!(lang, "config-reset", key = key.name())).await,
+        None => reply(ctx, tr!(lang, "config-reset-all")).await,
+    }
+}
+
+async fn reply(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(poise::CreateReply::default()
+             .content(content)
+             .ephemeral(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Scheduled summaries of what happened in a server, posted to a channel.
+//!
+//! Set up with [`/admin digest`](crate::commands::admin::digest). Each digest
+//! lists the top XP gainers, new levels reached, prestiges, and the biggest
+//! achievements of the day or week, from the achievement history and the
+//! [`audit`] log.
+//!
+//! The time each digest was due is saved in
+//! [`FileFormat::digests_posted`](crate::json_data::FileFormat::digests_posted)
+//! once it's posted, so restarting the bot never posts one twice. Any that
+//! were missed while the bot was offline are posted when it comes back, up to
+//! [`MAX_CATCH_UP`] per server. This isn't in the [`audit`] log, so a save
+//! file [rebuilt](audit::replay) from it starts again from the next digest due.
+//!
+//! Players aren't tied to a server, so levels and prestiges are only listed
+//! for players who have granted or received an achievement in that server.
+//!
+//! A digest can be limited to one [category](crate::guild_config::GuildConfig::categories),
+//! in which case only achievements in that category are counted, and levels
+//! and prestiges (which don't have a category) are left out.
+//!
+//! Digests are written in the server's language, if it has chosen one, and
+//! otherwise in English.
+
+use crate::{ Error, serenity, audit, file_management, i18n::{self, tr}, guild_config::{DigestPeriod, DigestSchedule}, json_data::FileFormat };
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// How often to check whether a digest is due.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// The most missed digests to post for a single server, after being offline.
+pub const MAX_CATCH_UP: usize = 7;
+
+/// How many entries to show in each section.
+const SECTION_LENGTH: usize = 5;
+
+/// Joins the lines of a section, keeping within Discord's limit for embed fields.
+fn section(lines: Vec<String>) -> Option<String> {
+    if lines.is_empty() {
+        return None
+    }
+    let mut value = String::new();
+    for line in lines {
+        if value.len() + line.len() + 1 > 1024 {
+            break
+        }
+        value.push_str(&line);
+        value.push('\n');
+    }
+    Some(value)
+}
+
+/// Builds the digest for a server, covering everything after `start`, up to and including `end`.
+///
+/// Only achievements in the schedule's category are included, if it has one.
+/// Returns `None` if nothing happened.
+pub fn build(data: &FileFormat, history: &[audit::Entry], guild_id: u64, schedule: &DigestSchedule,
+             start: DateTime<Utc>, end: DateTime<Utc>, lang: &str) -> Option<serenity::CreateEmbed> {
+    let category = schedule.category.as_deref();
+    let in_window = |x: DateTime<Utc>| start < x && x <= end;
+    let in_guild = data.achievements.iter().filter(|x| x.guild_id == Some(guild_id));
+    let members = in_guild.clone().flat_map(|x| [x.granter_id, x.recipient_id]).collect::<HashSet<_>>();
+    let mut achievements = in_guild
+        .filter(|x| !x.revoked && in_window(x.timestamp))
+        .filter(|x| category.is_none() || x.category.as_deref() == category)
+        .collect::<Vec<_>>();
+
+    let mut gains: HashMap<u64, i128> = HashMap::new();
+    for x in &achievements {
+        *gains.entry(x.recipient_id).or_default() += x.effective_xp;
+    }
+    let mut gains = gains.into_iter().filter(|(_, xp)| *xp > 0).collect::<Vec<_>>();
+    gains.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let gainers = gains.iter()
+        .take(SECTION_LENGTH)
+        .enumerate()
+        .map(|(i, (id, xp))| format!("{}. {}", i + 1, tr!(lang, "digest-gainer", user = format!("<@{id}>"), xp = xp.to_string())))
+        .collect();
+
+    let mut levels: HashMap<u64, i64> = HashMap::new();
+    let mut prestiges = vec![];
+    for entry in history.iter().filter(|x| category.is_none() && in_window(x.timestamp)) {
+        match &entry.event {
+            audit::Event::LevelChanged { user_id, old_lvl, new_lvl, .. } if new_lvl > old_lvl && members.contains(user_id) => {
+                let best = levels.entry(*user_id).or_default();
+                *best = (*best).max(*new_lvl);
+            },
+            audit::Event::Prestiged { title, player, .. } if members.contains(&player.user_id) =>
+                prestiges.push(tr!(lang, "digest-prestige",
+                                   user = format!("<@{}>", player.user_id),
+                                   prestige = format!("{:.2}", player.prestige),
+                                   title = title.as_str())),
+            _ => {},
+        }
+    }
+    let mut levels = levels.into_iter().collect::<Vec<_>>();
+    levels.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let levels = levels.iter()
+        .take(SECTION_LENGTH)
+        .map(|(id, lvl)| tr!(lang, "digest-level", user = format!("<@{id}>"), lvl = *lvl))
+        .collect();
+
+    achievements.sort_by(|a, b| b.effective_xp.cmp(&a.effective_xp).then(a.id.cmp(&b.id)));
+    let notable = achievements.iter()
+        .take(SECTION_LENGTH)
+        .map(|x| tr!(lang, "digest-achievement",
+                     title = x.title.as_str(),
+                     user = format!("<@{}>", x.recipient_id),
+                     xp = x.effective_xp.to_string(),
+                     granter = format!("<@{}>", x.granter_id)))
+        .collect();
+
+    let fields = [
+        (tr!(lang, "digest-gainers"), section(gainers)),
+        (tr!(lang, "digest-levels"), section(levels)),
+        (tr!(lang, "digest-prestiges"), section(prestiges.into_iter().take(SECTION_LENGTH).collect())),
+        (tr!(lang, "digest-achievements"), section(notable)),
+    ].into_iter()
+        .filter_map(|(name, value)| value.map(|x| (name, x, false)))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None
+    }
+
+    let title = match schedule.period {
+        DigestPeriod::Daily => tr!(lang, "digest-daily"),
+        DigestPeriod::Weekly => tr!(lang, "digest-weekly"),
+    };
+    let mut description = tr!(lang, "digest-range",
+                              start = format!("<t:{}:f>", start.timestamp()),
+                              end = format!("<t:{}:f>", end.timestamp()));
+    if let Some(category) = category {
+        description = format!("{description}\n{}", tr!(lang, "digest-category", category = category));
+    }
+    Some(serenity::CreateEmbed::new()
+         .title(title)
+         .description(description)
+         .fields(fields)
+         .timestamp(end))
+}
+
+/// The times of every digest that's due but hasn't been posted, oldest first.
+fn missed_slots(schedule: &DigestSchedule, last_posted: DateTime<Utc>, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut slots = vec![];
+    let mut slot = schedule.latest_slot(now);
+    while slot > last_posted && slots.len() < MAX_CATCH_UP {
+        slots.push(slot);
+        slot -= schedule.length();
+    }
+    slots.reverse();
+    slots
+}
+
+/// Records that a server's digest for `slot` has been dealt with.
+///
+/// Reloads the save file first, with the [lock](file_management::Lock)
+/// held, so nothing saved in the meantime is lost.
+async fn mark_posted(lock: &file_management::Lock, guild_id: u64, slot: DateTime<Utc>) -> Result<(), Error> {
+    let _lock = lock.lock().await;
+    let mut data = file_management::load()?;
+    data.digests_posted.insert(guild_id, slot);
+    file_management::save(&data)
+}
+
+/// Posts every digest that's due.
+async fn post_due(http: &serenity::Http, lock: &file_management::Lock) -> Result<(), Error> {
+    let data = file_management::load()?;
+    let now = Utc::now();
+    let mut history = None;
+
+    for (guild_id, config) in &data.guild_configs {
+        let Some(schedule) = &config.digest else {
+            continue
+        };
+        // Servers that have never had a digest start from the next one, rather than catching up.
+        let Some(last_posted) = data.digests_posted.get(guild_id) else {
+            mark_posted(lock, *guild_id, schedule.latest_slot(now)).await?;
+            continue
+        };
+
+        let lang = config.locale.as_deref().and_then(i18n::supported).unwrap_or(i18n::DEFAULT_LOCALE);
+        for slot in missed_slots(schedule, *last_posted, now) {
+            let history = history.get_or_insert_with(audit::load);
+            let Some(embed) = build(&data, history, *guild_id, schedule, slot - schedule.length(), slot, lang) else {
+                tracing::debug!(guild_id, %slot, "Nothing happened, so skipping digest");
+                mark_posted(lock, *guild_id, slot).await?;
+                continue
+            };
+
+            let message = serenity::CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(serenity::CreateAllowedMentions::new());
+            // If posting fails, it's tried again on the next check.
+            if let Err(e) = serenity::ChannelId::new(schedule.channel_id).send_message(http, message).await {
+                tracing::warn!(guild_id, channel_id = schedule.channel_id, error = %e, "Failed to post digest");
+                break
+            }
+            tracing::info!(guild_id, %slot, "Posted digest");
+            mark_posted(lock, *guild_id, slot).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Starts posting digests in the background, forever.
+pub fn spawn_scheduler(http: Arc<serenity::Http>, lock: file_management::Lock) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = post_due(&http, &lock).await {
+                tracing::error!(error = %e, "Failed to post digests");
+            }
+        }
+    });
+}
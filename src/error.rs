@@ -0,0 +1,120 @@
+//! The error type used by every command.
+//!
+//! Errors that reach Poise are handled by [`on_error`], which logs them and
+//! sends the user a short, friendly, ephemeral message instead of failing silently.
+
+use crate::{ Data, serenity, metrics, i18n::{self, tr} };
+
+/// Everything that can go wrong while running a command.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+
+    /// Discord rejected a request, or couldn't be reached.
+    ///
+    /// Boxed, as Serenity's errors are much bigger than any of the others.
+    #[error("Discord error: {0}")]
+    Discord(Box<serenity::Error>),
+
+    /// A file couldn't be read or written.
+    #[error("File error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The save file couldn't be read or written as JSON.
+    #[error("Save file is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Some players share the same ID, so the data can't be saved safely.
+    #[error("Refusing to save duplicate player IDs: {0:?}")]
+    DuplicatePlayers(Vec<u64>),
+
+    /// A player wasn't in the save file, even though they should have been.
+    #[error("Player {0} could not be found in the save file")]
+    PlayerNotFound(u64),
+
+    /// A button was pressed that the command didn't know how to handle.
+    #[error("Unknown button ID: {0}")]
+    UnknownButton(String),
+
+    /// The command belongs to a [feature](crate::guild_config::Features) this server has turned off.
+    #[error("Feature is disabled in this server")]
+    FeatureDisabled,
+
+    /// Somebody without permission tried to use an admin command.
+    #[error("User is not an admin")]
+    NotAdmin,
+
+    /// Somebody who doesn't own the bot tried to change a player's data.
+    #[error("User is not an owner")]
+    NotOwner,
+}
+
+impl From<serenity::Error> for Error {
+    fn from(e: serenity::Error) -> Error {
+        Error::Discord(Box::new(e))
+    }
+}
+
+impl Error {
+
+    /// A message which is safe to show to the user who ran the command, in their language.
+    pub fn user_message(&self, lang: &str) -> String {
+        match self {
+            Error::Discord(_) => tr!(lang, "error-discord"),
+            Error::Io(_) | Error::Json(_) | Error::DuplicatePlayers(_) => tr!(lang, "error-save-data"),
+            Error::PlayerNotFound(_) => tr!(lang, "error-player-not-found"),
+            Error::UnknownButton(_) => tr!(lang, "error-unknown-button"),
+            Error::FeatureDisabled => tr!(lang, "error-feature-disabled"),
+            Error::NotAdmin => tr!(lang, "error-not-admin"),
+            Error::NotOwner => tr!(lang, "error-not-owner"),
+        }
+    }
+}
+
+/// Handles any error returned by a command, or raised by Poise.
+///
+/// Errors from inside commands are logged, and the user is sent an ephemeral
+/// explanation. Everything else (missing permissions, bad arguments, etc.)
+/// is passed on to Poise's [default handler](poise::builtins::on_error).
+pub async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    if let Some(ctx) = error.ctx() {
+        let outcome = if matches!(error, poise::FrameworkError::Command { .. }) { "error" } else { "rejected" };
+        let started = ctx.invocation_data::<std::time::Instant>().await.map(|x| *x);
+        metrics::command_finished(&ctx.command().qualified_name, outcome, started);
+    }
+
+    match error {
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            tracing::error!(
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.get(),
+                error = %error,
+                "Command failed",
+            );
+            let reply = poise::CreateReply::default()
+                .content(format!(":warning: {}", error.user_message(i18n::locale(ctx))))
+                .ephemeral(true);
+            if let Err(e) = ctx.send(reply).await {
+                tracing::error!(error = %e, "Failed to send error message");
+            }
+        },
+        poise::FrameworkError::CommandCheckFailed { error: Some(error), ctx, .. } => {
+            tracing::info!(
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.get(),
+                reason = %error,
+                "Command refused",
+            );
+            let reply = poise::CreateReply::default()
+                .content(format!(":no_entry: {}", error.user_message(i18n::locale(ctx))))
+                .ephemeral(true);
+            if let Err(e) = ctx.send(reply).await {
+                tracing::error!(error = %e, "Failed to send error message");
+            }
+        },
+        other => {
+            if let Err(e) = poise::builtins::on_error(other).await {
+                tracing::error!(error = %e, "Error while handling error");
+            }
+        },
+    }
+}
@@ -1,46 +1,77 @@
 use crate::player_data::Player;
+use crate::{Error, metrics::METRICS};
 use std::fs;
 use std::hash::Hash;
 use std::collections::HashSet;
 use crate::json_data::FileFormat;
+use crate::Data;
 
-const FILENAME: &str = "saved_data.json";
+pub const FILENAME: &str = "saved_data.json";
 
-/// Saves a vector of Player to file.
+/// Lets only one thing at a time load, change and save the file.
 ///
-/// Panics if the vector it has to save has duplicate objects.
-/// This should never happen and would break everything if it did.
-pub fn save(data: &FileFormat) {
-    let j = serde_json::to_string(data).expect("Failed to convert to JSON");
-    fs::write(FILENAME,j).expect("Failed to save file");
+/// Hold it from [`load()`] until [`save()`], or anything saved by somebody
+/// else in between is overwritten. It can't be taken twice at once, so never
+/// take it while already holding it.
+pub type Lock = std::sync::Arc<tokio::sync::Mutex<()>>;
+
+impl Data {
 
+    /// Waits for, and takes, the [`Lock`] on the file.
+    pub async fn lock_file(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.file_lock.lock().await
+    }
 }
 
-pub fn save_players(players: &Vec<Player>) {
+/// Saves all data to file.
+///
+/// Returns an error, and saves nothing, if there are any duplicate players.
+/// This should never happen and would break everything if it did.
+pub fn save(data: &FileFormat) -> Result<(), Error> {
+    let ids = data.player_list.iter().map(|x| x.user_id).collect::<Vec<_>>();
+    if !no_unique_elements(ids.iter()) {
+        let mut seen = HashSet::new();
+        let duplicates = ids.into_iter().filter(|x| !seen.insert(*x)).collect();
+        return Err(Error::DuplicatePlayers(duplicates))
+    }
+
+    let timer = METRICS.save_duration.start_timer();
+    let j = serde_json::to_string(data)?;
+    // Written next to the file and then moved over it, so that nothing
+    // reading the file without the lock ever sees half of it.
+    let temp = format!("{FILENAME}.tmp");
+    fs::write(&temp, &j)?;
+    fs::rename(&temp, FILENAME)?;
+    timer.observe_duration();
 
-    // crash if there are any duplicate IDs
-    assert!(no_unique_elements(&players.iter().map(|x| x.user_id).collect::<Vec<_>>()));
+    METRICS.save_file_size.set(j.len() as i64);
+    METRICS.players.set(data.player_list.len() as i64);
+    Ok(())
+}
 
+/// Saves a vector of Player to file, keeping everything else as it is.
+pub fn save_players(players: &[Player]) -> Result<(), Error> {
     // load existing data with which to overwrite players
-    let mut existing_data = load();
-    existing_data.player_list = players.clone();
+    let mut existing_data = load()?;
+    existing_data.player_list = players.to_vec();
     save(&existing_data)
 }
 
-/// Loads a vector of Player from the file.
+/// Loads all data from the file.
 ///
-/// If the file doesn't exist, just return an empty vector.
-pub fn load() -> FileFormat {
-    let data = fs::read_to_string(FILENAME);
-    if data.is_err() {
-        return FileFormat::new()
-    }
+/// If the file doesn't exist, just return empty data.
+pub fn load() -> Result<FileFormat, Error> {
+    let data = match fs::read_to_string(FILENAME) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(FileFormat::new()),
+        Err(e) => return Err(e.into()),
+    };
 
-    serde_json::from_str(data.unwrap().as_str()).expect("Failed to parse JSON - invalid format")
+    Ok(serde_json::from_str(data.as_str())?)
 }
 
-pub fn load_players() -> Vec<Player> {
-    load().player_list
+pub fn load_players() -> Result<Vec<Player>, Error> {
+    Ok(load()?.player_list)
 }
 
 
@@ -0,0 +1,188 @@
+//! Translations of everything the bot says, using [Fluent](https://projectfluent.org/).
+//!
+//! Messages live in `translations/<locale>.ftl`, and are built into the
+//! binary. Use [`tr!`] to look one up:
+//!
+//! ```text
+//! let lang = i18n::locale(ctx);
+//! tr!(lang, "level-up", name = "Bob", lvl = 5)
+//! ```
+//!
+//! The language is chosen by [`locale()`]: the user's own choice (from
+//! `/language`), then the server's choice (from `/config`), then the language
+//! the user's Discord client is set to, and finally English. Anything missing
+//! from a translation falls back to English.
+//!
+//! Slash command names and descriptions are translated by
+//! [`localize_commands()`], from messages named `cmd-<function name>`.
+
+use crate::{ Context, Data, Error, serenity };
+use fluent::{ FluentArgs, FluentResource, concurrent::FluentBundle };
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// The language used when nothing else is chosen, or a message is missing.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Every supported language, with its Fluent source.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../translations/en.ftl")),
+    ("es", include_str!("../translations/es.ftl")),
+];
+
+/// The languages users and servers can choose from.
+pub const LOCALES: [&str; 2] = ["en", "es"];
+
+/// Discord's names for each supported language, used for command localizations.
+const DISCORD_LOCALES: [(&str, &[&str]); 1] = [
+    ("es", &["es-ES", "es-419"]),
+];
+
+/// Every language's messages, loaded the first time they're needed.
+static TRANSLATIONS: LazyLock<HashMap<&'static str, FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    CATALOGS.iter().map(|(locale, source)| {
+        let resource = FluentResource::try_new(source.to_string())
+            .unwrap_or_else(|(resource, errors)| {
+                tracing::error!(locale, ?errors, "Invalid translations, so some messages will be missing");
+                resource
+            });
+        let mut bundle = FluentBundle::new_concurrent(vec![locale.parse().expect("Locale names are valid")]);
+        // Discord doesn't need the invisible characters Fluent puts around arguments by default.
+        bundle.set_use_isolating(false);
+        if let Err(errors) = bundle.add_resource(resource) {
+            tracing::error!(locale, ?errors, "Duplicate translations");
+        }
+        (*locale, bundle)
+    }).collect()
+});
+
+/// Looks up a message in a single language.
+fn format(locale: &str, id: &str, attribute: Option<&str>, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = TRANSLATIONS.get(locale)?;
+    let message = bundle.get_message(id)?;
+    let pattern = match attribute {
+        Some(attribute) => message.get_attribute(attribute)?.value(),
+        None => message.value()?,
+    };
+    let mut errors = vec![];
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        tracing::warn!(locale, id, ?errors, "Problem formatting translation");
+    }
+    Some(text.into_owned())
+}
+
+/// Looks up a message, falling back to English, then to the message ID itself.
+///
+/// Usually called through [`tr!`].
+pub fn get(locale: &str, id: &str, args: Option<&FluentArgs>) -> String {
+    format(locale, id, None, args)
+        .or_else(|| format(DEFAULT_LOCALE, id, None, args))
+        .unwrap_or_else(|| {
+            tracing::warn!(id, "Missing translation");
+            id.to_string()
+        })
+}
+
+/// Looks up a translated message, with optional arguments.
+///
+/// `tr!(lang, "title-none")` or `tr!(lang, "level-gained", name = value, ...)`.
+macro_rules! tr {
+    ($locale:expr, $id:literal) => {
+        $crate::i18n::get($locale, $id, None)
+    };
+    ($locale:expr, $id:literal, $($arg:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent::FluentArgs::new();
+        $( args.set(stringify!($arg), $value); )+
+        $crate::i18n::get($locale, $id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+/// The supported language that best matches a locale, e.g. `es-ES` gives `es`.
+pub fn supported(locale: &str) -> Option<&'static str> {
+    let language = locale.split(['-', '_']).next()?.to_lowercase();
+    LOCALES.iter().find(|x| **x == language).copied()
+}
+
+impl Data {
+
+    /// The language a user has chosen with `/language`, if any.
+    pub fn user_locale(&self, user_id: u64) -> Option<String> {
+        self.user_locales.read().unwrap_or_else(|e| e.into_inner()).get(&user_id).cloned()
+    }
+
+    /// Updates the cached language for a user. `None` goes back to automatic.
+    pub fn cache_user_locale(&self, user_id: u64, locale: Option<String>) {
+        let mut locales = self.user_locales.write().unwrap_or_else(|e| e.into_inner());
+        match locale {
+            Some(locale) => locales.insert(user_id, locale),
+            None => locales.remove(&user_id),
+        };
+    }
+}
+
+/// The language to reply to a command in.
+pub fn locale(ctx: Context<'_>) -> &'static str {
+    resolve(ctx.data(), ctx.author().id.get(), ctx.guild_id(), ctx.locale())
+}
+
+/// The language to reply to a user in: their chosen language, then the
+/// server's, then their Discord's. Used by [`locale()`], and for button
+/// presses that don't come from a command.
+pub fn resolve(data: &Data, user_id: u64, guild_id: Option<serenity::GuildId>, discord_locale: Option<&str>) -> &'static str {
+    data.user_locale(user_id)
+        .or_else(|| data.guild_config(guild_id).locale)
+        .or_else(|| discord_locale.map(str::to_string))
+        .and_then(|x| supported(&x))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Adds translated names and descriptions to every slash command, and their parameters.
+///
+/// For a command defined by the function `level`, with a parameter `user`:
+///
+/// ```text
+/// cmd-level = nivel
+///     .description = Consulta tu XP, nivel y prestigio.
+///     .user = usuario
+///     .user-description = El usuario a consultar
+/// ```
+pub fn localize_commands(commands: &mut [poise::Command<Data, Error>]) {
+    for command in commands {
+        let id = format!("cmd-{}", command.identifying_name);
+        for (locale, discord_locales) in DISCORD_LOCALES {
+            for discord_locale in discord_locales.iter().map(|x| x.to_string()) {
+                if let Some(name) = format(locale, &id, None, None) {
+                    command.name_localizations.insert(discord_locale.clone(), name);
+                }
+                if let Some(description) = format(locale, &id, Some("description"), None) {
+                    command.description_localizations.insert(discord_locale.clone(), description);
+                }
+                for parameter in &mut command.parameters {
+                    // `.description` is the command's own description, so a parameter
+                    // called `description` keeps its name, and only has its description translated.
+                    if parameter.name != "description"
+                        && let Some(name) = format(locale, &id, Some(&parameter.name), None) {
+                        parameter.name_localizations.insert(discord_locale.clone(), name);
+                    }
+                    if let Some(description) = format(locale, &id, Some(&format!("{}-description", parameter.name)), None) {
+                        parameter.description_localizations.insert(discord_locale.clone(), description);
+                    }
+                }
+            }
+        }
+        localize_commands(&mut command.subcommands);
+    }
+}
+
+/// Reads a language picked by a user: one of [`LOCALES`], or `auto` (or `none`) to stop choosing one.
+///
+/// Returns the value back as the error if it isn't supported.
+pub fn parse_choice(value: &str) -> Result<Option<&'static str>, String> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("auto") || value.eq_ignore_ascii_case("none") {
+        return Ok(None)
+    }
+    LOCALES.iter().find(|x| x.eq_ignore_ascii_case(value)).copied().map(Some).ok_or(value.to_string())
+}
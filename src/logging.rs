@@ -0,0 +1,75 @@
+//! Sets up logging, using [`tracing`].
+//!
+//! Logging is configured with environment variables (which can also go in `.env`):
+//!
+//! - `LOG_LEVEL` - which logs to show, in [`EnvFilter`] syntax, e.g. `info` or
+//!   `trophies=debug,serenity=warn`. Defaults to `info`.
+//! - `LOG_FORMAT` - either `pretty` (the default) or `json`.
+//! - `LOG_DIR` - if set, logs are also written to files in this folder.
+//! - `LOG_ROTATION` - how often a new log file is started: `daily` (the default),
+//!   `hourly`, or `never`.
+
+use crate::Context;
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter, Layer};
+
+/// Starts logging.
+///
+/// If logs are being written to a file, this returns a guard which must be
+/// kept alive for as long as the bot runs - dropping it stops the file
+/// from being written to.
+pub fn init() -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_env("LOG_LEVEL").unwrap_or_else(|_| EnvFilter::new("info"));
+    let json = std::env::var("LOG_FORMAT").is_ok_and(|x| x.eq_ignore_ascii_case("json"));
+
+    let (file_writer, guard) = match std::env::var("LOG_DIR") {
+        Ok(dir) => {
+            let appender = match std::env::var("LOG_ROTATION").unwrap_or_default().to_lowercase().as_str() {
+                "hourly" => rolling::hourly(dir, "trophies.log"),
+                "never" => rolling::never(dir, "trophies.log"),
+                _ => rolling::daily(dir, "trophies.log"),
+            };
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(writer), Some(guard))
+        },
+        Err(_) => (None, None),
+    };
+
+    let stdout_layer = if json {
+        fmt::layer().json().boxed()
+    } else {
+        fmt::layer().pretty().boxed()
+    };
+
+    // Files are never pretty-printed, as colours and multiple lines make them hard to search.
+    let file_layer = file_writer.map(|writer| {
+        if json {
+            fmt::layer().json().with_writer(writer).boxed()
+        } else {
+            fmt::layer().with_ansi(false).with_writer(writer).boxed()
+        }
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout_layer)
+        .with(file_layer)
+        .init();
+
+    guard
+}
+
+/// Creates a span for a single use of a command.
+///
+/// Everything logged while the command runs is tagged with the command's name,
+/// who used it and where, and the ID of the invocation, so that all the logs
+/// for one use of a command can be found together.
+pub fn command_span(ctx: Context<'_>) -> tracing::Span {
+    tracing::info_span!(
+        "command",
+        name = %ctx.command().qualified_name,
+        user_id = ctx.author().id.get(),
+        guild_id = ctx.guild_id().map(|x| x.get()),
+        invocation_id = ctx.id(),
+    )
+}
@@ -3,83 +3,158 @@ use dotenv::dotenv;
 use std::cmp;
 use serde::{Serialize, Deserialize};
 
+mod api;
+mod audit;
+mod bounties;
+mod challenges;
+mod cli;
 mod commands;
+mod config;
+mod digest;
+mod error;
 mod modules;
 mod file_management;
+mod i18n;
+mod logging;
+mod metrics;
+mod migrate;
+mod perks;
+mod shop;
+mod site;
 mod slash_commands;
+mod teams;
+mod titles;
+mod transfer;
+mod webhooks;
 
 use modules::player_data;
+use modules::achievement_data;
+use modules::admin_data;
+use modules::guild_config;
 use modules::json_data;
 use modules::functions;
+use error::Error;
 
-struct Data{} // user data, stored and accessible everywhere
-
-// define error and context
-type Error = Box<dyn std::error::Error + Send + Sync>;
-type Context<'a> = poise::Context<'a, Data, Error>;
-
-
-/// Reregister application commands with Discord.
-#[poise::command(slash_command, prefix_command)]
-async fn register(ctx: Context<'_>) -> Result<(), Error> {
-    // register_application_commands_buttons returns Result<(), Error> -
-    // the ? means that it will only await if the result is NOT error, and otherwise
-    // it will return the function, passing the error to the Result of the function
-    poise::builtins::register_application_commands_buttons(ctx).await?;
-    Ok(())
-}
+/// How long, by default, somebody has to undo an achievement they granted.
+const DEFAULT_UNDO_WINDOW_SECS: u64 = 60;
 
+// user data, stored and accessible everywhere
+struct Data {
+    /// How long the "Undo" button stays on an achievement.
+    ///
+    /// Set with the `UNDO_WINDOW_SECS` environment variable.
+    undo_window: std::time::Duration,
 
+    /// Channel that every [`audit`] event is mirrored to, if any.
+    ///
+    /// Set with the `AUDIT_CHANNEL_ID` environment variable.
+    audit_channel: Option<serenity::ChannelId>,
 
-/// Edit your existing titles
-#[poise::command(slash_command, prefix_command)]
-async fn update_title(ctx: Context<'_>) -> Result<(),Error> {
+    /// Every server's settings, by guild ID. Read with [`Data::guild_config()`].
+    guild_configs: std::sync::RwLock<std::collections::HashMap<u64, guild_config::GuildConfig>>,
 
-    let players = file_management::load_players();
-    let p = players.iter().find(|x| x.user_id == ctx.author().id.get()).expect("User not present in Players despite verification").clone();
+    /// Every user's chosen language, by user ID. Read with [`Data::user_locale()`].
+    user_locales: std::sync::RwLock<std::collections::HashMap<u64, String>>,
 
-    if p.title_segments.len() == 0 {
-        ctx.send(poise::CreateReply::default()
-                 .content("You do not have a title to edit.")
-                 .ephemeral(true)).await?;
-        return Ok(())
-    }
-    Ok(())
+    /// Taken around every change to `saved_data.json`. Take it with [`Data::lock_file()`].
+    file_lock: file_management::Lock,
 }
-                  
-
-
-
-
-
 
+// define context
+type Context<'a> = poise::Context<'a, Data, Error>;
 
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
+    // must be kept until the end of `main`, or file logging stops
+    let _log_guard = logging::init();
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if !args.is_empty() {
+        cli::run(&args).await;
+        return
+    }
+
     let token = std::env::var("DISCORD_TOKEN").expect("Missing DISCORD_TOKEN");
     let intents = serenity::GatewayIntents::non_privileged();
 
+    let mut commands = vec![
+        slash_commands::register(),
+        slash_commands::achievement(),
+        slash_commands::level(),
+        slash_commands::leaderboard(),
+        slash_commands::stats(),
+        slash_commands::compare(),
+        slash_commands::prestige(),
+        slash_commands::update_title(),
+        slash_commands::perks(),
+        slash_commands::shop(),
+        slash_commands::team(),
+        slash_commands::challenge(),
+        slash_commands::bounty(),
+        slash_commands::bounties(),
+        slash_commands::language(),
+        slash_commands::admin(),
+        slash_commands::config(),
+    ];
+    i18n::localize_commands(&mut commands);
+
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![
-                slash_commands::register(),
-                slash_commands::achievement(),
-                slash_commands::level(),
-                slash_commands::prestige(),
-            ],
+            commands,
+            pre_command: |ctx| Box::pin(async move {
+                logging::command_span(ctx).in_scope(|| tracing::info!("Command invoked"));
+                ctx.set_invocation_data(std::time::Instant::now()).await;
+            }),
+            post_command: |ctx| Box::pin(async move {
+                logging::command_span(ctx).in_scope(|| tracing::info!("Command finished"));
+                let started = ctx.invocation_data::<std::time::Instant>().await.map(|x| *x);
+                metrics::command_finished(&ctx.command().qualified_name, "success", started);
+            }),
+            command_check: Some(|ctx| Box::pin(config::check_feature(ctx))),
+            on_error: |error| Box::pin(error::on_error(error)),
+            event_handler: |ctx, event, _framework, data| Box::pin(challenges::handle_event(ctx, event, data)),
             ..Default::default()
 
         })
-        .setup(|_ctx, _ready, _framework| {
+        .setup(|ctx, _ready, _framework| {
             Box::pin(async move {
+                let file_lock = file_management::Lock::default();
+                digest::spawn_scheduler(ctx.http.clone(), file_lock.clone());
                 // poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                Ok(Data {})
+                let undo_window = std::env::var("UNDO_WINDOW_SECS")
+                    .ok()
+                    .and_then(|x| x.parse().ok())
+                    .unwrap_or(DEFAULT_UNDO_WINDOW_SECS);
+                let audit_channel = std::env::var("AUDIT_CHANNEL_ID")
+                    .ok()
+                    .and_then(|x| x.parse().ok())
+                    .map(serenity::ChannelId::new);
+                challenges::spawn_expirer(ctx.http.clone(), audit_channel, file_lock.clone());
+                bounties::spawn_expirer(ctx.http.clone(), audit_channel, file_lock.clone());
+                let (guild_configs, user_locales) = file_management::load()
+                    .map(|x| (x.guild_configs, x.user_locales))
+                    .unwrap_or_else(|e| {
+                        tracing::error!(error = %e, "Failed to load server settings, so using the defaults");
+                        Default::default()
+                    });
+                Ok(Data {
+                    undo_window: std::time::Duration::from_secs(undo_window),
+                    audit_channel,
+                    guild_configs: std::sync::RwLock::new(guild_configs),
+                    user_locales: std::sync::RwLock::new(user_locales),
+                    file_lock,
+                })
             })
         })
         .build();
 
+    metrics::spawn_server();
+    api::spawn_server();
+    webhooks::spawn_worker();
+
+    tracing::info!("Starting bot");
     let client = serenity::ClientBuilder::new(token, intents)
         .framework(framework)
         .await;
@@ -0,0 +1,132 @@
+//! Prometheus metrics, for keeping an eye on the bot.
+//!
+//! Metrics are always collected, but are only served over HTTP if
+//! `METRICS_ENABLED=true` is set. They're served at `/metrics`, on
+//! `METRICS_ADDR` (defaults to [`DEFAULT_METRICS_ADDR`], so only
+//! reachable from the same machine).
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+
+pub const DEFAULT_METRICS_ADDR: &str = "127.0.0.1:9091";
+
+/// Every metric the bot collects.
+pub struct Metrics {
+    registry: Registry,
+
+    /// Commands run, by `command` name and `outcome` (`success`, `error` or `rejected`).
+    pub commands: IntCounterVec,
+
+    /// How long each command took to run, in seconds, by `command` name.
+    pub command_latency: HistogramVec,
+
+    /// Total XP granted with [`/achievement`](crate::commands::achievement), after multipliers.
+    pub xp_granted: IntCounter,
+
+    /// Total levels gained by all players.
+    pub level_ups: IntCounter,
+
+    /// Total number of prestiges.
+    pub prestiges: IntCounter,
+
+    /// How long [`file_management::save()`](crate::file_management::save) takes, in seconds.
+    pub save_duration: Histogram,
+
+    /// Size of the save file, in bytes, as of the last save.
+    pub save_file_size: IntGauge,
+
+    /// Number of players in the save file, as of the last save.
+    pub players: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        let registry = Registry::new_custom(Some("trophies".to_string()), None)
+            .expect("Metric prefix is valid");
+
+        let metrics = Metrics {
+            commands: IntCounterVec::new(Opts::new("commands_total", "Commands run, by name and outcome"), &["command", "outcome"])
+                .expect("Metric is valid"),
+            command_latency: HistogramVec::new(HistogramOpts::new("command_duration_seconds", "Time taken to run each command"), &["command"])
+                .expect("Metric is valid"),
+            xp_granted: IntCounter::new("xp_granted_total", "XP granted by achievements, after multipliers")
+                .expect("Metric is valid"),
+            level_ups: IntCounter::new("level_ups_total", "Levels gained by all players")
+                .expect("Metric is valid"),
+            prestiges: IntCounter::new("prestiges_total", "Number of prestiges")
+                .expect("Metric is valid"),
+            save_duration: Histogram::with_opts(HistogramOpts::new("save_duration_seconds", "Time taken to save the save file")
+                                                .buckets(vec![0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0]))
+                .expect("Metric is valid"),
+            save_file_size: IntGauge::new("save_file_bytes", "Size of the save file as of the last save")
+                .expect("Metric is valid"),
+            players: IntGauge::new("players", "Number of players as of the last save")
+                .expect("Metric is valid"),
+            registry,
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 8] = [
+            Box::new(metrics.commands.clone()),
+            Box::new(metrics.command_latency.clone()),
+            Box::new(metrics.xp_granted.clone()),
+            Box::new(metrics.level_ups.clone()),
+            Box::new(metrics.prestiges.clone()),
+            Box::new(metrics.save_duration.clone()),
+            Box::new(metrics.save_file_size.clone()),
+            Box::new(metrics.players.clone()),
+        ];
+        for c in collectors {
+            metrics.registry.register(c).expect("Metrics are only registered once");
+        }
+
+        metrics
+    }
+
+    /// Renders every metric in Prometheus' text format.
+    pub fn render(&self) -> String {
+        let mut buffer = vec![];
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!(error = %e, "Failed to encode metrics");
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// The bot's metrics. Created the first time they're used.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Records that a command finished, and how long it took.
+pub fn command_finished(command: &str, outcome: &str, started: Option<std::time::Instant>) {
+    METRICS.commands.with_label_values(&[command, outcome]).inc();
+    if let Some(started) = started {
+        METRICS.command_latency.with_label_values(&[command]).observe(started.elapsed().as_secs_f64());
+    }
+}
+
+/// Starts the metrics server, if `METRICS_ENABLED` is set.
+///
+/// Runs in the background on the current tokio runtime.
+pub fn spawn_server() {
+    if !std::env::var("METRICS_ENABLED").is_ok_and(|x| x == "true" || x == "1") {
+        return
+    }
+    let addr = std::env::var("METRICS_ADDR").unwrap_or_else(|_| DEFAULT_METRICS_ADDR.to_string());
+
+    let app = axum::Router::new().route("/metrics", axum::routing::get(|| async { METRICS.render() }));
+
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(&addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(addr, error = %e, "Failed to start metrics server");
+                return
+            }
+        };
+        tracing::info!(addr, "Serving metrics");
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "Metrics server stopped");
+        }
+    });
+}
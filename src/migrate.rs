@@ -0,0 +1,210 @@
+//! Brings players over from other leveling bots.
+//!
+//! Takes a leaderboard exported from another bot, and gives each user the
+//! same total XP here, by replaying it through
+//! [`add_xp()`](Player::add_xp) and [`lvl_check()`](Player::lvl_check).
+//! Players end up at whatever level that XP is worth on trophies' own curve,
+//! which is usually quite different from their old level.
+//!
+//! Understands:
+//! - MEE6's leaderboard JSON, i.e. `{"players": [{"id": "...", "xp": ..., "level": ...}]}`.
+//! - A JSON array of objects, in the same shape.
+//! - A CSV file with a header row.
+//!
+//! Columns are matched by name, ignoring case, with spaces treated as
+//! underscores: the ID can be `id`, `user_id`, `userid` or `member_id`, and XP
+//! can be `xp`, `total_xp`, `exp`, `experience` or `points`. If a file only has
+//! levels (`level` or `lvl`), the total XP is worked out using MEE6's curve,
+//! which most leveling bots copy.
+
+use crate::{ player_data::Player, transfer::{self, Change} };
+use serde_json::Value;
+
+/// Highest old level that will be converted, so the XP can't overflow.
+const MAX_LEVEL: i64 = 100_000;
+
+const ID_FIELDS: [&str; 4] = ["id", "user_id", "userid", "member_id"];
+const XP_FIELDS: [&str; 5] = ["xp", "total_xp", "exp", "experience", "points"];
+const LEVEL_FIELDS: [&str; 2] = ["level", "lvl"];
+const NAME_FIELDS: [&str; 4] = ["username", "name", "display_name", "tag"];
+
+/// A user's progress in another bot.
+pub struct OldProgress {
+    pub user_id: u64,
+    pub name: Option<String>,
+    pub level: Option<i64>,
+    pub xp: Option<i128>,
+}
+
+impl OldProgress {
+
+    /// The user's total XP in the old bot.
+    ///
+    /// If the file only had their level, this is the XP MEE6 requires to reach it.
+    pub fn total_xp(&self) -> i128 {
+        self.xp.unwrap_or_else(|| mee6_total_xp(self.level.unwrap_or(0)))
+    }
+
+    /// Makes a brand new [`Player`] with the same total XP.
+    pub async fn to_player(&self) -> Player {
+        let mut p = Player::new(self.user_id);
+        p.add_xp(self.total_xp());
+        p.lvl_check(None).await;
+        p
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.xp.is_none() && self.level.is_none() {
+            return Err(format!("User {} has neither XP nor a level", self.user_id))
+        }
+        if self.xp.is_some_and(|x| x < 0) {
+            return Err(format!("User {} has negative XP", self.user_id))
+        }
+        if self.level.is_some_and(|x| !(0..=MAX_LEVEL).contains(&x)) {
+            return Err(format!("User {} has a level outside 0 to {MAX_LEVEL}", self.user_id))
+        }
+        Ok(())
+    }
+}
+
+/// Total XP needed to reach a level with MEE6.
+///
+/// Going from level `l` to `l + 1` takes `5l² + 50l + 100` XP.
+pub fn mee6_total_xp(level: i64) -> i128 {
+    let l = level.clamp(0, MAX_LEVEL) as i128;
+    5 * (l - 1) * l * (2 * l - 1) / 6 + 25 * l * (l - 1) + 100 * l
+}
+
+/// Reads a number that might be written as a string, as Discord IDs often are.
+fn number<T: std::str::FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Number(n) => n.to_string().parse().ok(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Lowercases a column name, and turns spaces and dashes into underscores.
+fn normalise(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Finds the first of `names` in a set of `(column, value)` pairs.
+fn field<'a>(fields: &'a [(String, Value)], names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|name| fields.iter().find(|(k, _)| k == name).map(|(_, v)| v))
+}
+
+/// Turns one row or object of a file into an [`OldProgress`].
+fn progress(fields: &[(String, Value)], row: usize) -> Result<OldProgress, String> {
+    let user_id = field(fields, &ID_FIELDS)
+        .and_then(number)
+        .ok_or(format!("Row {row} has no valid user ID"))?;
+    let bad = |name: &str| format!("Row {row} has an invalid {name}");
+
+    let xp = match field(fields, &XP_FIELDS) {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) if s.trim().is_empty() => None,
+        Some(v) => Some(number(v).ok_or(bad("XP"))?),
+    };
+    let level = match field(fields, &LEVEL_FIELDS) {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) if s.trim().is_empty() => None,
+        Some(v) => Some(number(v).ok_or(bad("level"))?),
+    };
+    let name = field(fields, &NAME_FIELDS).and_then(Value::as_str).map(str::to_string);
+
+    let progress = OldProgress { user_id, name, level, xp };
+    progress.validate()?;
+    Ok(progress)
+}
+
+fn parse_json(content: &str) -> Result<Vec<OldProgress>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {e}"))?;
+    let list = match &value {
+        Value::Array(x) => x,
+        Value::Object(x) => match x.get("players") {
+            Some(Value::Array(x)) => x,
+            _ => return Err("Expected a list of players, or an object with a `players` list".to_string()),
+        },
+        _ => return Err("Expected a list of players".to_string()),
+    };
+
+    list.iter().enumerate().map(|(i, x)| {
+        let Value::Object(object) = x else {
+            return Err(format!("Row {} isn't an object", i + 1))
+        };
+        let fields = object.iter().map(|(k, v)| (normalise(k), v.clone())).collect::<Vec<_>>();
+        progress(&fields, i + 1)
+    }).collect()
+}
+
+fn parse_csv(content: &str) -> Result<Vec<OldProgress>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers()
+        .map_err(|e| format!("Invalid CSV: {e}"))?
+        .iter()
+        .map(normalise)
+        .collect::<Vec<_>>();
+
+    reader.records().enumerate().map(|(i, record)| {
+        let record = record.map_err(|e| format!("Invalid CSV on row {}: {e}", i + 1))?;
+        let fields = headers.iter().cloned()
+            .zip(record.iter().map(|x| Value::String(x.to_string())))
+            .collect::<Vec<_>>();
+        progress(&fields, i + 1)
+    }).collect()
+}
+
+/// Reads another bot's leaderboard, in the given format.
+///
+/// Rejects the whole file if any row can't be understood, or if a user
+/// appears more than once.
+pub fn parse(content: &str, format: transfer::Format) -> Result<Vec<OldProgress>, String> {
+    let list = match format {
+        transfer::Format::Json => parse_json(content)?,
+        transfer::Format::Csv => parse_csv(content)?,
+    };
+    if !crate::file_management::no_unique_elements(list.iter().map(|x| x.user_id)) {
+        return Err("The file contains the same user more than once".to_string())
+    }
+    Ok(list)
+}
+
+/// What migrating a single user would do.
+pub struct Preview {
+    pub old: OldProgress,
+    pub change: Change,
+}
+
+impl Preview {
+
+    /// A line showing the user's level before and after, for a dry run.
+    pub fn describe(&self) -> String {
+        let name = match &self.old.name {
+            Some(name) => format!("{} ({name})", self.old.user_id),
+            None => self.old.user_id.to_string(),
+        };
+        let before = match self.old.level {
+            Some(lvl) => format!("Lv. {lvl}, {} XP", self.old.total_xp()),
+            None => format!("{} XP", self.old.total_xp()),
+        };
+        let after = match &self.change {
+            Change::New(p) => format!("Lv. {} {}", p.lvl, p.xp_progress()),
+            Change::Updated { before, after } =>
+                format!("Lv. {} {}, replacing Lv. {} {}", after.lvl, after.xp_progress(), before.lvl, before.xp_progress()),
+            Change::Unchanged(_) => "no change".to_string(),
+            Change::Skipped(_) => "skipped, as they already have progress here".to_string(),
+        };
+        format!("{name}: {before} → {after}")
+    }
+}
+
+/// Works out what migrating some users would do, without changing anything.
+pub async fn plan(existing: &[Player], old: Vec<OldProgress>, strategy: transfer::Strategy) -> Vec<Preview> {
+    let mut players = vec![];
+    for x in &old {
+        players.push(x.to_player().await);
+    }
+    let changes = transfer::plan_players(existing, players, strategy);
+    old.into_iter().zip(changes).map(|(old, change)| Preview { old, change }).collect()
+}
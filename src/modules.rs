@@ -3,12 +3,19 @@ pub mod player_data {
         Serialize,
         Deserialize,
         Context,
+        Error,
         serenity,
         file_management,
         functions,
+        audit,
+        achievement_data::Medal,
+        i18n::{self, tr},
+        perks::{self, Effect},
+        shop,
         cmp
     };
     use functions::Overflows;
+    use std::collections::BTreeMap;
 
 
     /// The amount by which the XP threshold is multiplied by prestige
@@ -28,7 +35,7 @@ pub mod player_data {
     /// using [`file_management::save()`]. Whenever you access
     /// the vector created by [`load()`](file_management::load()),
     /// use [`verify_player()`](verify_player) first, to ensure that the
-    /// player is present - otherwise it will panic.
+    /// player is present - otherwise you'll get [`Error::PlayerNotFound`].
     #[derive(Serialize,Deserialize,Clone)]
     #[non_exhaustive]
     pub struct Player {
@@ -73,6 +80,79 @@ pub mod player_data {
         /// Starts at 10.0, and increases to current level whenever
         /// the player prestiges.
         pub prestige_threshold: i64,
+
+        /// Perk points that haven't been spent yet.
+        ///
+        /// Earned by prestiging, and spent with [`/perks buy`](crate::commands::perks_buy).
+        #[serde(default)]
+        pub perk_points: u32,
+
+        /// The rank of every [perk](crate::perks) the player has bought, by perk ID.
+        #[serde(default)]
+        pub perks: BTreeMap<String, u32>,
+
+        /// Coins to spend in the [shop](crate::shop). Never reset by prestiging.
+        #[serde(default)]
+        pub coins: u64,
+
+        /// The IDs of every [shop item](crate::shop::Item) the player owns.
+        #[serde(default)]
+        pub inventory: Vec<String>,
+
+        /// The IDs of the items in the player's [`inventory`](Self::inventory) that they're showing off.
+        #[serde(default)]
+        pub equipped: Vec<String>,
+
+        /// How many of each [medal](crate::achievement_data::Medal) the player's achievements have earned.
+        #[serde(default)]
+        pub medals: MedalTally,
+    }
+
+    /// A count of the medals a player has won, like a row of an Olympic medal table.
+    #[derive(Serialize,Deserialize,Clone,Copy,Default,PartialEq,Eq,PartialOrd,Ord)]
+    #[serde(default)]
+    pub struct MedalTally {
+        pub gold: u32,
+        pub silver: u32,
+        pub bronze: u32,
+    }
+
+    impl MedalTally {
+
+        /// Counts a new medal. 💩 isn't a medal, so it isn't counted.
+        pub fn add(&mut self, medal: Medal) {
+            if let Some(count) = self.count_mut(medal) {
+                *count = count.saturating_add(1);
+            }
+        }
+
+        /// Takes a medal away again, like when its achievement is revoked.
+        pub fn remove(&mut self, medal: Medal) {
+            if let Some(count) = self.count_mut(medal) {
+                *count = count.saturating_sub(1);
+            }
+        }
+
+        fn count_mut(&mut self, medal: Medal) -> Option<&mut u32> {
+            match medal {
+                Medal::Gold => Some(&mut self.gold),
+                Medal::Silver => Some(&mut self.silver),
+                Medal::Bronze => Some(&mut self.bronze),
+                Medal::Poo => None,
+            }
+        }
+
+        /// How many medals there are altogether.
+        pub fn total(&self) -> u32 {
+            self.gold.saturating_add(self.silver).saturating_add(self.bronze)
+        }
+    }
+
+    impl std::fmt::Display for MedalTally {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{} {}  {} {}  {} {}",
+                   Medal::Gold.emoji(), self.gold, Medal::Silver.emoji(), self.silver, Medal::Bronze.emoji(), self.bronze)
+        }
     }
 
     impl Player {
@@ -83,11 +163,28 @@ pub mod player_data {
             let mut output: String = "".to_owned();
             for i in &self.title_segments {
                 output.push_str(i);
-                output.push_str(" ");
+                output.push(' ');
             }
             output
         }
 
+        /// A player's name, with their level and title in front of it.
+        ///
+        /// e.g. `Lv. 12 Mighty Seed`, or `Lv. 12 「Mighty」 Seed` with a
+        /// [title frame](Self::title_frame) equipped.
+        pub fn name_line(&self, name: &str) -> String {
+            match self.title_frame() {
+                Some((left, right)) if !self.title_segments.is_empty() =>
+                    format!("Lv. {} {left}{}{right} {name}", self.lvl, self.title().trim_end()),
+                _ => format!("Lv. {} {}{}", self.lvl, self.title(), name),
+            }
+        }
+
+        /// How far through the current level the player is, e.g. `(43 / 50)`.
+        pub fn xp_progress(&self) -> String {
+            format!("({} / {})", self.xp, self.xp_threshold())
+        }
+
         /// Returns Discord user from Player
         ///
         /// Requires a `ctx` object in order to access Discord's servers.
@@ -104,9 +201,20 @@ pub mod player_data {
                 prestige: 1.0,
                 title_segments: vec![],
                 prestige_threshold: 10,
+                perk_points: 0,
+                perks: BTreeMap::new(),
+                coins: 0,
+                inventory: vec![],
+                equipped: vec![],
+                medals: MedalTally::default(),
             }
         }
 
+        /// The total of one kind of [perk](crate::perks) effect, from every perk the player has.
+        pub fn perk(&self, effect: Effect) -> f64 {
+            perks::tree().total(&self.perks, effect)
+        }
+
         /// Calculates how much XP you should earn, from a base number.
         ///
         /// The formula for the XP is:
@@ -114,20 +222,46 @@ pub mod player_data {
         /// xp * (1 + ((self.prestige - 1) * PRESTIGE_MULTIPLIER))
         /// ```
         ///
+        /// with `xp` first multiplied by any [`XpBonus`](Effect::XpBonus) perks.
         pub fn xp_change(&self, xp: i128) -> i128 {
-            println!("Calculating XP change");
-            match functions::overflow_check::<_,i128>(|| xp * self.prestige as i128) {
+            let bonus_xp = (xp as f64 * (1.0 + self.perk(Effect::XpBonus))) as i128;
+            let change = match functions::overflow_check::<_,i128>(|| bonus_xp * self.prestige as i128) {
                 Overflows::Panic => i128::MAX - self.xp,
-                Overflows::Float => xp * self.prestige as i128,
-                Overflows::Safe => (xp as f64 + (xp as f64 * ( self.prestige - 1.0 ) * XP_MULTIPLIER)) as i128,
-            }
+                Overflows::Float => bonus_xp * self.prestige as i128,
+                Overflows::Safe => (bonus_xp as f64 + (bonus_xp as f64 * ( self.prestige - 1.0 ) * XP_MULTIPLIER)) as i128,
+            };
+            tracing::trace!(user_id = self.user_id, base_xp = %xp, prestige = self.prestige, effective_xp = %change, "Calculated XP change");
+            change
         }
 
         /// Adds XP, calculated using [`xp_change`](Self::xp_change).
-        pub fn add_xp(&mut self, xp: i128) {
-            println!("Adding XP internally");
-            self.xp += self.xp_change(xp);
-            println!("XP added");
+        ///
+        /// Returns the XP that was actually added, so that it can be
+        /// [credited](crate::teams::credit) to the player's team too.
+        pub fn add_xp(&mut self, xp: i128) -> i128 {
+            let change = self.xp_change(xp);
+            self.xp += change;
+            tracing::debug!(user_id = self.user_id, base_xp = %xp, effective_xp = %change, new_xp = %self.xp, "Added XP");
+            change
+        }
+
+        /// Takes XP off the player, to be held by a [challenge](crate::challenges)
+        /// or a [bounty](crate::bounties). No multipliers are used.
+        ///
+        /// Held XP can only come out of the player's current level, so holding
+        /// it never costs a level. At least 1 XP has to be held, so it can't
+        /// be used to give the player XP. If they don't have enough, or `xp`
+        /// isn't positive, returns why, in the language `lang`.
+        pub fn hold_xp(&mut self, xp: i128, lang: &str) -> Result<(), String> {
+            if xp < 1 {
+                return Err(tr!(lang, "hold-not-positive"))
+            }
+            if self.xp < xp {
+                return Err(tr!(lang, "hold-not-enough-xp", user = format!("<@{}>", self.user_id),
+                               held = xp.to_string(), xp = self.xp.to_string()))
+            }
+            self.xp -= xp;
+            Ok(())
         }
 
         /// Checks how much XP you need to level up.
@@ -144,12 +278,10 @@ pub mod player_data {
         /// would make it basically impossible to prestige after your third prestige.
         /// (In one test, it required billions of XP to reach a single level past level
         /// 60, and it required reaching level 2000 to be able to prestige 😭)
-
         pub fn xp_threshold(&self) -> i128 {
-            // println!("Debug: Threshold for level {}: {}",level.unwrap_or(self.lvl),2^level.unwrap_or(self.lvl - 1));
-            // (50.0 * ((XP_EXPONENT).powf(level.unwrap_or(self.lvl - 1) as f64))) as i64
-
-            let threshold_calc = ||50 + (25.0 * XP_THRESHOLD_MULTIPLIER * (self.prestige - 1.0)) as i128;
+            // Perks can slow down how fast the threshold grows, but never make it shrink.
+            let growth = 1.0 - self.perk(Effect::ThresholdReduction).clamp(0.0, 1.0);
+            let threshold_calc = ||50 + (25.0 * XP_THRESHOLD_MULTIPLIER * (self.prestige - 1.0) * growth) as i128;
             match functions::overflow_check::<_,i128>(threshold_calc) {
                 Overflows::Panic => i128::MAX,
                 Overflows::Float => 50 + (25 * XP_THRESHOLD_MULTIPLIER as i128 * (self.prestige as i128 - 1)),
@@ -157,6 +289,48 @@ pub mod player_data {
             }
         }
 
+        /// Where the player ranks by [`Ord`]: their level, plus any whole levels
+        /// their XP is worth that [`lvl_check()`](Self::lvl_check) hasn't counted yet.
+        pub fn rank_score(&self) -> i128 {
+            self.lvl as i128 + (self.xp / self.xp_threshold())
+        }
+
+        /// The XP (after multipliers) the player needs to rank above `other`,
+        /// or `None` if they already do.
+        ///
+        /// They need a [`rank_score()`](Self::rank_score) one past `other`'s,
+        /// and every level takes their own [`xp_threshold()`](Self::xp_threshold).
+        pub fn xp_to_overtake(&self, other: &Player) -> Option<i128> {
+            if self.rank_score() > other.rank_score() {
+                return None
+            }
+            let levels = other.rank_score() + 1 - self.lvl as i128;
+            Some(levels.saturating_mul(self.xp_threshold()).saturating_sub(self.xp))
+        }
+
+        /// The smallest base XP that [`xp_change()`](Self::xp_change) turns into
+        /// at least `xp`, i.e. how big an achievement would need to be to give it.
+        pub fn base_xp_for(&self, xp: i128) -> i128 {
+            if xp <= 0 {
+                return 0
+            }
+            let mut high = xp;
+            while self.xp_change(high) < xp && high < i128::MAX / 2 {
+                high *= 2;
+            }
+            // `xp_change()` never goes down as the base goes up, so search between nothing and `high`.
+            let mut low = 0;
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                if self.xp_change(mid) >= xp {
+                    high = mid;
+                } else {
+                    low = mid;
+                }
+            }
+            high
+        }
+
         // Prestige Points Section
         // So. Prestige points should be calculated as starting from the current prestige threshold.
         // e.g. prestige threshold = 10, lvl = 11 => prestige points = 1 + (1 / 20)
@@ -172,6 +346,75 @@ pub mod player_data {
             ((self.lvl as f64 - self.prestige_threshold as f64 + 10.0) / 100.0) + 1.0
         }
 
+        /// What this player would look like after prestiging at their current level.
+        ///
+        /// Their prestige is multiplied by their [`prestige_points()`](Self::prestige_points)
+        /// (capped at [`f64::MAX`]), the next prestige threshold becomes their current level,
+        /// and they go back to Lv. 1 with no XP. They also earn
+        /// [perk points](crate::perks::PerkTree::points_per_prestige).
+        ///
+        /// `title` is added to their title, if given. If their title is already
+        /// as long as their [title slots](Self::title_slots) allow, the oldest
+        /// word is dropped to make room. Titles from before there was a limit
+        /// can be longer than that, and keep their length.
+        ///
+        /// Used by [`/prestige`](crate::commands::prestige) both to preview a prestige, and to do it.
+        pub fn prestiged(&self, title: Option<String>) -> Player {
+            let mut p = self.clone();
+            p.prestige = match functions::overflow_check(|| self.prestige * self.prestige_points()) {
+                Overflows::Float | Overflows::Panic => f64::MAX,
+                Overflows::Safe => self.prestige * self.prestige_points(),
+            };
+            p.prestige_threshold = self.lvl;
+            p.lvl = 1;
+            p.xp = 0;
+            p.perk_points = p.perk_points.saturating_add(perks::tree().points_per_prestige);
+            if let Some(title) = title {
+                if p.title_segments.len() >= self.title_slots() {
+                    p.title_segments.remove(0);
+                }
+                p.title_segments.push(title);
+            }
+            p
+        }
+
+        /// How many words the player's title can have, when they add a new one by prestiging.
+        pub fn title_slots(&self) -> usize {
+            (perks::tree().base_title_slots + self.perk(Effect::TitleSlots) as usize).max(1)
+        }
+
+        /// The accent colour the player has equipped, if any.
+        pub fn accent_colour(&self) -> Option<u32> {
+            shop::catalog().equipped(self).find_map(|x| match x {
+                shop::Cosmetic::Colour(colour) => shop::parse_colour(colour),
+                _ => None,
+            })
+        }
+
+        /// The characters used for the player's [XP bar](Player::xp_bar), filled and empty.
+        pub fn xp_bar_characters(&self) -> (&str, &str) {
+            shop::catalog().equipped(self).find_map(|x| match x {
+                shop::Cosmetic::XpBar { filled, empty } => Some((filled.as_str(), empty.as_str())),
+                _ => None,
+            }).unwrap_or(("█", "░"))
+        }
+
+        /// The badges the player has equipped, separated by spaces.
+        pub fn badges(&self) -> String {
+            shop::catalog().equipped(self).filter_map(|x| match x {
+                shop::Cosmetic::Badge(badge) => Some(badge.as_str()),
+                _ => None,
+            }).collect::<Vec<_>>().join(" ")
+        }
+
+        /// The text either side of the player's title, if they have a frame equipped.
+        pub fn title_frame(&self) -> Option<(&str, &str)> {
+            shop::catalog().equipped(self).find_map(|x| match x {
+                shop::Cosmetic::TitleFrame { left, right } => Some((left.as_str(), right.as_str())),
+                _ => None,
+            })
+        }
+
         /// Checks whether a Player has enough [`XP`](Self::xp) to level up.
         ///
         /// First, checks to see if they have negative XP.
@@ -183,75 +426,88 @@ pub mod player_data {
         /// If the XP is above [`xp_threshold`](Self::xp_threshold), then it
         /// removes XP, increments the level, and repeats,
         /// until the XP is below [`xp_threshold`](Self::xp_threshold) again.
-        pub async fn lvl_check(&mut self, ctx: Option<Context<'_>>) -> Vec<String> {
+        ///
+        /// Every level gained earns [coins](crate::shop::Catalog::coins_per_level),
+        /// and every level lost takes them back.
+        ///
+        /// Returns the messages to show, and, if the level changed, an
+        /// [`audit`] event to [record](audit::record) once the change is saved.
+        pub async fn lvl_check(&mut self, ctx: Option<Context<'_>>) -> (Vec<String>, Option<audit::Event>) {
             let mut output = vec![];
             let old_lvl = self.lvl;
 
 
 
+            let lang = ctx.map_or(i18n::DEFAULT_LOCALE, i18n::locale);
             let username: String =
-                if ctx.is_some() {
-                    self.user_data(ctx.unwrap()).await.expect("Failed to find user data").display_name().to_owned()
+                if let Some(ctx) = ctx {
+                    match self.user_data(ctx).await {
+                        Some(u) => u.display_name().to_owned(),
+                        None => format!("<@{}>", self.user_id),
+                    }
                 } else {
-                    "[Unknown Username]".to_owned()
+                    tr!(lang, "unknown-username")
                 };
 
 
-            println!("Checking level down");
             while self.xp < 0 && self.lvl > 1 {
                 self.lvl -= 1;
                 self.xp += self.xp_threshold();
-                output.push(format!("{username} lost a level! They are now at Lv. {}!", self.lvl));
+                output.push(tr!(lang, "level-lost", name = username.as_str(), lvl = self.lvl));
             }
 
-            println!("Checking level up");
-
             if self.xp > self.xp_threshold() {
                 let level_change = (self.xp / self.xp_threshold()) as i64;
                 if level_change > 4 {
-                    output.push(format!("{username} gained a level! They are now at Lv. {}!\n...",self.lvl + 1));
-                    output.push(format!("{username} gained a level! They are now at Lv. {}!",self.lvl + level_change - 1));
-                    output.push(format!("{username} gained a level! They are now at Lv. {}!",self.lvl + level_change));
+                    output.push(format!("{}\n...", tr!(lang, "level-gained", name = username.as_str(), lvl = self.lvl + 1)));
+                    output.push(tr!(lang, "level-gained", name = username.as_str(), lvl = self.lvl + level_change - 1));
+                    output.push(tr!(lang, "level-gained", name = username.as_str(), lvl = self.lvl + level_change));
                 } else {
                     for i in 1..level_change {
-                        output.push(format!("{username} gained a level! They are now at Lv. {}!",self.lvl + i));
+                        output.push(tr!(lang, "level-gained", name = username.as_str(), lvl = self.lvl + i));
                     }
                 }
                 self.lvl += level_change;
-                println!("Gained {} levels!",self.xp / self.xp_threshold());
-                self.xp = self.xp % self.xp_threshold();
+                self.xp %= self.xp_threshold();
             }
-            // while self.xp >= self.xp_threshold() {
-            //     println!("Subtracting XP");
-            //     self.xp -= self.xp_threshold();
-            //     println!("Increasing level");
-            //     self.lvl += 1;
-            //     println!("Adding to output string");
-            //     output.push(format!("{username} gained a level! They are now at Lv. {}!", self.lvl));
-            // }
-
-            println!("Checking prestige eligibility");
 
             if self.lvl >= self.prestige_threshold && old_lvl < self.prestige_threshold {
-                output.push("You are now eligible to Prestige! Use `/prestige` to find out more.".to_string())
+                output.push(tr!(lang, "prestige-eligible"))
             }
 
-            println!("Compressing msg");
-
             if output.len() > 10 {
                 output[1] = "...".to_string();
             }
             while output.len() > 10 {
-                println!("Removing line");
                 output.remove(2);
             }
 
-            println!("Level check complete");
-            return output;
+            let level_coins = shop::catalog().coins_per_level.saturating_mul(self.lvl.abs_diff(old_lvl));
+            if self.lvl > old_lvl {
+                crate::metrics::METRICS.level_ups.inc_by((self.lvl - old_lvl) as u64);
+                self.coins = self.coins.saturating_add(level_coins);
+            } else {
+                self.coins = self.coins.saturating_sub(level_coins);
+            }
+            if self.lvl != old_lvl {
+                tracing::info!(user_id = self.user_id, old_lvl, new_lvl = self.lvl, xp = %self.xp, "Level changed");
+            }
+
+            let event = (self.lvl != old_lvl).then(|| audit::Event::LevelChanged {
+                user_id: self.user_id,
+                old_lvl,
+                new_lvl: self.lvl,
+                player: Some(self.clone()),
+            });
+
+            (output, event)
         }
 
         /// Return an XP bar, as a string.
         ///
+        /// Uses the characters from the player's equipped
+        /// [XP bar](crate::shop::Cosmetic::XpBar), if they have one.
+        ///
         /// **Example**
         /// ```
         /// Player.xp = 43;
@@ -263,10 +519,12 @@ pub mod player_data {
         /// ```
         ///
         pub fn xp_bar(&self) -> String {
-            let progress = ((self.xp as f64 / self.xp_threshold() as f64) * 10.0) as usize;
+            // Clamped, in case the XP is somehow outside of the level.
+            let progress = ((self.xp as f64 / self.xp_threshold() as f64) * 10.0).clamp(0.0, 10.0) as usize;
 
-            let xp_gotten = "█".repeat(progress);
-            let xp_left = "░".repeat(10-progress);
+            let (filled, empty) = self.xp_bar_characters();
+            let xp_gotten = filled.repeat(progress);
+            let xp_left = empty.repeat(10-progress);
 
             format!("{xp_gotten}{xp_left}")
 
@@ -277,9 +535,10 @@ pub mod player_data {
     ///
     /// Currently unused, as it does not verify the player's presence beforehand,
     /// making it less safe than just running it manually.
-    pub fn find_player_by_id(id: u64) -> Player {
-        let players = file_management::load_players();
-        players.iter().find(|x| x.user_id == id).expect("User not present in players.").clone()
+    #[allow(dead_code)]
+    pub fn find_player_by_id(id: u64) -> Result<Player, Error> {
+        let players = file_management::load_players()?;
+        players.iter().find(|x| x.user_id == id).cloned().ok_or(Error::PlayerNotFound(id))
     }
 
     /// Verify whether a player is present inside `players.json`.
@@ -288,26 +547,45 @@ pub mod player_data {
     /// If it isn't, save it back to the file, and run the check again.
     ///
     /// It only saves the file and runs the second check if the first check fails.
-    /// **Panics if the second check fails.**
+    /// Returns [`Error::PlayerNotFound`] if the second check fails.
     ///
-    pub fn verify_player(ctx: Context<'_>, id: Option<u64>) {
+    /// Since it can save the file, take [`Data::lock_file()`](crate::Data::lock_file) first.
+    pub fn verify_player(ctx: Context<'_>, id: Option<u64>) -> Result<(), Error> {
         let u_id = id.unwrap_or_else(|| ctx.author().id.get());
-        let mut players = file_management::load_players();
+        let mut players = file_management::load_players()?;
         let id_vector = players.iter().map(|x| x.user_id).collect::<Vec<_>>();
 
         if !id_vector.contains(&u_id) {
             players.push(Player::new(u_id));
 
             // only needs to save if a change needs to be made
-            file_management::save_players(&players);
+            file_management::save_players(&players)?;
 
-            // assert that the loaded file, mapped for ids, contains the id that we're looking for
-            assert!(file_management::load_players().iter().map(|x| x.user_id).collect::<Vec<_>>().contains(&u_id));
-            // if it doesn't, then all hope is lost
+            // check that the loaded file, mapped for ids, contains the id that we're looking for
+            if !file_management::load_players()?.iter().any(|x| x.user_id == u_id) {
+                // if it doesn't, then all hope is lost
+                return Err(Error::PlayerNotFound(u_id))
+            }
         }
 
+        Ok(())
+    }
 
+    /// Sorts players best first (using [`Ord`]), and numbers them from 1.
+    pub fn rank_players(mut players: Vec<Player>) -> Vec<(usize, Player)> {
+        players.sort();
+        players.into_iter().enumerate().map(|(i, p)| (i + 1, p)).collect()
+    }
 
+    /// Sorts players like an Olympic medal table, and numbers them from 1.
+    ///
+    /// Most golds come first, then most silvers, then most bronzes. Players
+    /// with the same medals are sorted using [`Ord`]. Players without any
+    /// medals are left out.
+    pub fn rank_players_by_medals(mut players: Vec<Player>) -> Vec<(usize, Player)> {
+        players.retain(|x| x.medals.total() > 0);
+        players.sort_by(|a, b| b.medals.cmp(&a.medals).then_with(|| a.cmp(b)));
+        players.into_iter().enumerate().map(|(i, p)| (i + 1, p)).collect()
     }
 
     impl PartialEq for Player {
@@ -320,37 +598,751 @@ pub mod player_data {
 
     impl PartialOrd for Player {
         fn partial_cmp(&self, other: &Player) -> Option<cmp::Ordering> {
-            Some((other.lvl as i128 + (other.xp / other.xp_threshold()))
-                 .cmp(&(self.lvl as i128 + (self.xp / self.xp_threshold()))))
+            Some(self.cmp(other))
         }
     }
 
     impl Ord for Player {
         fn cmp(&self, other: &Player) -> cmp::Ordering {
-            (other.lvl as i128 + (other.xp / other.xp_threshold()))
-                .cmp(&(self.lvl as i128 + (self.xp / self.xp_threshold())))
+            other.rank_score().cmp(&self.rank_score())
+        }
+    }
+
+    #[cfg(test)]
+    impl Player {
+        /// A new player with some XP already, for tests.
+        pub fn with_xp(user_id: u64, xp: i128) -> Player {
+            Player { xp, ..Player::new(user_id) }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Player;
+        use crate::i18n::DEFAULT_LOCALE;
+
+        fn player(xp: i128) -> Player {
+            Player::with_xp(1, xp)
+        }
+
+        #[test]
+        fn prestiging_keeps_titles_from_before_the_limit() {
+            let mut p = Player::new(1);
+            p.title_segments = (0..p.title_slots() + 2).map(|x| x.to_string()).collect();
+            let old_len = p.title_segments.len();
+            let p = p.prestiged(Some("new".to_string()));
+            assert_eq!(p.title_segments.len(), old_len);
+            assert_eq!(p.title_segments.first().map(String::as_str), Some("1"));
+            assert_eq!(p.title_segments.last().map(String::as_str), Some("new"));
+        }
+
+        #[test]
+        fn prestiging_with_a_full_title_drops_the_oldest_word() {
+            let mut p = Player::new(1);
+            p.title_segments = (0..p.title_slots()).map(|x| x.to_string()).collect();
+            let p = p.prestiged(Some("new".to_string()));
+            assert_eq!(p.title_segments.len(), p.title_slots());
+            assert_eq!(p.title_segments.first().map(String::as_str), Some("1"));
+        }
+
+        #[test]
+        fn hold_xp_takes_xp_from_the_current_level() {
+            let mut p = player(50);
+            assert!(p.hold_xp(30, DEFAULT_LOCALE).is_ok());
+            assert_eq!(p.xp, 20);
+            assert!(p.hold_xp(20, DEFAULT_LOCALE).is_ok());
+            assert_eq!(p.xp, 0);
+        }
+
+        #[test]
+        fn hold_xp_refuses_more_than_the_player_has() {
+            let mut p = player(50);
+            assert!(p.hold_xp(51, DEFAULT_LOCALE).is_err());
+            assert_eq!(p.xp, 50);
+        }
+
+        #[test]
+        fn hold_xp_refuses_nothing_or_negative_xp() {
+            let mut p = player(50);
+            assert!(p.hold_xp(0, DEFAULT_LOCALE).is_err());
+            assert!(p.hold_xp(-100, DEFAULT_LOCALE).is_err());
+            assert_eq!(p.xp, 50);
+        }
+    }
+
+}
+
+pub mod achievement_data {
+    use crate::{Serialize, Deserialize, player_data::{self, MedalTally}};
+    use chrono::{DateTime, Utc};
+    use std::collections::HashMap;
+
+    /// The tier of an achievement, picked from its XP by
+    /// [`MedalThresholds::medal()`](crate::guild_config::MedalThresholds::medal).
+    #[derive(Serialize,Deserialize,Clone,Copy,PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Medal {
+        /// For achievements that take XP away.
+        Poo,
+        Bronze,
+        Silver,
+        Gold,
+    }
+
+    impl Medal {
+        pub fn emoji(self) -> &'static str {
+            match self {
+                Medal::Poo => "💩",
+                Medal::Bronze => "🥉",
+                Medal::Silver => "🥈",
+                Medal::Gold => "🥇",
+            }
+        }
+    }
+
+    /// A single use of [`/achievement`](crate::commands::achievement).
+    ///
+    /// Every grant is kept in [`FileFormat::achievements`](crate::json_data::FileFormat::achievements),
+    /// so that it can be looked back on, and revoked by an admin if it
+    /// was a mistake.
+    #[derive(Serialize,Deserialize,Clone)]
+    #[non_exhaustive]
+    pub struct AchievementRecord {
+
+        /// Unique, incrementing ID of the achievement.
+        pub id: u64,
+
+        /// The user who used the command.
+        pub granter_id: u64,
+
+        /// The user who received the XP.
+        pub recipient_id: u64,
+
+        /// The server the achievement was granted in, if any.
+        #[serde(default)]
+        pub guild_id: Option<u64>,
+
+        /// Title of the achievement, as typed by the granter.
+        pub title: String,
+
+        /// The XP that was asked for, before any multipliers.
+        pub base_xp: i128,
+
+        /// The XP that was actually added to the player, after
+        /// [`xp_change`](player_data::Player::xp_change).
+        ///
+        /// This is what gets taken away again when the achievement is revoked.
+        pub effective_xp: i128,
+
+        /// The [coins](crate::shop) the achievement earned, not counting any from levelling up.
+        ///
+        /// Also taken away again when the achievement is revoked.
+        #[serde(default)]
+        pub coins: u64,
+
+        /// The medal the achievement won, counted in the player's
+        /// [`medals`](player_data::Player::medals).
+        ///
+        /// `None` for achievements granted before medals were kept.
+        #[serde(default)]
+        pub medal: Option<Medal>,
+
+        /// The server [category](crate::guild_config::GuildConfig::categories)
+        /// the achievement was granted under, if any.
+        #[serde(default)]
+        pub category: Option<String>,
+
+        /// The [team](crate::teams) the recipient was on, which the XP was credited to.
+        ///
+        /// Revoking the achievement takes the XP back off this team, even if
+        /// the recipient has left it since.
+        #[serde(default)]
+        pub team_id: Option<u64>,
+
+        /// When the achievement was granted.
+        pub timestamp: DateTime<Utc>,
+
+        /// Whether the achievement has since been revoked.
+        pub revoked: bool,
+    }
+
+    impl AchievementRecord {
+
+        /// Create a new record of an achievement, timestamped now.
+        pub fn new(id: u64, granter_id: u64, recipient_id: u64, guild_id: Option<u64>, title: String, base_xp: i128, effective_xp: i128) -> AchievementRecord {
+            AchievementRecord {
+                id,
+                granter_id,
+                recipient_id,
+                guild_id,
+                title,
+                base_xp,
+                effective_xp,
+                coins: 0,
+                medal: None,
+                category: None,
+                team_id: None,
+                timestamp: Utc::now(),
+                revoked: false,
+            }
+        }
+
+        /// Takes the [`effective_xp`](Self::effective_xp), [`coins`](Self::coins)
+        /// and [`medal`](Self::medal) back off of the player, and marks the
+        /// achievement as revoked.
+        ///
+        /// Does not run [`lvl_check()`](player_data::Player::lvl_check) -
+        /// that should be done by the caller afterwards, so that the level
+        /// drops back down if it needs to.
+        pub fn revoke(&mut self, p: &mut player_data::Player) {
+            p.xp -= self.effective_xp;
+            p.coins = p.coins.saturating_sub(self.coins);
+            if let Some(medal) = self.medal {
+                p.medals.remove(medal);
+            }
+            self.revoked = true;
+        }
+    }
+
+    /// What a group of achievements added up to.
+    #[derive(Clone,Copy,Default)]
+    pub struct Totals {
+        pub achievements: usize,
+
+        /// The XP the achievements actually gave, after multipliers.
+        pub xp: i128,
+
+        pub medals: MedalTally,
+    }
+
+    /// Adds up achievements that haven't been revoked, grouped by `key`.
+    ///
+    /// Only achievements from `guild_id` are counted, if it's given.
+    pub fn totals_by<'a, K: Eq + std::hash::Hash>(
+        records: impl IntoIterator<Item = &'a AchievementRecord>,
+        guild_id: Option<u64>,
+        key: impl Fn(&AchievementRecord) -> K,
+    ) -> HashMap<K, Totals> {
+        let mut totals: HashMap<K, Totals> = HashMap::new();
+        for record in records.into_iter().filter(|x| !x.revoked && guild_id.is_none_or(|id| x.guild_id == Some(id))) {
+            let total = totals.entry(key(record)).or_default();
+            total.achievements += 1;
+            total.xp += record.effective_xp;
+            if let Some(medal) = record.medal {
+                total.medals.add(medal);
+            }
+        }
+        totals
+    }
+}
 
+pub mod admin_data {
+    use crate::{Serialize, Deserialize};
+    use chrono::{DateTime, Utc};
 
+    /// A record of an admin changing somebody's data by hand.
+    ///
+    /// These are kept in [`FileFormat::admin_log`](crate::json_data::FileFormat::admin_log)
+    /// so that every change can be traced back to who did it, and why.
+    #[derive(Serialize,Deserialize,Clone)]
+    #[non_exhaustive]
+    pub struct AdminAction {
+
+        /// The admin who made the change.
+        pub actor_id: u64,
+
+        /// The player whose data was changed.
+        ///
+        /// For changes to a server's settings, this is the guild ID instead.
+        pub target_id: u64,
+
+        /// What was changed, e.g. `"set xp 10 -> 50"`.
+        pub action: String,
+
+        /// The reason the admin gave for the change.
+        pub reason: String,
+
+        /// When the change was made.
+        pub timestamp: DateTime<Utc>,
+    }
+
+    impl AdminAction {
+
+        /// Create a new admin action, timestamped now.
+        pub fn new(actor_id: u64, target_id: u64, action: String, reason: String) -> AdminAction {
+            tracing::info!(actor_id, target_id, action, reason, "Admin action");
+            AdminAction {
+                actor_id,
+                target_id,
+                action,
+                reason,
+                timestamp: Utc::now(),
+            }
+        }
+    }
+}
+
+pub mod guild_config {
+    use crate::{Serialize, Deserialize, achievement_data::{AchievementRecord, Medal}, player_data::Player, perks::Effect, i18n::tr};
+    use chrono::{DateTime, Datelike, Duration, Utc, Weekday};
+
+    /// Limits on how much XP can be handed out, to stop
+    /// [`/achievement`](crate::commands::achievement) from being spammed.
+    #[derive(Serialize,Deserialize,Clone)]
+    #[serde(default)]
+    pub struct XpLimits {
+
+        /// How many seconds somebody has to wait between granting achievements.
+        pub cooldown_secs: i64,
+
+        /// The most XP somebody can grant in a day, to anybody (including themselves).
+        pub daily_granted_cap: i128,
+
+        /// The most XP somebody can receive in a day, from anybody (including themselves).
+        pub daily_received_cap: i128,
+
+        /// The most XP (positive or negative) a single achievement can be worth.
+        pub max_xp_per_achievement: i128,
+
+        /// How many achievements two users can grant *each other* in a day,
+        /// before it starts to look like they're just farming XP.
+        pub reciprocal_limit: usize,
+    }
+
+    impl Default for XpLimits {
+        fn default() -> XpLimits {
+            XpLimits {
+                cooldown_secs: 30,
+                daily_granted_cap: 500,
+                daily_received_cap: 500,
+                max_xp_per_achievement: 100,
+                reciprocal_limit: 3,
+            }
+        }
+    }
+
+    impl XpLimits {
+
+        /// Checks whether an achievement is allowed to be granted.
+        ///
+        /// Looks back through the achievement history for the last day,
+        /// and returns `Err` with a message to show the granter if any limit
+        /// would be broken, in the language `lang`. Limits belong to a server,
+        /// so only achievements granted in `guild_id` count, and revoked
+        /// (or undone) achievements don't count at all.
+        ///
+        /// The granter's [perks](crate::perks) can shorten their cooldown and raise their daily cap.
+        #[allow(clippy::too_many_arguments)]
+        pub fn check(&self, history: &[AchievementRecord], guild_id: Option<u64>, granter: &Player, recipient_id: u64, xp: i128, now: DateTime<Utc>, lang: &str) -> Result<(), String> {
+            let history = history.iter().filter(|x| x.guild_id == guild_id && !x.revoked).collect::<Vec<_>>();
+            let granter_id = granter.user_id;
+            let cooldown_secs = (self.cooldown_secs as f64 * (1.0 - granter.perk(Effect::CooldownReduction).clamp(0.0, 1.0))) as i64;
+            let daily_granted_cap = self.daily_granted_cap.saturating_add(granter.perk(Effect::GiftLimit) as i128);
+
+            if xp.abs() > self.max_xp_per_achievement {
+                return Err(tr!(lang, "limit-max-xp", max = self.max_xp_per_achievement.to_string()))
+            }
+
+            if let Some(last) = history.iter().filter(|x| x.granter_id == granter_id).map(|x| x.timestamp).max() {
+                let remaining = (last + Duration::seconds(cooldown_secs) - now).num_seconds();
+                if remaining > 0 {
+                    return Err(tr!(lang, "limit-cooldown", remaining = remaining))
+                }
+            }
+
+            // Removing XP from yourself is never limited by the daily caps.
+            if xp <= 0 {
+                return Ok(())
+            }
+
+            let today = history.iter()
+                .filter(|x| x.base_xp > 0 && now - x.timestamp < Duration::days(1))
+                .collect::<Vec<_>>();
+
+            let granted: i128 = today.iter().filter(|x| x.granter_id == granter_id).map(|x| x.base_xp).sum();
+            if granted + xp > daily_granted_cap {
+                return Err(tr!(lang, "limit-granted",
+                               cap = daily_granted_cap.to_string(),
+                               left = (daily_granted_cap - granted).max(0).to_string()))
+            }
+
+            let received: i128 = today.iter().filter(|x| x.recipient_id == recipient_id).map(|x| x.base_xp).sum();
+            if received + xp > self.daily_received_cap {
+                return Err(tr!(lang, "limit-received",
+                               user = format!("<@{recipient_id}>"),
+                               cap = self.daily_received_cap.to_string(),
+                               left = (self.daily_received_cap - received).max(0).to_string()))
+            }
+
+            if granter_id != recipient_id {
+                let given = today.iter().filter(|x| x.granter_id == granter_id && x.recipient_id == recipient_id).count();
+                let returned = today.iter().filter(|x| x.granter_id == recipient_id && x.recipient_id == granter_id).count();
+                if given + 1 >= self.reciprocal_limit && returned >= self.reciprocal_limit {
+                    tracing::warn!(granter_id, recipient_id, given, returned, "Suspicious reciprocal granting");
+                    return Err(tr!(lang, "limit-reciprocal", user = format!("<@{recipient_id}>")))
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// How often a [digest](crate::digest) is posted.
+    #[derive(Serialize,Deserialize,Clone,Copy,PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum DigestPeriod {
+        Daily,
+        Weekly,
+    }
+
+    /// When and where a server's [digest](crate::digest) is posted.
+    #[derive(Serialize,Deserialize,Clone)]
+    pub struct DigestSchedule {
+
+        /// The channel to post in.
+        pub channel_id: u64,
+
+        pub period: DigestPeriod,
+
+        /// The hour of the day to post at, in UTC.
+        pub hour: u32,
+
+        /// The day of the week to post on, for weekly digests.
+        pub weekday: Weekday,
+
+        /// Only include achievements in this [category](GuildConfig::categories), if set.
+        #[serde(default)]
+        pub category: Option<String>,
+    }
+
+    impl DigestSchedule {
+
+        /// How much time each digest covers.
+        pub fn length(&self) -> Duration {
+            match self.period {
+                DigestPeriod::Daily => Duration::days(1),
+                DigestPeriod::Weekly => Duration::weeks(1),
+            }
+        }
+
+        /// The most recent time a digest should have been posted, at or before `now`.
+        pub fn latest_slot(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+            let mut slot = now.date_naive()
+                .and_hms_opt(self.hour.min(23), 0, 0)
+                .expect("Hour is between 0 and 23")
+                .and_utc();
+            if slot > now {
+                slot -= Duration::days(1);
+            }
+            if self.period == DigestPeriod::Weekly {
+                while slot.weekday() != self.weekday {
+                    slot -= Duration::days(1);
+                }
+            }
+            slot
+        }
+
+        /// A short description, e.g. `Weekly, on Mon at 18:00 UTC, in #general`,
+        /// or `Daily, at 09:00 UTC, in #general, for Fitness` with a category.
+        pub fn describe(&self) -> String {
+            let schedule = match self.period {
+                DigestPeriod::Daily => format!("Daily, at {:02}:00 UTC, in <#{}>", self.hour, self.channel_id),
+                DigestPeriod::Weekly => format!("Weekly, on {} at {:02}:00 UTC, in <#{}>", self.weekday, self.hour, self.channel_id),
+            };
+            match &self.category {
+                Some(category) => format!("{schedule}, for {category}"),
+                None => schedule,
+            }
+        }
+    }
+
+    /// Which parts of the bot can be used in a server.
+    ///
+    /// Commands for a feature that's turned off are refused by
+    /// [`config::check_feature()`](crate::config::check_feature).
+    #[derive(Serialize,Deserialize,Clone)]
+    #[serde(default)]
+    pub struct Features {
+        pub achievements: bool,
+        pub prestige: bool,
+        pub levels: bool,
+    }
+
+    impl Default for Features {
+        fn default() -> Features {
+            Features {
+                achievements: true,
+                prestige: true,
+                levels: true,
+            }
+        }
+    }
+
+    /// How much XP an achievement needs to be worth (before multipliers) to win each medal.
+    ///
+    /// Anything worth more than 0 XP, but less than `silver`, wins bronze.
+    #[derive(Serialize,Deserialize,Clone)]
+    #[serde(default)]
+    pub struct MedalThresholds {
+        pub silver: i128,
+        pub gold: i128,
+    }
+
+    impl Default for MedalThresholds {
+        fn default() -> MedalThresholds {
+            MedalThresholds {
+                silver: 25,
+                gold: 50,
+            }
+        }
+    }
+
+    impl MedalThresholds {
+
+        /// The medal an achievement worth `xp` wins.
+        pub fn medal(&self, xp: i128) -> Medal {
+            if xp <= 0 {
+                Medal::Poo
+            } else if xp < self.silver {
+                Medal::Bronze
+            } else if xp < self.gold {
+                Medal::Silver
+            } else {
+                Medal::Gold
+            }
+        }
+    }
+
+    /// The colour down the side of each kind of embed, as `0xRRGGBB`.
+    #[derive(Serialize,Deserialize,Clone)]
+    #[serde(default)]
+    pub struct EmbedColours {
+        pub level: u32,
+        pub achievement: u32,
+        pub prestige: u32,
+    }
+
+    impl Default for EmbedColours {
+        fn default() -> EmbedColours {
+            EmbedColours {
+                level: 0x5865F2,
+                achievement: 0xF1C40F,
+                prestige: 0x9B59B6,
+            }
         }
     }
 
+    /// Settings for a single Discord server.
+    ///
+    /// Changed with [`/config`](crate::config), and cached in
+    /// [`Data`](crate::Data) so every command can read them cheaply.
+    #[derive(Serialize,Deserialize,Clone,Default)]
+    #[serde(default)]
+    pub struct GuildConfig {
+
+        /// Limits on granting XP with [`/achievement`](crate::commands::achievement).
+        pub limits: XpLimits,
+
+        /// When to post a [digest](crate::digest), if at all.
+        pub digest: Option<DigestSchedule>,
+
+        /// A channel to announce level-ups and prestiges in, if any.
+        pub announcement_channel: Option<u64>,
+
+        /// A role whose members can use [`/admin`](crate::commands::admin),
+        /// as well as anybody with the Manage Server permission.
+        pub admin_role: Option<u64>,
+
+        /// A role whose members can decide the result of a
+        /// [challenge](crate::challenges) when its players don't agree.
+        pub referee_role: Option<u64>,
+
+        pub features: Features,
+
+        /// The language to reply in, unless a user has chosen their own.
+        /// `None` uses each user's Discord language.
+        pub locale: Option<String>,
+
+        pub colours: EmbedColours,
+
+        /// How much XP each achievement [medal](crate::achievement_data::Medal) needs.
+        pub medals: MedalThresholds,
+
+        /// Words that can't be used in titles, checked by [`titles::check()`](crate::titles::check).
+        pub banned_title_words: Vec<String>,
+
+        /// Categories that achievements can be granted under, like "Fitness" or "Work".
+        pub categories: Vec<String>,
+    }
+
+    /// The most categories a server can have, which is as many as Discord can suggest at once.
+    pub const MAX_CATEGORIES: usize = 25;
+
+    /// The longest a category's name can be.
+    pub const MAX_CATEGORY_LENGTH: usize = 32;
+
+    impl GuildConfig {
+
+        /// Finds one of the server's categories, ignoring case.
+        pub fn category(&self, name: &str) -> Option<&str> {
+            let name = name.trim().to_lowercase();
+            self.categories.iter().find(|x| x.to_lowercase() == name).map(String::as_str)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::XpLimits;
+        use crate::{achievement_data::AchievementRecord, player_data::Player, i18n::DEFAULT_LOCALE};
+        use chrono::Utc;
+
+        const GRANTER: u64 = 1;
+        const RECIPIENT: u64 = 2;
+
+        fn grant(id: u64, guild_id: u64, xp: i128) -> AchievementRecord {
+            AchievementRecord::new(id, GRANTER, RECIPIENT, Some(guild_id), "Test".to_string(), xp, xp)
+        }
+
+        fn check(limits: &XpLimits, history: &[AchievementRecord], guild_id: u64, xp: i128) -> Result<(), String> {
+            limits.check(history, Some(guild_id), &Player::new(GRANTER), RECIPIENT, xp, Utc::now(), DEFAULT_LOCALE)
+        }
+
+        #[test]
+        fn cooldown_only_counts_grants_in_the_same_server() {
+            let limits = XpLimits::default();
+            let history = [grant(1, 1, 10)];
+            assert!(check(&limits, &history, 1, 10).is_err());
+            assert!(check(&limits, &history, 2, 10).is_ok());
+        }
+
+        #[test]
+        fn undone_grants_dont_start_the_cooldown() {
+            let limits = XpLimits::default();
+            let mut history = [grant(1, 1, 10)];
+            history[0].revoked = true;
+            assert!(check(&limits, &history, 1, 10).is_ok());
+        }
+
+        #[test]
+        fn daily_caps_only_count_grants_in_the_same_server() {
+            let limits = XpLimits { cooldown_secs: 0, ..XpLimits::default() };
+            let history = (1..=5).map(|id| grant(id, 2, 100)).collect::<Vec<_>>();
+            assert!(check(&limits, &history, 2, 100).is_err());
+            assert!(check(&limits, &history, 1, 100).is_ok());
+        }
+
+        #[test]
+        fn achievements_over_the_max_are_refused() {
+            let limits = XpLimits::default();
+            assert!(check(&limits, &[], 1, limits.max_xp_per_achievement).is_ok());
+            assert!(check(&limits, &[], 1, limits.max_xp_per_achievement + 1).is_err());
+        }
+    }
 }
 
 pub mod json_data {
-    use crate::{Serialize, Deserialize, player_data};
+    use crate::{Serialize, Deserialize, player_data, achievement_data, admin_data, guild_config, teams, challenges, bounties};
+    use chrono::{DateTime, Utc};
+    use std::collections::HashMap;
 
     #[non_exhaustive]
     #[derive(Serialize, Deserialize)]
     pub struct FileFormat {
         pub player_list: Vec<player_data::Player>,
+
+        /// Every achievement ever granted, in order.
+        #[serde(default)]
+        pub achievements: Vec<achievement_data::AchievementRecord>,
+
+        /// Every change made with [`/admin`](crate::commands::admin).
+        #[serde(default)]
+        pub admin_log: Vec<admin_data::AdminAction>,
+
+        /// Settings for each server, by guild ID.
+        #[serde(default)]
+        pub guild_configs: HashMap<u64, guild_config::GuildConfig>,
+
+        /// When the last [digest](crate::digest) in each server was due, by guild ID.
+        ///
+        /// Kept apart from [`guild_configs`](Self::guild_configs), so that
+        /// posting a digest isn't logged as a change to the settings.
+        #[serde(default)]
+        pub digests_posted: HashMap<u64, DateTime<Utc>>,
+
+        /// The language each user has chosen with `/language`, by user ID.
+        #[serde(default)]
+        pub user_locales: HashMap<u64, String>,
+
+        /// Every [team](crate::teams), in the order they were created.
+        #[serde(default)]
+        pub teams: Vec<teams::Team>,
+
+        /// Every [challenge](crate::challenges), including finished ones, in the order they were sent.
+        #[serde(default)]
+        pub challenges: Vec<challenges::Challenge>,
+
+        /// Every [bounty](crate::bounties), including closed ones, in the order they were posted.
+        #[serde(default)]
+        pub bounties: Vec<bounties::Bounty>,
     }
 
     impl FileFormat {
         pub fn new() -> FileFormat {
             FileFormat {
                 player_list: vec![],
+                achievements: vec![],
+                admin_log: vec![],
+                guild_configs: HashMap::new(),
+                digests_posted: HashMap::new(),
+                user_locales: HashMap::new(),
+                teams: vec![],
+                challenges: vec![],
+                bounties: vec![],
             }
         }
+
+        /// The settings for a server, or the defaults if it hasn't changed any.
+        pub fn guild_config(&self, guild_id: Option<u64>) -> guild_config::GuildConfig {
+            guild_id.and_then(|x| self.guild_configs.get(&x).cloned()).unwrap_or_default()
+        }
+
+        /// Finds a player in [`player_list`](Self::player_list) by their ID.
+        pub fn player_mut(&mut self, id: u64) -> Option<&mut player_data::Player> {
+            self.player_list.iter_mut().find(|x| x.user_id == id)
+        }
+
+        /// The ID that the next [`AchievementRecord`](achievement_data::AchievementRecord) should use.
+        pub fn next_achievement_id(&self) -> u64 {
+            self.achievements.iter().map(|x| x.id + 1).max().unwrap_or(1)
+        }
+
+        /// Deletes a player, taking them off their team, and out of any team
+        /// invites and claims on open [bounties](crate::bounties).
+        ///
+        /// A team they owned is handed over, or disbanded, just like when they
+        /// [leave](teams::leave) it. Their open challenges and bounties should be
+        /// wound up first, since that gives XP back. Returns whether they existed.
+        pub fn remove_player(&mut self, user_id: u64) -> bool {
+            let old_len = self.player_list.len();
+            self.player_list.retain(|x| x.user_id != user_id);
+            // Only fails if they aren't on a team.
+            teams::leave(&mut self.teams, user_id, crate::i18n::DEFAULT_LOCALE).ok();
+            for team in &mut self.teams {
+                team.invites.retain(|x| *x != user_id);
+            }
+            for bounty in self.bounties.iter_mut().filter(|x| x.is_open()) {
+                bounty.claims.retain(|x| x.user_id != user_id);
+            }
+            self.player_list.len() != old_len
+        }
+    }
+
+    #[cfg(test)]
+    impl FileFormat {
+        /// The XP of the player with this ID, for tests.
+        pub fn xp_of(&self, id: u64) -> i128 {
+            self.player_list.iter().find(|x| x.user_id == id).map(|x| x.xp).expect("The player exists")
+        }
     }
 }
 
@@ -368,7 +1360,7 @@ pub mod functions {
         F: FnOnce() -> N + std::panic::UnwindSafe,
         N: PartialOrd + FromFloat
     {
-        let result = std::panic::catch_unwind(||f());
+        let result = std::panic::catch_unwind(f);
         if result.is_err() {
             return Overflows::Panic;
         }
@@ -376,7 +1368,7 @@ pub mod functions {
         if result.unwrap() >= N::from_float(f64::MAX) { Overflows::Float } else { Overflows::Safe }
     }
 
-    trait FromFloat {
+    pub trait FromFloat {
         fn from_float(n: f64) -> Self;
     }
     impl FromFloat for f64 {
@@ -0,0 +1,209 @@
+//! Perks that players buy with the points they earn by prestiging.
+//!
+//! The perk tree is read from [`PERKS_FILENAME`], next to `saved_data.json`,
+//! so perks and their costs can be changed without rebuilding the bot. If
+//! there's no such file, or it's invalid, the tree built into the bot (from
+//! `perks.json` in the repository) is used instead:
+//!
+//! ```json
+//! {
+//!     "points_per_prestige": 1,
+//!     "base_title_slots": 5,
+//!     "perks": [
+//!         {
+//!             "id": "quick_learner",
+//!             "name": "Quick Learner",
+//!             "description": "Earn 5% more XP from achievements per rank.",
+//!             "cost": 1,
+//!             "max_rank": 5,
+//!             "requires": [],
+//!             "effect": "xp_bonus",
+//!             "per_rank": 0.05
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Every rank of a perk adds `per_rank` to its [effect](Effect), and costs
+//! `cost` points. A perk can only be bought once the player has at least one
+//! rank of every perk in `requires`. Perks that players have bought stay on
+//! them even if they're removed from the tree, but stop having any effect.
+//!
+//! The tree is read once, when it's first needed, so the bot has to be
+//! restarted to pick up changes.
+
+use crate::{ Serialize, Deserialize, player_data::Player, i18n::tr };
+use std::collections::{ BTreeMap, HashSet };
+use std::fs;
+use std::sync::LazyLock;
+
+pub const PERKS_FILENAME: &str = "perks.json";
+
+/// The perk tree built into the bot, used when [`PERKS_FILENAME`] is missing or invalid.
+const DEFAULT_TREE: &str = include_str!("../perks.json");
+
+/// The most perks a tree can have, so `/perks view` fits in a single embed.
+pub const MAX_PERKS: usize = 25;
+
+/// What a perk does. Every rank adds the perk's [`per_rank`](Perk::per_rank) to its effect.
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+
+    /// Multiplies XP earned by `1 + bonus`, in [`Player::xp_change`].
+    XpBonus,
+
+    /// Slows how fast [`Player::xp_threshold`] grows with prestige,
+    /// as a fraction (`0.1` is 10% slower).
+    ThresholdReduction,
+
+    /// Raises the [daily cap](crate::guild_config::XpLimits::daily_granted_cap)
+    /// on XP the player can grant.
+    GiftLimit,
+
+    /// Shortens the player's [cooldown](crate::guild_config::XpLimits::cooldown_secs)
+    /// between granting achievements, as a fraction.
+    CooldownReduction,
+
+    /// Lets the player keep more words in their title when they prestige.
+    TitleSlots,
+}
+
+/// A single perk in the tree.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Perk {
+
+    /// What the perk is stored as on players. Should never change once players have it.
+    pub id: String,
+
+    /// What the perk is called in `/perks`.
+    pub name: String,
+
+    /// What the perk does, shown in `/perks`.
+    pub description: String,
+
+    /// How many perk points each rank costs.
+    pub cost: u32,
+
+    /// How many times the perk can be bought.
+    pub max_rank: u32,
+
+    /// Perks the player needs at least one rank of first.
+    #[serde(default)]
+    pub requires: Vec<String>,
+
+    /// What the perk does.
+    pub effect: Effect,
+
+    /// How much each rank adds to the effect.
+    pub per_rank: f64,
+}
+
+/// Every perk, and how players earn points to buy them.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct PerkTree {
+
+    /// How many perk points a player earns every time they prestige.
+    pub points_per_prestige: u32,
+
+    /// How many words a title can have without any perks.
+    pub base_title_slots: usize,
+
+    /// Every perk, in the order they're shown.
+    pub perks: Vec<Perk>,
+}
+
+impl PerkTree {
+
+    /// Checks that the tree makes sense, returning what's wrong with it if it doesn't.
+    fn validate(&self) -> Result<(), String> {
+        if self.perks.len() > MAX_PERKS {
+            return Err(format!("there are more than {MAX_PERKS} perks"))
+        }
+        if self.base_title_slots == 0 {
+            return Err("base_title_slots must be at least 1".to_owned())
+        }
+        let mut seen = HashSet::new();
+        for perk in &self.perks {
+            if perk.cost == 0 || perk.max_rank == 0 {
+                return Err(format!("perk {} must have a cost and max_rank of at least 1", perk.id))
+            }
+            if !perk.per_rank.is_finite() {
+                return Err(format!("perk {} has an invalid per_rank", perk.id))
+            }
+            // Requirements have to come earlier, which also rules out loops.
+            if let Some(missing) = perk.requires.iter().find(|x| !seen.contains(x.as_str())) {
+                return Err(format!("perk {} requires {missing}, which isn't listed before it", perk.id))
+            }
+            if !seen.insert(perk.id.as_str()) {
+                return Err(format!("perk {} is listed twice", perk.id))
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds a perk by its ID.
+    pub fn get(&self, id: &str) -> Option<&Perk> {
+        self.perks.iter().find(|x| x.id == id)
+    }
+
+    /// The total of one effect, from every perk in `ranks`.
+    pub fn total(&self, ranks: &BTreeMap<String, u32>, effect: Effect) -> f64 {
+        self.perks.iter()
+            .filter(|x| x.effect == effect)
+            .map(|x| x.per_rank * ranks.get(&x.id).copied().unwrap_or(0).min(x.max_rank) as f64)
+            .sum()
+    }
+
+    /// Buys the next rank of a perk for a player, spending their perk points.
+    ///
+    /// Returns the perk, or why it can't be bought, in the language `lang`.
+    pub fn buy(&self, player: &mut Player, id: &str, lang: &str) -> Result<&Perk, String> {
+        let Some(perk) = self.get(id) else {
+            return Err(tr!(lang, "perk-unknown", perk = id))
+        };
+        let rank = player.perks.get(id).copied().unwrap_or(0);
+        if rank >= perk.max_rank {
+            return Err(tr!(lang, "perk-maxed", perk = perk.name.as_str()))
+        }
+        let missing = perk.requires.iter()
+            .filter(|x| player.perks.get(*x).copied().unwrap_or(0) == 0)
+            .map(|x| self.get(x).map_or(x.as_str(), |x| x.name.as_str()))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(tr!(lang, "perk-locked", perk = perk.name.as_str(), requires = missing.join(", ")))
+        }
+        if player.perk_points < perk.cost {
+            return Err(tr!(lang, "perk-no-points", perk = perk.name.as_str(),
+                           cost = perk.cost, points = player.perk_points))
+        }
+        player.perk_points -= perk.cost;
+        player.perks.insert(id.to_owned(), rank + 1);
+        tracing::info!(user_id = player.user_id, perk = id, rank = rank + 1, "Bought perk");
+        Ok(perk)
+    }
+}
+
+/// Parses and validates a perk tree.
+fn parse(data: &str) -> Result<PerkTree, String> {
+    let tree: PerkTree = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    tree.validate()?;
+    Ok(tree)
+}
+
+/// Reads the perk tree from [`PERKS_FILENAME`], or the built-in one if it's missing or invalid.
+fn load() -> PerkTree {
+    let custom = fs::read_to_string(PERKS_FILENAME).ok().and_then(|data| {
+        parse(&data).inspect_err(|e| {
+            tracing::error!(error = %e, "Invalid {PERKS_FILENAME}, so the built-in perks will be used");
+        }).ok()
+    });
+    custom.unwrap_or_else(|| parse(DEFAULT_TREE).expect("The built-in perk tree is valid"))
+}
+
+static TREE: LazyLock<PerkTree> = LazyLock::new(load);
+
+/// The perk tree, read the first time it's needed.
+pub fn tree() -> &'static PerkTree {
+    &TREE
+}
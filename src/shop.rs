@@ -0,0 +1,246 @@
+//! Coins, and the cosmetic items players can spend them on.
+//!
+//! Players earn coins from [`/achievement`](crate::commands::achievement)
+//! and from levelling up. Coins are never reset by prestiging. Losing a
+//! level, or having an achievement revoked, takes its coins back.
+//!
+//! The catalog is read from [`SHOP_FILENAME`], next to `saved_data.json`, so
+//! items and prices can be changed without rebuilding the bot. If there's no
+//! such file, or it's invalid, the catalog built into the bot (from
+//! `shop.json` in the repository) is used instead:
+//!
+//! ```json
+//! {
+//!     "coins_per_xp": 0.1,
+//!     "coins_per_level": 10,
+//!     "items": [
+//!         {
+//!             "id": "sunset",
+//!             "name": "Sunset",
+//!             "description": "An orange accent on your profile and achievements.",
+//!             "price": 50,
+//!             "cosmetic": { "colour": "#E67E22" }
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Each item is one [`Cosmetic`]. A player can only have one accent colour,
+//! XP bar and title frame equipped at once, but up to [`MAX_BADGES`] badges.
+//! Items that players have bought stay in their inventory even if they're
+//! removed from the catalog, but stop being shown.
+//!
+//! The catalog is read once, when it's first needed, so the bot has to be
+//! restarted to pick up changes.
+
+use crate::{ Serialize, Deserialize, player_data::Player, i18n::tr };
+use std::collections::HashSet;
+use std::fs;
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const SHOP_FILENAME: &str = "shop.json";
+
+/// The catalog built into the bot, used when [`SHOP_FILENAME`] is missing or invalid.
+const DEFAULT_CATALOG: &str = include_str!("../shop.json");
+
+/// The most items a catalog can have, so `/shop view` fits in a single embed.
+pub const MAX_ITEMS: usize = 25;
+
+/// The most badges a player can have equipped at once.
+pub const MAX_BADGES: usize = 3;
+
+/// What an item changes about how a player is shown.
+#[derive(Serialize,Deserialize,Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Cosmetic {
+
+    /// The colour down the side of the player's `/level` and achievement embeds, as `#RRGGBB`.
+    Colour(String),
+
+    /// The characters used for the player's [XP bar](Player::xp_bar).
+    /// Each should be a single character or emoji.
+    XpBar { filled: String, empty: String },
+
+    /// An emoji shown on the player's `/level`.
+    Badge(String),
+
+    /// Text shown either side of the player's title, in their [name line](Player::name_line).
+    TitleFrame { left: String, right: String },
+}
+
+impl Cosmetic {
+
+    /// Whether two cosmetics replace each other when equipped.
+    fn same_slot(&self, other: &Cosmetic) -> bool {
+        match (self, other) {
+            (Cosmetic::Badge(_), _) | (_, Cosmetic::Badge(_)) => false,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    /// Checks that the cosmetic can actually be shown.
+    fn validate(&self) -> Result<(), String> {
+        let single = |x: &str| x.graphemes(true).count() == 1;
+        match self {
+            Cosmetic::Colour(colour) => parse_colour(colour).map(|_| ())
+                .ok_or_else(|| format!("{colour} isn't a colour like #RRGGBB")),
+            Cosmetic::XpBar { filled, empty } if !single(filled) || !single(empty) =>
+                Err("XP bar characters must be a single character each".to_owned()),
+            Cosmetic::Badge(badge) if badge.trim().is_empty() =>
+                Err("badges can't be empty".to_owned()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reads a colour written as `#RRGGBB`.
+pub fn parse_colour(colour: &str) -> Option<u32> {
+    let hex = colour.trim_start_matches('#');
+    u32::from_str_radix(hex, 16).ok().filter(|_| hex.len() == 6)
+}
+
+/// A single item in the shop.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Item {
+
+    /// What the item is stored as on players. Should never change once players own it.
+    pub id: String,
+
+    /// What the item is called in `/shop`.
+    pub name: String,
+
+    /// What the item looks like, shown in `/shop`.
+    pub description: String,
+
+    /// How many coins the item costs.
+    pub price: u64,
+
+    /// What the item does.
+    pub cosmetic: Cosmetic,
+}
+
+/// Every item in the shop, and how players earn coins to buy them.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Catalog {
+
+    /// How many coins each XP of an achievement is worth, before any multipliers.
+    pub coins_per_xp: f64,
+
+    /// How many coins a player earns every time they gain a level.
+    pub coins_per_level: u64,
+
+    /// Every item, in the order they're shown.
+    pub items: Vec<Item>,
+}
+
+impl Catalog {
+
+    /// Checks that the catalog makes sense, returning what's wrong with it if it doesn't.
+    fn validate(&self) -> Result<(), String> {
+        if self.items.len() > MAX_ITEMS {
+            return Err(format!("there are more than {MAX_ITEMS} items"))
+        }
+        if !self.coins_per_xp.is_finite() || self.coins_per_xp < 0.0 {
+            return Err("coins_per_xp must be a positive number".to_owned())
+        }
+        let mut seen = HashSet::new();
+        for item in &self.items {
+            item.cosmetic.validate().map_err(|e| format!("item {}: {e}", item.id))?;
+            if !seen.insert(item.id.as_str()) {
+                return Err(format!("item {} is listed twice", item.id))
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds an item by its ID.
+    pub fn get(&self, id: &str) -> Option<&Item> {
+        self.items.iter().find(|x| x.id == id)
+    }
+
+    /// How many coins an achievement worth `base_xp` (before multipliers) earns.
+    ///
+    /// Achievements that take XP away don't earn, or cost, any coins.
+    pub fn achievement_coins(&self, base_xp: i128) -> u64 {
+        if base_xp <= 0 {
+            return 0
+        }
+        (base_xp as f64 * self.coins_per_xp) as u64
+    }
+
+    /// The cosmetics a player has equipped, that are still in the catalog.
+    pub fn equipped<'a>(&'a self, player: &'a Player) -> impl Iterator<Item = &'a Cosmetic> {
+        player.equipped.iter().filter_map(|x| self.get(x)).map(|x| &x.cosmetic)
+    }
+
+    /// Buys an item for a player, spending their coins, and equips it.
+    ///
+    /// Returns the item, or why it can't be bought, in the language `lang`.
+    pub fn buy(&self, player: &mut Player, id: &str, lang: &str) -> Result<&Item, String> {
+        let Some(item) = self.get(id) else {
+            return Err(tr!(lang, "shop-unknown", item = id))
+        };
+        if player.inventory.iter().any(|x| x == id) {
+            return Err(tr!(lang, "shop-owned", item = item.name.as_str()))
+        }
+        if player.coins < item.price {
+            return Err(tr!(lang, "shop-no-coins", item = item.name.as_str(),
+                           price = item.price.to_string(), coins = player.coins.to_string()))
+        }
+        player.coins -= item.price;
+        player.inventory.push(item.id.clone());
+        tracing::info!(user_id = player.user_id, item = id, price = item.price, coins = player.coins, "Bought item");
+        // A full set of badges just means the new one isn't shown yet.
+        let _ = self.equip(player, id, lang);
+        Ok(item)
+    }
+
+    /// Equips an item the player owns, or unequips it if it's already equipped.
+    ///
+    /// Anything else in the same slot is unequipped. Returns the item, and
+    /// whether it's now equipped, or why it can't be, in the language `lang`.
+    pub fn equip(&self, player: &mut Player, id: &str, lang: &str) -> Result<(&Item, bool), String> {
+        let Some(item) = self.get(id) else {
+            return Err(tr!(lang, "shop-unknown", item = id))
+        };
+        if !player.inventory.iter().any(|x| x == id) {
+            return Err(tr!(lang, "shop-not-owned", item = item.name.as_str()))
+        }
+        if player.equipped.iter().any(|x| x == id) {
+            player.equipped.retain(|x| x != id);
+            return Ok((item, false))
+        }
+        let badges = self.equipped(player).filter(|x| matches!(x, Cosmetic::Badge(_))).count();
+        if matches!(item.cosmetic, Cosmetic::Badge(_)) && badges >= MAX_BADGES {
+            return Err(tr!(lang, "shop-too-many-badges", max = MAX_BADGES))
+        }
+        player.equipped.retain(|x| self.get(x).is_none_or(|x| !x.cosmetic.same_slot(&item.cosmetic)));
+        player.equipped.push(item.id.clone());
+        Ok((item, true))
+    }
+}
+
+/// Parses and validates a catalog.
+fn parse(data: &str) -> Result<Catalog, String> {
+    let catalog: Catalog = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    catalog.validate()?;
+    Ok(catalog)
+}
+
+/// Reads the catalog from [`SHOP_FILENAME`], or the built-in one if it's missing or invalid.
+fn load() -> Catalog {
+    let custom = fs::read_to_string(SHOP_FILENAME).ok().and_then(|data| {
+        parse(&data).inspect_err(|e| {
+            tracing::error!(error = %e, "Invalid {SHOP_FILENAME}, so the built-in shop will be used");
+        }).ok()
+    });
+    custom.unwrap_or_else(|| parse(DEFAULT_CATALOG).expect("The built-in shop is valid"))
+}
+
+static CATALOG: LazyLock<Catalog> = LazyLock::new(load);
+
+/// The shop's catalog, read the first time it's needed.
+pub fn catalog() -> &'static Catalog {
+    &CATALOG
+}
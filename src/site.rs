@@ -0,0 +1,144 @@
+//! Exports a static HTML leaderboard, which can be hosted anywhere.
+//!
+//! Works entirely offline, from `saved_data.json` and the [`audit`] log:
+//!
+//! ```text
+//! cargo run -- export-site <folder>
+//! ```
+//!
+//! Creates an `index.html` leaderboard, and a page per player in `players/`,
+//! with their title, achievements, and level history. Players are shown by
+//! their ID, as Discord can't be asked for their names while offline.
+
+use crate::{ Error, file_management, audit, player_data::{self, Player}, json_data::FileFormat };
+use std::fs;
+use std::path::Path;
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; background: #2b2d31; color: #dbdee1; }
+a { color: #00a8fc; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.4em 0.8em; border-bottom: 1px solid #3f4147; }
+.bar { font-family: monospace; }
+.revoked { text-decoration: line-through; opacity: 0.6; }
+";
+
+/// Escapes text so it can be safely put inside HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Wraps the body of a page in the HTML boilerplate.
+fn page(title: &str, style_path: &str, body: &str) -> String {
+    format!("<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<link rel=\"stylesheet\" href=\"{style_path}\">
+</head>
+<body>
+{body}
+<footer><p>Generated {}</p></footer>
+</body>
+</html>
+", escape(title), chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"))
+}
+
+/// The leaderboard page.
+fn leaderboard(ranked: &[(usize, Player)]) -> String {
+    let rows = ranked.iter().map(|(rank, p)| {
+        format!("<tr><td>#{rank}</td><td><a href=\"players/{id}.html\">{name}</a></td><td>{prestige}</td><td><span class=\"bar\">{bar}</span> {progress}</td></tr>",
+                id = p.user_id,
+                name = escape(&p.name_line(&p.user_id.to_string())),
+                prestige = if p.prestige > 1.0 { format!("{:.2}", p.prestige) } else { String::new() },
+                bar = p.xp_bar(),
+                progress = p.xp_progress())
+    }).collect::<Vec<_>>().join("\n");
+
+    page("Trophies Leaderboard", "style.css", &format!(
+        "<h1>🏆 Leaderboard</h1>
+<table>
+<tr><th>Rank</th><th>Player</th><th>Prestige</th><th>XP</th></tr>
+{rows}
+</table>"))
+}
+
+/// A single player's page.
+fn player_page(rank: usize, p: &Player, data: &FileFormat, history: &[audit::Entry]) -> String {
+    let achievements = data.achievements.iter()
+        .filter(|x| x.recipient_id == p.user_id)
+        .rev()
+        .map(|x| format!("<tr{}><td>#{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                         if x.revoked { " class=\"revoked\"" } else { "" },
+                         x.id,
+                         x.timestamp.format("%Y-%m-%d"),
+                         escape(&x.title),
+                         x.effective_xp))
+        .collect::<Vec<_>>().join("\n");
+
+    let levels = history.iter()
+        .filter_map(|entry| {
+            let change = match &entry.event {
+                audit::Event::LevelChanged { user_id, old_lvl, new_lvl, .. } if *user_id == p.user_id =>
+                    format!("Lv. {old_lvl} → Lv. {new_lvl}"),
+                audit::Event::Prestiged { title, player, .. } if player.user_id == p.user_id =>
+                    format!("Prestiged to {:.2}, gaining the title \"{}\"", player.prestige, escape(title)),
+                _ => return None,
+            };
+            Some(format!("<tr><td>{}</td><td>{change}</td></tr>", entry.timestamp.format("%Y-%m-%d %H:%M")))
+        })
+        .rev()
+        .collect::<Vec<_>>().join("\n");
+
+    let title = if p.title_segments.is_empty() { "<em>None yet</em>".to_string() } else { escape(p.title().trim_end()) };
+
+    page(&p.name_line(&p.user_id.to_string()), "../style.css", &format!(
+        "<p><a href=\"../index.html\">← Leaderboard</a></p>
+<h1>{name}</h1>
+<table>
+<tr><th>Rank</th><td>#{rank}</td></tr>
+<tr><th>Level</th><td>{lvl}</td></tr>
+<tr><th>XP</th><td><span class=\"bar\">{bar}</span> {progress}</td></tr>
+<tr><th>Prestige</th><td>{prestige:.2}</td></tr>
+<tr><th>Title</th><td>{title}</td></tr>
+</table>
+<h2>Level history</h2>
+<table>
+<tr><th>When</th><th>Change</th></tr>
+{levels}
+</table>
+<h2>Achievements</h2>
+<table>
+<tr><th>ID</th><th>When</th><th>Achievement</th><th>XP</th></tr>
+{achievements}
+</table>",
+        name = escape(&p.name_line(&p.user_id.to_string())),
+        lvl = p.lvl,
+        bar = p.xp_bar(),
+        progress = p.xp_progress(),
+        prestige = p.prestige))
+}
+
+/// Writes the whole site into `dir`, creating it if needed.
+///
+/// Returns the number of players exported.
+pub fn export(dir: &Path) -> Result<usize, Error> {
+    let data = file_management::load()?;
+    let history = audit::load();
+    let ranked = player_data::rank_players(data.player_list.clone());
+
+    fs::create_dir_all(dir.join("players"))?;
+    fs::write(dir.join("style.css"), STYLE.trim_start())?;
+    fs::write(dir.join("index.html"), leaderboard(&ranked))?;
+
+    for (rank, p) in &ranked {
+        fs::write(dir.join("players").join(format!("{}.html", p.user_id)), player_page(*rank, p, &data, &history))?;
+    }
+
+    Ok(ranked.len())
+}
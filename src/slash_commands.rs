@@ -6,15 +6,40 @@
 //! automatically uses.
 
 
-use crate::{commands, Context, Error, serenity};
+use crate::{bounties, commands, config, logging, transfer, Context, Error, serenity};
+use tracing::Instrument;
 
 /// Reset your progress, with an advantage.
-#[poise::command(slash_command, prefix_command)]
-pub async fn prestige(
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommand_required,
+    subcommands("prestige_start", "prestige_preview"),
+)]
+pub async fn prestige(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Prestige now, after checking what you'll get.
+#[poise::command(slash_command, prefix_command, rename = "start")]
+pub async fn prestige_start(
     ctx: Context<'_>,
     #[description="A new word to add to your Title."] title: String,
 ) -> Result<(),Error> {
-    commands::prestige(ctx,title).await
+    commands::prestige(ctx,title)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See what prestiging would do, without doing it.
+#[poise::command(slash_command, prefix_command, rename = "preview")]
+pub async fn prestige_preview(
+    ctx: Context<'_>,
+    #[description = "A word you might add to your Title"] title: Option<String>,
+) -> Result<(), Error> {
+    commands::prestige_preview(ctx, title)
+        .instrument(logging::command_span(ctx))
+        .await
 }
 
 /// Check your current XP, Level and Prestige.
@@ -23,7 +48,9 @@ pub async fn level(
     ctx: Context<'_>,
     #[description = "Selected User"] user: Option<serenity::User>,
 ) -> Result<(), Error> {
-    commands::level(ctx, user).await
+    commands::level(ctx, user)
+        .instrument(logging::command_span(ctx))
+        .await
 }
 
 /// Complete an Achievement, and gain XP.
@@ -33,9 +60,35 @@ pub async fn achievement(
     #[description = "Title of your achievement"] title: String,
     #[description = "XP Achieved"] xp: i64,
     #[description = "Recipient of Achievement"] recipient: Option<serenity::User>,
+    #[description = "What kind of achievement it is"]
+    #[autocomplete = "commands::autocomplete_category"] category: Option<String>,
 ) -> Result<(),Error> {
-    println!("RECEIVED ACHIEVEMENT COMMAND - {xp}");
-    commands::achievement(ctx, title, xp as i128, recipient).await
+    commands::achievement(ctx, title, xp as i128, recipient, category)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See your XP and achievements, split up by category.
+#[poise::command(slash_command, prefix_command)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    commands::stats(ctx, user)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Choose the language the bot replies to you in.
+#[poise::command(slash_command)]
+pub async fn language(
+    ctx: Context<'_>,
+    #[description = "Your language, or auto to use your Discord's"]
+    #[choices("auto", "en", "es")] language: &'static str,
+) -> Result<(), Error> {
+    commands::language(ctx, language)
+        .instrument(logging::command_span(ctx))
+        .await
 }
 
 /// Reregister application commands with Discord.
@@ -45,12 +98,661 @@ pub async fn register(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Change a word in your title.
+#[poise::command(slash_command, prefix_command, rename = "title")]
+pub async fn update_title(
+    ctx: Context<'_>,
+    #[description = "Which word to change (1 is the first word)"]
+    #[min = 1]
+    index: u64,
+    #[description = "The new word"] word: String,
+) -> Result<(),Error> {
+    commands::update_title(ctx, index as usize, word)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Spend the points you earn by prestiging on perks.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommand_required,
+    subcommands("perks_view", "perks_buy"),
+)]
+pub async fn perks(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// See your perk points, and every perk you can buy.
+#[poise::command(slash_command, prefix_command, rename = "view")]
+pub async fn perks_view(ctx: Context<'_>) -> Result<(), Error> {
+    commands::perks_view(ctx)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Buy the next rank of a perk.
+#[poise::command(slash_command, prefix_command, rename = "buy")]
+pub async fn perks_buy(
+    ctx: Context<'_>,
+    #[description = "The perk to buy"]
+    #[autocomplete = "commands::autocomplete_perk"] perk: String,
+) -> Result<(), Error> {
+    commands::perks_buy(ctx, perk)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Spend the coins you earn from achievements and levelling up.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommand_required,
+    subcommands("shop_view", "shop_buy", "shop_equip"),
+)]
+pub async fn shop(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// See your coins, and everything in the shop.
+#[poise::command(slash_command, prefix_command, rename = "view")]
+pub async fn shop_view(ctx: Context<'_>) -> Result<(), Error> {
+    commands::shop_view(ctx)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Buy an item from the shop.
+#[poise::command(slash_command, prefix_command, rename = "buy")]
+pub async fn shop_buy(
+    ctx: Context<'_>,
+    #[description = "The item to buy"]
+    #[autocomplete = "commands::autocomplete_item"] item: String,
+) -> Result<(), Error> {
+    commands::shop_buy(ctx, item)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Equip an item you own, or take it off.
+#[poise::command(slash_command, prefix_command, rename = "equip")]
+pub async fn shop_equip(
+    ctx: Context<'_>,
+    #[description = "The item to equip or unequip"]
+    #[autocomplete = "commands::autocomplete_owned_item"] item: String,
+) -> Result<(), Error> {
+    commands::shop_equip(ctx, item)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See who's at the top.
 #[poise::command(slash_command, prefix_command)]
-pub async fn update_title(ctx: Context<'_>) -> Result<(),Error> {
-    commands::update_title(ctx).await
+pub async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "How to sort players (default: level)"] sort: Option<commands::LeaderboardSort>,
+    #[description = "Which page to show (default: 1)"]
+    #[min = 1]
+    page: Option<u64>,
+    #[description = "Only count achievements in this category"]
+    #[autocomplete = "commands::autocomplete_category"] category: Option<String>,
+) -> Result<(),Error> {
+    commands::leaderboard(ctx, sort.unwrap_or(commands::LeaderboardSort::Level), page.unwrap_or(1) as usize, category)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Compare two players side by side.
+#[poise::command(slash_command, prefix_command)]
+pub async fn compare(
+    ctx: Context<'_>,
+    #[description = "The first player"] user_a: serenity::User,
+    #[description = "The second player (default: you)"] user_b: Option<serenity::User>,
+) -> Result<(), Error> {
+    commands::compare(ctx, user_a, user_b)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Form a team with your friends, and earn XP together.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommand_required,
+    subcommands("team_create", "team_invite", "team_join", "team_leave", "team_kick", "team_info", "team_leaderboard"),
+)]
+pub async fn team(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Create a new team.
+#[poise::command(slash_command, prefix_command, rename = "create")]
+pub async fn team_create(
+    ctx: Context<'_>,
+    #[description = "What the team is called"] name: String,
+    #[description = "A short tag for the team, like ABC"] tag: String,
+) -> Result<(), Error> {
+    commands::team_create(ctx, name, tag)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Invite somebody to your team.
+#[poise::command(slash_command, prefix_command, rename = "invite")]
+pub async fn team_invite(
+    ctx: Context<'_>,
+    #[description = "Who to invite"] user: serenity::User,
+) -> Result<(), Error> {
+    commands::team_invite(ctx, user)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Join a team you've been invited to.
+#[poise::command(slash_command, prefix_command, rename = "join")]
+pub async fn team_join(
+    ctx: Context<'_>,
+    #[description = "The team's name or tag"]
+    #[autocomplete = "commands::autocomplete_team"] team: String,
+) -> Result<(), Error> {
+    commands::team_join(ctx, team)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Leave your team.
+#[poise::command(slash_command, prefix_command, rename = "leave")]
+pub async fn team_leave(ctx: Context<'_>) -> Result<(), Error> {
+    commands::team_leave(ctx)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Remove somebody from your team, or cancel their invite.
+#[poise::command(slash_command, prefix_command, rename = "kick")]
+pub async fn team_kick(
+    ctx: Context<'_>,
+    #[description = "Who to remove"] user: serenity::User,
+) -> Result<(), Error> {
+    commands::team_kick(ctx, user)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See a team's level and members.
+#[poise::command(slash_command, prefix_command, rename = "info")]
+pub async fn team_info(
+    ctx: Context<'_>,
+    #[description = "The team's name or tag (default: your team)"]
+    #[autocomplete = "commands::autocomplete_team"] team: Option<String>,
+) -> Result<(), Error> {
+    commands::team_info(ctx, team)
+        .instrument(logging::command_span(ctx))
+        .await
 }
 
+/// See which teams have earned the most XP.
+#[poise::command(slash_command, prefix_command, rename = "leaderboard")]
+pub async fn team_leaderboard(
+    ctx: Context<'_>,
+    #[description = "Which page to show (default: 1)"]
+    #[min = 1]
+    page: Option<u64>,
+) -> Result<(), Error> {
+    commands::team_leaderboard(ctx, page.unwrap_or(1) as usize)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Challenge somebody to a wager of XP.
 #[poise::command(slash_command, prefix_command)]
-pub async fn leaderboard(ctx: Context<'_>) -> Result<(),Error> {
-    commands::leaderboard(ctx).await
+pub async fn challenge(
+    ctx: Context<'_>,
+    #[description = "Who to challenge"] user: serenity::User,
+    #[description = "How much XP each of you puts in"]
+    #[min = 1]
+    xp: i64,
+    #[description = "What the challenge is"] description: String,
+) -> Result<(), Error> {
+    commands::challenge(ctx, user, xp as i128, description)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Post, claim and pay out bounties.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommand_required,
+    subcommands("bounty_post", "bounty_claim", "bounty_view", "bounty_approve", "bounty_cancel"),
+)]
+pub async fn bounty(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Post a bounty, paid for with your own XP.
+#[poise::command(slash_command, prefix_command, rename = "post")]
+pub async fn bounty_post(
+    ctx: Context<'_>,
+    #[description = "What needs doing"] title: String,
+    #[description = "The XP to reward, held from your current level"]
+    #[min = 1]
+    xp: i64,
+    #[description = "How many days until it expires (default: 7)"]
+    #[min = 1]
+    #[max = 30]
+    days: Option<u64>,
+) -> Result<(), Error> {
+    commands::bounty_post(ctx, title, xp as i128, days.map_or(bounties::DEFAULT_DAYS, |x| x as i64))
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Claim a bounty.
+#[poise::command(slash_command, prefix_command, rename = "claim")]
+pub async fn bounty_claim(
+    ctx: Context<'_>,
+    #[description = "The bounty to claim"]
+    #[autocomplete = "commands::autocomplete_bounty"] id: u64,
+    #[description = "How you did what it asks"] proof: String,
+) -> Result<(), Error> {
+    commands::bounty_claim(ctx, id, proof)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See a bounty and its claims.
+#[poise::command(slash_command, prefix_command, rename = "view")]
+pub async fn bounty_view(
+    ctx: Context<'_>,
+    #[description = "The bounty to see"]
+    #[autocomplete = "commands::autocomplete_bounty"] id: u64,
+) -> Result<(), Error> {
+    commands::bounty_view(ctx, id)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Approve a claim on your bounty, and pay the claimant.
+#[poise::command(slash_command, prefix_command, rename = "approve")]
+pub async fn bounty_approve(
+    ctx: Context<'_>,
+    #[description = "Your bounty"]
+    #[autocomplete = "commands::autocomplete_bounty"] id: u64,
+    #[description = "Who to pay"] user: serenity::User,
+) -> Result<(), Error> {
+    commands::bounty_approve(ctx, id, user)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Take down your bounty, getting its XP back.
+#[poise::command(slash_command, prefix_command, rename = "cancel")]
+pub async fn bounty_cancel(
+    ctx: Context<'_>,
+    #[description = "Your bounty"]
+    #[autocomplete = "commands::autocomplete_bounty"] id: u64,
+) -> Result<(), Error> {
+    commands::bounty_cancel(ctx, id)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// See the open bounties in this server.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn bounties(
+    ctx: Context<'_>,
+    #[description = "Which page to show (default: 1)"]
+    #[min = 1]
+    page: Option<u64>,
+) -> Result<(), Error> {
+    commands::bounties(ctx, page.unwrap_or(1) as usize)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Moderation tools for fixing people's data.
+///
+/// Usable by anybody with Manage Server, or the server's admin role. The
+/// subcommands that change players' data are only usable by the bot's owners.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommand_required,
+    check = "config::is_admin",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands(
+        "admin_set_xp",
+        "admin_adjust_xp",
+        "admin_set_level",
+        "admin_set_prestige",
+        "admin_reset",
+        "admin_remove_title",
+        "admin_set_title",
+        "admin_title_filter",
+        "admin_categories",
+        "admin_delete",
+        "admin_revoke",
+        "admin_history",
+        "admin_limits",
+        "admin_digest",
+        "admin_export",
+        "admin_import",
+        "admin_migrate",
+        "admin_bounty",
+    ),
+)]
+pub async fn admin(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set a player's XP to an exact amount.
+#[poise::command(slash_command, prefix_command, rename = "set_xp", check = "config::is_owner")]
+pub async fn admin_set_xp(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "New XP"] xp: i64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::set_xp(ctx, user, xp as i128, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Add or remove XP from a player, ignoring their prestige.
+#[poise::command(slash_command, prefix_command, rename = "adjust_xp", check = "config::is_owner")]
+pub async fn admin_adjust_xp(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "XP to add (negative to remove)"] amount: i64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::adjust_xp(ctx, user, amount as i128, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Set a player's level.
+#[poise::command(slash_command, prefix_command, rename = "set_level", check = "config::is_owner")]
+pub async fn admin_set_level(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "New level"] level: i64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::set_level(ctx, user, level, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Set a player's prestige.
+#[poise::command(slash_command, prefix_command, rename = "set_prestige", check = "config::is_owner")]
+pub async fn admin_set_prestige(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "New prestige"] prestige: f64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::set_prestige(ctx, user, prestige, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Reset a player's progress entirely.
+#[poise::command(slash_command, prefix_command, rename = "reset", check = "config::is_owner")]
+pub async fn admin_reset(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::reset(ctx, user, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Remove a word from a player's title.
+#[poise::command(slash_command, prefix_command, rename = "remove_title")]
+pub async fn admin_remove_title(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "Which word to remove (1 is the first word)"] index: u64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::remove_title(ctx, user, index as usize, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Set a word in a player's title, ignoring the banned word list.
+#[poise::command(slash_command, prefix_command, rename = "set_title")]
+pub async fn admin_set_title(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "The new word"] word: String,
+    #[description = "Which word to replace (default: add a new word at the end)"]
+    #[min = 1]
+    index: Option<u64>,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::set_title(ctx, user, index.map(|x| x as usize), word, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// View or change the words that can't be used in titles.
+#[poise::command(slash_command, prefix_command, rename = "title_filter")]
+pub async fn admin_title_filter(
+    ctx: Context<'_>,
+    #[description = "Whether to add, remove or list banned words"] action: commands::admin::FilterAction,
+    #[description = "The word to add or remove"] word: Option<String>,
+    #[description = "Why this change is being made"] reason: Option<String>,
+) -> Result<(), Error> {
+    commands::admin::title_filter(ctx, action, word, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// View or change the categories achievements can be granted under.
+#[poise::command(slash_command, prefix_command, rename = "categories")]
+pub async fn admin_categories(
+    ctx: Context<'_>,
+    #[description = "Whether to add, remove or list categories"] action: commands::admin::FilterAction,
+    #[description = "The category to add or remove"]
+    #[autocomplete = "commands::autocomplete_category"] name: Option<String>,
+    #[description = "Why this change is being made"] reason: Option<String>,
+) -> Result<(), Error> {
+    commands::admin::categories(ctx, action, name, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Delete a player's data.
+#[poise::command(slash_command, prefix_command, rename = "delete", check = "config::is_owner")]
+pub async fn admin_delete(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::delete(ctx, user, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Revoke a past achievement, taking back its XP.
+#[poise::command(slash_command, prefix_command, rename = "revoke")]
+pub async fn admin_revoke(
+    ctx: Context<'_>,
+    #[description = "ID of the achievement, shown at the bottom of it"] achievement_id: u64,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::revoke(ctx, achievement_id, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// List a player's recent achievements and their IDs.
+#[poise::command(slash_command, prefix_command, rename = "history")]
+pub async fn admin_history(
+    ctx: Context<'_>,
+    #[description = "Selected User"] user: serenity::User,
+) -> Result<(), Error> {
+    commands::admin::history(ctx, user)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// View or change the limits on granting XP in this server.
+#[poise::command(slash_command, prefix_command, rename = "limits")]
+pub async fn admin_limits(
+    ctx: Context<'_>,
+    #[description = "Seconds between granting achievements"] cooldown_secs: Option<i64>,
+    #[description = "Most XP somebody can grant per day"] daily_granted_cap: Option<i64>,
+    #[description = "Most XP somebody can receive per day"] daily_received_cap: Option<i64>,
+    #[description = "Most XP a single achievement can be worth"] max_xp_per_achievement: Option<i64>,
+    #[description = "Grants each way between two users per day before it looks suspicious"] reciprocal_limit: Option<u64>,
+    #[description = "Why this change is being made"] reason: Option<String>,
+) -> Result<(), Error> {
+    commands::admin::limits(
+        ctx,
+        cooldown_secs,
+        daily_granted_cap.map(|x| x as i128),
+        daily_received_cap.map(|x| x as i128),
+        max_xp_per_achievement.map(|x| x as i128),
+        reciprocal_limit.map(|x| x as usize),
+        reason,
+    )
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// View or change when a summary of the day or week is posted.
+#[poise::command(slash_command, prefix_command, rename = "digest")]
+pub async fn admin_digest(
+    ctx: Context<'_>,
+    #[description = "How often to post"] period: Option<commands::admin::DigestSetting>,
+    #[description = "Channel to post in"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+    #[description = "Hour of the day to post at, in UTC (default 0)"]
+    #[min = 0]
+    #[max = 23]
+    hour: Option<u32>,
+    #[description = "Day of the week to post weekly digests on (default Monday)"] weekday: Option<String>,
+    #[description = "Only include achievements in this category, or \"none\" for all of them"]
+    #[autocomplete = "commands::autocomplete_category"] category: Option<String>,
+    #[description = "Why this change is being made"] reason: Option<String>,
+) -> Result<(), Error> {
+    commands::admin::digest(ctx, period, channel.map(|x| x.id), hour, weekday, category, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Export every player's data as a CSV or JSON file.
+#[poise::command(slash_command, prefix_command, rename = "export", check = "config::is_owner")]
+pub async fn admin_export(
+    ctx: Context<'_>,
+    #[description = "File format"] format: transfer::Format,
+) -> Result<(), Error> {
+    commands::admin::export(ctx, format)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Import players from a CSV or JSON file made by /admin export.
+#[poise::command(slash_command, prefix_command, rename = "import", check = "config::is_owner")]
+pub async fn admin_import(
+    ctx: Context<'_>,
+    #[description = "A .csv or .json file"] file: serenity::Attachment,
+    #[description = "What to do with players who already exist"] strategy: transfer::Strategy,
+    #[description = "Only show what would change (default: true)"] dry_run: Option<bool>,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::import(ctx, file, strategy, dry_run.unwrap_or(true), reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Bring players over from another leveling bot's leaderboard export.
+#[poise::command(slash_command, prefix_command, rename = "migrate", check = "config::is_owner")]
+pub async fn admin_migrate(
+    ctx: Context<'_>,
+    #[description = "The other bot's leaderboard, as a .csv or .json file"] file: serenity::Attachment,
+    #[description = "What to do with players who already exist (default: skip)"] strategy: Option<transfer::Strategy>,
+    #[description = "Only show what would change (default: true)"] dry_run: Option<bool>,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::migrate(ctx, file, strategy.unwrap_or(transfer::Strategy::Skip), dry_run.unwrap_or(true), reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Post a bounty whose reward doesn't come from anybody's XP.
+#[poise::command(slash_command, prefix_command, rename = "bounty")]
+pub async fn admin_bounty(
+    ctx: Context<'_>,
+    #[description = "What needs doing"] title: String,
+    #[description = "The XP to reward"]
+    #[min = 1]
+    xp: i64,
+    #[description = "How many days until it expires (default: 7)"]
+    #[min = 1]
+    #[max = 30]
+    days: Option<u64>,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    commands::admin::bounty(ctx, title, xp as i128, days.map_or(bounties::DEFAULT_DAYS, |x| x as i64), reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// View and change this server's settings.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands("config_get", "config_set", "config_reset"),
+)]
+pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show this server's settings.
+#[poise::command(slash_command, prefix_command, rename = "get")]
+pub async fn config_get(
+    ctx: Context<'_>,
+    #[description = "A single setting to show (default: all of them)"] key: Option<config::Key>,
+) -> Result<(), Error> {
+    config::get(ctx, key)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Change one of this server's settings.
+#[poise::command(slash_command, prefix_command, rename = "set")]
+pub async fn config_set(
+    ctx: Context<'_>,
+    #[description = "The setting to change"] key: config::Key,
+    #[description = "The new value. Use \"none\" to clear a channel or role"] value: String,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    config::set(ctx, key, value, reason)
+        .instrument(logging::command_span(ctx))
+        .await
+}
+
+/// Put one, or all, of this server's settings back to the defaults.
+#[poise::command(slash_command, prefix_command, rename = "reset")]
+pub async fn config_reset(
+    ctx: Context<'_>,
+    #[description = "The setting to reset (default: all of them)"] key: Option<config::Key>,
+    #[description = "Why this change is being made"] reason: String,
+) -> Result<(), Error> {
+    config::reset(ctx, key, reason)
+        .instrument(logging::command_span(ctx))
+        .await
 }
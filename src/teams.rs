@@ -0,0 +1,266 @@
+//! Teams of players, who earn XP together.
+//!
+//! A team's XP is the total of the XP its members have earned with
+//! [`Player::add_xp`](crate::player_data::Player::add_xp) while they were on
+//! it. It's added up as the XP is earned, rather than from whoever is on the
+//! team now, so players joining or leaving never move XP from one team to
+//! another. Revoking an achievement takes its XP back off the team it was
+//! [credited](crate::achievement_data::AchievementRecord::team_id) to, even if
+//! the player has since left.
+//!
+//! Teams level up on their own [curve](Team::level), since a whole team
+//! earns XP much faster than any one player.
+//!
+//! Only a team's owner can invite and kick members. When the owner leaves,
+//! whoever has been on the team longest takes over, and a team is disbanded
+//! once its last member leaves.
+
+use crate::{ Serialize, Deserialize, i18n::tr };
+use chrono::{DateTime, Utc};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The longest a team name can be, in characters.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// The shortest and longest a team tag can be.
+pub const MIN_TAG_LENGTH: usize = 2;
+pub const MAX_TAG_LENGTH: usize = 5;
+
+/// The most players a team can have, including its owner.
+pub const MAX_MEMBERS: usize = 10;
+
+/// The XP a team needs to reach level 2. Every level after that needs this much more than the last.
+pub const LEVEL_XP: i128 = 500;
+
+/// What happened to a team's members, in an [audit event](crate::audit::Event::TeamChanged).
+#[derive(Serialize,Deserialize,Clone,Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Created,
+    Invited,
+    Joined,
+    Left,
+    Kicked,
+}
+
+/// A team, and the XP its members have earned for it.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Team {
+    pub id: u64,
+
+    /// What the team is called. Unique, ignoring case.
+    pub name: String,
+
+    /// A short, upper case tag shown before the name, like `[ABC]`. Unique.
+    pub tag: String,
+
+    /// The member who can invite and kick players.
+    pub owner_id: u64,
+
+    /// Everybody on the team, including the owner, in the order they joined.
+    pub members: Vec<u64>,
+
+    /// Players who have been invited, but haven't joined yet.
+    #[serde(default)]
+    pub invites: Vec<u64>,
+
+    /// The XP members have earned while on the team, after multipliers.
+    pub xp: i128,
+
+    /// When the team was created.
+    pub created: DateTime<Utc>,
+}
+
+impl Team {
+
+    /// The tag and name together, like `[ABC] Some Team`.
+    pub fn display_name(&self) -> String {
+        format!("[{}] {}", self.tag, self.name)
+    }
+
+    /// The total XP needed to reach a level.
+    ///
+    /// Uses this formula, so that each level needs [`LEVEL_XP`] more than the last:
+    /// ```
+    /// LEVEL_XP * lvl * (lvl - 1) / 2
+    /// ```
+    fn level_start(lvl: i64) -> i128 {
+        let lvl = lvl as i128;
+        LEVEL_XP.saturating_mul(lvl).saturating_mul(lvl - 1) / 2
+    }
+
+    /// The team's level, starting at 1.
+    pub fn level(&self) -> i64 {
+        if self.xp <= 0 {
+            return 1
+        }
+        // Solve the curve, then correct for any rounding.
+        let mut lvl = ((1.0 + (1.0 + 8.0 * self.xp as f64 / LEVEL_XP as f64).sqrt()) / 2.0) as i64;
+        while lvl > 1 && Self::level_start(lvl) > self.xp {
+            lvl -= 1;
+        }
+        while Self::level_start(lvl + 1) <= self.xp {
+            lvl += 1;
+        }
+        lvl.max(1)
+    }
+
+    /// How far the team is through its current level, like `(200 / 1500)`.
+    pub fn xp_progress(&self) -> String {
+        let lvl = self.level();
+        let start = Self::level_start(lvl);
+        format!("({} / {})", self.xp - start, Self::level_start(lvl + 1) - start)
+    }
+}
+
+/// Finds the team a player is on, if any.
+pub fn team_of(teams: &[Team], user_id: u64) -> Option<&Team> {
+    teams.iter().find(|x| x.members.contains(&user_id))
+}
+
+/// Finds a team by its name or tag, ignoring case.
+pub fn find<'a>(teams: &'a [Team], name: &str) -> Option<&'a Team> {
+    position(teams, name).map(|x| &teams[x])
+}
+
+/// Like [`find`], but returns the team's index, for changing it.
+fn position(teams: &[Team], name: &str) -> Option<usize> {
+    let name = name.trim().to_lowercase();
+    teams.iter().position(|x| x.name.to_lowercase() == name || x.tag.to_lowercase() == name)
+}
+
+/// Adds XP to a team, if there is one. Used when a member earns XP, and
+/// (with negative XP) when an achievement is revoked.
+pub fn credit(teams: &mut [Team], team_id: Option<u64>, xp: i128) {
+    let Some(team) = team_id.and_then(|id| teams.iter_mut().find(|x| x.id == id)) else {
+        return
+    };
+    team.xp += xp;
+    tracing::debug!(team_id = team.id, xp = %xp, team_xp = %team.xp, "Credited team XP");
+}
+
+/// Creates a new team, owned by `owner_id`.
+///
+/// Returns the team, or why it can't be created, in the language `lang`.
+pub fn create<'a>(teams: &'a mut Vec<Team>, owner_id: u64, name: &str, tag: &str, lang: &str) -> Result<&'a Team, String> {
+    if team_of(teams, owner_id).is_some() {
+        return Err(tr!(lang, "team-already-member"))
+    }
+    let name = name.trim();
+    let tag = tag.trim().to_uppercase();
+    let length = name.graphemes(true).count();
+    if length == 0 || length > MAX_NAME_LENGTH || name.contains(['@', '<', '>', '\n']) {
+        return Err(tr!(lang, "team-invalid-name", max = MAX_NAME_LENGTH))
+    }
+    if !(MIN_TAG_LENGTH..=MAX_TAG_LENGTH).contains(&tag.len()) || !tag.chars().all(|x| x.is_ascii_alphanumeric()) {
+        return Err(tr!(lang, "team-invalid-tag", min = MIN_TAG_LENGTH, max = MAX_TAG_LENGTH))
+    }
+    if find(teams, name).is_some() || find(teams, &tag).is_some() {
+        return Err(tr!(lang, "team-taken"))
+    }
+
+    let id = teams.iter().map(|x| x.id + 1).max().unwrap_or(1);
+    teams.push(Team {
+        id,
+        name: name.to_owned(),
+        tag,
+        owner_id,
+        members: vec![owner_id],
+        invites: vec![],
+        xp: 0,
+        created: Utc::now(),
+    });
+    tracing::info!(team_id = id, owner_id, "Created team");
+    Ok(&teams[teams.len() - 1])
+}
+
+/// Finds the team that `owner_id` owns, for commands only the owner can use.
+fn owned_mut<'a>(teams: &'a mut [Team], owner_id: u64, lang: &str) -> Result<&'a mut Team, String> {
+    match teams.iter_mut().find(|x| x.members.contains(&owner_id)) {
+        None => Err(tr!(lang, "team-not-member")),
+        Some(team) if team.owner_id != owner_id => Err(tr!(lang, "team-not-owner")),
+        Some(team) => Ok(team),
+    }
+}
+
+/// Invites a player to the team that `owner_id` owns.
+pub fn invite<'a>(teams: &'a mut [Team], owner_id: u64, user_id: u64, lang: &str) -> Result<&'a Team, String> {
+    let team = owned_mut(teams, owner_id, lang)?;
+    if team.members.contains(&user_id) {
+        return Err(tr!(lang, "team-invite-member", user = format!("<@{user_id}>")))
+    }
+    if team.members.len() >= MAX_MEMBERS {
+        return Err(tr!(lang, "team-full", team = team.display_name(), max = MAX_MEMBERS))
+    }
+    if !team.invites.contains(&user_id) {
+        team.invites.push(user_id);
+    }
+    Ok(team)
+}
+
+/// Joins a team that `user_id` has been invited to.
+pub fn join<'a>(teams: &'a mut [Team], user_id: u64, name: &str, lang: &str) -> Result<&'a Team, String> {
+    if team_of(teams, user_id).is_some() {
+        return Err(tr!(lang, "team-already-member"))
+    }
+    let Some(index) = position(teams, name) else {
+        return Err(tr!(lang, "team-unknown", team = name.trim()))
+    };
+    let team = &mut teams[index];
+    if !team.invites.contains(&user_id) {
+        return Err(tr!(lang, "team-not-invited", team = team.display_name()))
+    }
+    if team.members.len() >= MAX_MEMBERS {
+        return Err(tr!(lang, "team-full", team = team.display_name(), max = MAX_MEMBERS))
+    }
+    team.invites.retain(|x| *x != user_id);
+    team.members.push(user_id);
+    tracing::info!(team_id = team.id, user_id, "Joined team");
+    Ok(team)
+}
+
+/// Takes a player off their team.
+///
+/// If they owned it, the longest-standing member takes over. Returns the team
+/// as it is now, and whether it was disbanded because nobody was left.
+pub fn leave(teams: &mut Vec<Team>, user_id: u64, lang: &str) -> Result<(Team, bool), String> {
+    let Some(index) = teams.iter().position(|x| x.members.contains(&user_id)) else {
+        return Err(tr!(lang, "team-not-member"))
+    };
+    let team = &mut teams[index];
+    team.members.retain(|x| *x != user_id);
+    tracing::info!(team_id = team.id, user_id, "Left team");
+    match team.members.first() {
+        None => {
+            tracing::info!(team_id = team.id, "Disbanded team");
+            Ok((teams.remove(index), true))
+        },
+        Some(&next) => {
+            if team.owner_id == user_id {
+                team.owner_id = next;
+            }
+            Ok((team.clone(), false))
+        },
+    }
+}
+
+/// Removes a member from the team that `owner_id` owns, or cancels their invite.
+pub fn kick<'a>(teams: &'a mut [Team], owner_id: u64, user_id: u64, lang: &str) -> Result<&'a Team, String> {
+    let team = owned_mut(teams, owner_id, lang)?;
+    if user_id == owner_id {
+        return Err(tr!(lang, "team-kick-self"))
+    }
+    if !team.members.contains(&user_id) && !team.invites.contains(&user_id) {
+        return Err(tr!(lang, "team-kick-not-member", user = format!("<@{user_id}>")))
+    }
+    team.members.retain(|x| *x != user_id);
+    team.invites.retain(|x| *x != user_id);
+    tracing::info!(team_id = team.id, user_id, "Kicked from team");
+    Ok(team)
+}
+
+/// Every team, highest XP first. Ties go to whichever team was created first.
+pub fn rank_teams(mut teams: Vec<Team>) -> Vec<Team> {
+    teams.sort_by(|a, b| b.xp.cmp(&a.xp).then(a.id.cmp(&b.id)));
+    teams
+}
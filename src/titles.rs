@@ -0,0 +1,143 @@
+//! Checks words that people want to add to their title.
+//!
+//! Used by [`/prestige`](crate::commands::prestige) and
+//! [`/title`](crate::commands::update_title). A title word has to be:
+//! - At most [`MAX_LENGTH`] characters long, counting each emoji or accented
+//!   letter as one character.
+//! - A single word, with no spaces of any kind.
+//! - Free of invisible and control characters, like zero-width spaces.
+//!   Zero-width joiners and tags are still allowed inside emoji, like 👩‍💻.
+//! - Free of mentions, so a title can't look like it pings somebody.
+//! - Not one of the server's [banned words](GuildConfig::banned_title_words).
+//!
+//! Words are normalised to NFC first, so accented letters typed different
+//! ways are treated the same.
+//!
+//! Banned words are matched after [folding](fold) both words, so `B4DW0RD`,
+//! `Bädwörd` and `baaadword` all match `badword`. Anything containing a banned
+//! word is rejected, so short banned words can catch innocent titles - a
+//! moderator can always set a title by hand with
+//! [`/admin set_title`](crate::commands::admin::set_title), which skips the
+//! banned word list.
+
+use crate::{ i18n::tr, guild_config::GuildConfig };
+use unicode_normalization::{ UnicodeNormalization, char::is_combining_mark };
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The most characters a title word can have.
+pub const MAX_LENGTH: usize = 10;
+
+/// The most code points a single character can be made of.
+///
+/// Enough for any emoji, but stops letters with dozens of accents piled on top.
+const MAX_CHARACTER_PARTS: usize = 10;
+
+/// The most banned words a server can have.
+pub const MAX_BANNED_WORDS: usize = 200;
+
+/// Joins emoji together, like 👩 and 💻 into 👩‍💻.
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// The black flag, which tag characters turn into flags like 🏴󠁧󠁢󠁳󠁣󠁴󠁿.
+const BLACK_FLAG: char = '\u{1F3F4}';
+
+/// Characters that don't show up, or that change how the text around them is shown.
+fn is_invisible(c: char) -> bool {
+    c.is_control() || matches!(c,
+        '\u{00AD}' | '\u{034F}' | '\u{061C}' | '\u{115F}' | '\u{1160}' | '\u{17B4}' | '\u{17B5}'
+        | '\u{180B}'..='\u{180F}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}'
+        | '\u{2060}'..='\u{206F}' | '\u{2800}' | '\u{3164}' | '\u{FEFF}' | '\u{FFA0}'
+        | '\u{E0000}'..='\u{E007F}')
+}
+
+/// Whether a character (grapheme) hides anything, apart from the parts of emoji.
+fn has_hidden_parts(grapheme: &str) -> bool {
+    let flag = grapheme.starts_with(BLACK_FLAG);
+    let last = grapheme.chars().count() - 1;
+    grapheme.chars().enumerate().any(|(i, c)| match c {
+        ZERO_WIDTH_JOINER => i == 0 || i == last,
+        '\u{E0020}'..='\u{E007F}' => !flag,
+        c => is_invisible(c),
+    })
+}
+
+/// Whether a word would show up as a mention of a user, role, channel or command.
+fn has_mention(word: &str) -> bool {
+    let word = word.nfkc().collect::<String>();
+    word.contains('@') || word.contains("<#") || word.contains("</")
+}
+
+/// Reduces a word to plain lowercase letters, so that lookalikes of a banned word still match it.
+///
+/// Removes accents, undoes common leetspeak, drops anything that isn't a
+/// letter or number, and squashes repeated letters: `Ĥ4ẌX0Ŕ!` becomes `haxori`.
+pub fn fold(word: &str) -> String {
+    let mut folded = String::new();
+    for c in word.nfkd().filter(|c| !is_combining_mark(*c)).flat_map(char::to_lowercase) {
+        let c = match c {
+            '0' => 'o',
+            '1' | '!' | '|' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' | '+' => 't',
+            '8' => 'b',
+            '9' => 'g',
+            c => c,
+        };
+        if c.is_alphanumeric() && !folded.ends_with(c) {
+            folded.push(c);
+        }
+    }
+    folded
+}
+
+/// Checks that a word can be used in a title in any server, ignoring banned words.
+///
+/// Returns the word, trimmed and normalised, or why it can't be used, in the language `lang`.
+pub fn check_format(word: &str, lang: &str) -> Result<String, String> {
+    let word = word.trim().nfc().collect::<String>();
+    let graphemes = word.graphemes(true).collect::<Vec<_>>();
+
+    if graphemes.is_empty() {
+        return Err(tr!(lang, "title-empty"))
+    }
+    if graphemes.len() > MAX_LENGTH {
+        return Err(tr!(lang, "title-too-long", max = MAX_LENGTH))
+    }
+    if word.chars().any(char::is_whitespace) {
+        return Err(tr!(lang, "title-one-word"))
+    }
+    if graphemes.iter().any(|x| has_hidden_parts(x)) {
+        return Err(tr!(lang, "title-invisible"))
+    }
+    if graphemes.iter().any(|x| x.chars().count() > MAX_CHARACTER_PARTS) {
+        return Err(tr!(lang, "title-cluttered"))
+    }
+    if has_mention(&word) {
+        return Err(tr!(lang, "title-mention"))
+    }
+    Ok(word)
+}
+
+/// The first of a server's banned words that's hidden in `word`, if any.
+pub fn banned_word<'a>(word: &str, config: &'a GuildConfig) -> Option<&'a str> {
+    let folded = fold(word);
+    config.banned_title_words.iter()
+        .find(|banned| {
+            let banned = fold(banned);
+            !banned.is_empty() && folded.contains(&banned)
+        })
+        .map(String::as_str)
+}
+
+/// Checks that a word can be used in a title in a server.
+///
+/// Like [`check_format()`], but also rejects the server's banned words.
+pub fn check(word: &str, config: &GuildConfig, lang: &str) -> Result<String, String> {
+    let word = check_format(word, lang)?;
+    if banned_word(&word, config).is_some() {
+        return Err(tr!(lang, "title-banned"))
+    }
+    Ok(word)
+}
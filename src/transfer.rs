@@ -0,0 +1,269 @@
+//! Moving player data in and out of the bot, as CSV or JSON.
+//!
+//! Used by both the [`/admin export` and `/admin import`](crate::commands::admin)
+//! commands, and the `export-players` and `import-players` terminal commands.
+//!
+//! Both formats use the same fields as [`Row`]. In CSV, the title is a single
+//! column with its words separated by spaces.
+
+use crate::{ Serialize, Deserialize, file_management, player_data::Player, admin_data::AdminAction, json_data::FileFormat };
+use std::collections::HashSet;
+
+/// A file format that players can be exported to, or imported from.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum Format {
+    #[name = "csv"]
+    Csv,
+    #[name = "json"]
+    Json,
+}
+
+impl Format {
+
+    /// The file extension for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+        }
+    }
+
+    /// Guesses the format of a file from its name.
+    pub fn from_filename(name: &str) -> Option<Format> {
+        let name = name.to_lowercase();
+        if name.ends_with(".csv") {
+            Some(Format::Csv)
+        } else if name.ends_with(".json") {
+            Some(Format::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// What to do when an imported player already exists.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum Strategy {
+
+    /// Replace the existing player with the imported one.
+    #[name = "overwrite"]
+    Overwrite,
+
+    /// Keep whichever is higher, for each of progress (level and XP together),
+    /// prestige, and prestige threshold. The longer title is kept.
+    #[name = "merge-max"]
+    MergeMax,
+
+    /// Leave the existing player alone.
+    #[name = "skip"]
+    Skip,
+}
+
+/// A single player, as written to or read from a file.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct Row {
+    pub user_id: u64,
+    pub xp: i128,
+    pub lvl: i64,
+    pub prestige: f64,
+    pub prestige_threshold: i64,
+    pub title: String,
+}
+
+impl Row {
+    fn from_player(p: &Player) -> Row {
+        Row {
+            user_id: p.user_id,
+            xp: p.xp,
+            lvl: p.lvl,
+            prestige: p.prestige,
+            prestige_threshold: p.prestige_threshold,
+            title: p.title_segments.join(" "),
+        }
+    }
+
+    fn to_player(&self) -> Player {
+        let mut p = Player::new(self.user_id);
+        p.xp = self.xp;
+        p.lvl = self.lvl;
+        p.prestige = self.prestige;
+        p.prestige_threshold = self.prestige_threshold;
+        p.title_segments = self.title.split_whitespace().map(str::to_string).collect();
+        p
+    }
+
+    /// Checks that the values in the row make sense.
+    fn validate(&self) -> Result<(), String> {
+        if self.lvl < 1 {
+            return Err(format!("Player {} has a level below 1", self.user_id))
+        }
+        if !self.prestige.is_finite() || self.prestige < 1.0 {
+            return Err(format!("Player {} has a prestige below 1.0", self.user_id))
+        }
+        Ok(())
+    }
+}
+
+/// Writes every player to a string, in the given format.
+pub fn export(players: &[Player], format: Format) -> Result<String, String> {
+    let rows = players.iter().map(Row::from_player).collect::<Vec<_>>();
+    match format {
+        Format::Json => serde_json::to_string_pretty(&rows).map_err(|e| e.to_string()),
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for row in &rows {
+                writer.serialize(row).map_err(|e| e.to_string())?;
+            }
+            let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+            String::from_utf8(bytes).map_err(|e| e.to_string())
+        },
+    }
+}
+
+/// Reads players from a string, in the given format.
+///
+/// Rejects the whole file if any row is invalid, or if any user ID appears
+/// more than once - the same check that
+/// [`file_management::save()`](file_management::save) makes.
+pub fn parse(content: &str, format: Format) -> Result<Vec<Row>, String> {
+    let rows: Vec<Row> = match format {
+        Format::Json => serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {e}"))?,
+        Format::Csv => csv::Reader::from_reader(content.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(i, x)| x.map_err(|e| format!("Invalid CSV on row {}: {e}", i + 1)))
+            .collect::<Result<_, _>>()?,
+    };
+
+    if !file_management::no_unique_elements(rows.iter().map(|x| x.user_id)) {
+        let mut seen = HashSet::new();
+        let duplicates = rows.iter().map(|x| x.user_id).filter(|x| !seen.insert(*x)).collect::<Vec<_>>();
+        return Err(format!("The file contains duplicate user IDs: {duplicates:?}"))
+    }
+    for row in &rows {
+        row.validate()?;
+    }
+
+    Ok(rows)
+}
+
+/// What an import would do to a single player.
+pub enum Change {
+    New(Player),
+    Updated { before: Player, after: Player },
+    Unchanged(u64),
+    Skipped(u64),
+}
+
+impl Change {
+
+    /// A line describing the change, for showing in a dry run.
+    pub fn describe(&self) -> String {
+        let summary = |p: &Player| format!("Lv. {} ({} XP), prestige {:.2}, title \"{}\"",
+                                            p.lvl, p.xp, p.prestige, p.title_segments.join(" "));
+        match self {
+            Change::New(p) => format!("+ {}: {}", p.user_id, summary(p)),
+            Change::Updated { before, after } =>
+                format!("~ {}: {} → {}", after.user_id, summary(before), summary(after)),
+            Change::Unchanged(id) => format!("= {id}: no change"),
+            Change::Skipped(id) => format!("- {id}: already exists, skipped"),
+        }
+    }
+
+    /// The player after the change, if there is a change to make.
+    pub fn result(&self) -> Option<&Player> {
+        match self {
+            Change::New(p) | Change::Updated { after: p, .. } => Some(p),
+            Change::Unchanged(_) | Change::Skipped(_) => None,
+        }
+    }
+}
+
+/// Combines an existing player with an imported one, keeping the best of each.
+fn merge_max(existing: &Player, imported: &Player) -> Player {
+    let mut p = existing.clone();
+    if (imported.lvl, imported.xp) > (existing.lvl, existing.xp) {
+        p.lvl = imported.lvl;
+        p.xp = imported.xp;
+    }
+    p.prestige = existing.prestige.max(imported.prestige);
+    p.prestige_threshold = existing.prestige_threshold.max(imported.prestige_threshold);
+    if imported.title_segments.len() > existing.title_segments.len() {
+        p.title_segments = imported.title_segments.clone();
+    }
+    p
+}
+
+/// Whether two players have exactly the same progress.
+fn same(a: &Player, b: &Player) -> bool {
+    a.xp == b.xp && a.lvl == b.lvl && a.prestige == b.prestige
+        && a.prestige_threshold == b.prestige_threshold && a.title_segments == b.title_segments
+}
+
+/// Works out what importing some rows would do, without changing anything.
+pub fn plan(existing: &[Player], rows: &[Row], strategy: Strategy) -> Vec<Change> {
+    plan_players(existing, rows.iter().map(Row::to_player).collect(), strategy)
+}
+
+/// Works out what importing some players would do, without changing anything.
+///
+/// Also used by [`migrate`](crate::migrate), which makes its own players.
+pub fn plan_players(existing: &[Player], imported: Vec<Player>, strategy: Strategy) -> Vec<Change> {
+    imported.into_iter().map(|imported| {
+        let id = imported.user_id;
+        let Some(current) = existing.iter().find(|x| x.user_id == id) else {
+            return Change::New(imported)
+        };
+        let after = match strategy {
+            Strategy::Skip => return Change::Skipped(id),
+            // Perks, coins and medals aren't part of an export, so they're kept as they were.
+            Strategy::Overwrite => Player {
+                perk_points: current.perk_points,
+                perks: current.perks.clone(),
+                coins: current.coins,
+                inventory: current.inventory.clone(),
+                equipped: current.equipped.clone(),
+                medals: current.medals,
+                ..imported
+            },
+            Strategy::MergeMax => merge_max(current, &imported),
+        };
+        if same(current, &after) {
+            Change::Unchanged(id)
+        } else {
+            Change::Updated { before: current.clone(), after }
+        }
+    }).collect()
+}
+
+/// Applies planned changes to the save data, recording an admin action for each one.
+///
+/// Returns the action and new state of every player that was added or changed,
+/// ready to be recorded in the [`audit`](crate::audit) log. The data still
+/// needs saving afterwards.
+pub fn apply(data: &mut FileFormat, changes: &[Change], actor_id: u64, reason: &str) -> Vec<(AdminAction, Player)> {
+    let mut applied = vec![];
+    for change in changes {
+        let Some(p) = change.result() else {
+            continue
+        };
+        match data.player_mut(p.user_id) {
+            Some(existing) => *existing = p.clone(),
+            None => data.player_list.push(p.clone()),
+        }
+        let action = AdminAction::new(actor_id, p.user_id, format!("import: {}", change.describe()), reason.to_string());
+        data.admin_log.push(action.clone());
+        applied.push((action, p.clone()));
+    }
+    applied
+}
+
+/// Counts how many of each kind of change there are, as a single line.
+pub fn summarise(changes: &[Change]) -> String {
+    let count = |f: fn(&Change) -> bool| changes.iter().filter(|x| f(x)).count();
+    format!("{} new, {} updated, {} unchanged, {} skipped",
+            count(|x| matches!(x, Change::New(_))),
+            count(|x| matches!(x, Change::Updated { .. })),
+            count(|x| matches!(x, Change::Unchanged(_))),
+            count(|x| matches!(x, Change::Skipped(_))))
+}
@@ -0,0 +1,301 @@
+//! Sends game events to other systems, as outgoing webhooks.
+//!
+//! Webhooks are configured in [`WEBHOOKS_FILENAME`], next to `saved_data.json`:
+//!
+//! ```json
+//! [
+//!     {
+//!         "url": "https://example.com/trophies",
+//!         "secret": "something long and random",
+//!         "events": ["level_up", "prestige", "achievement"],
+//!         "min_achievement_xp": 50
+//!     }
+//! ]
+//! ```
+//!
+//! Leaving `events` empty sends every event. The event types are
+//! `achievement`, `achievement_revoked`, `level_up`, `level_down`,
+//! `prestige`, `title_changed`, `perk_bought`, `item_bought`, `item_equipped`,
+//! `team_changed`, `team_disbanded`, `challenge_created`, `challenge_accepted`,
+//! `challenge_won`, `challenge_declined`, `challenge_called_off`,
+//! `challenge_expired`, `bounty_posted`, `bounty_claimed`, `bounty_paid`,
+//! `bounty_cancelled`, `bounty_expired`, `admin_override`, `config_changed`
+//! and `locale_changed`.
+//!
+//! Every request is a `POST` with a JSON body, signed with HMAC-SHA256 using
+//! the webhook's secret. The signature is sent in the `X-Trophies-Signature`
+//! header, as `sha256=<hex digest of the body>`.
+//!
+//! Deliveries that fail are retried with exponential backoff, up to
+//! [`MAX_ATTEMPTS`] times. The queue is saved to [`QUEUE_FILENAME`], so
+//! nothing is lost if the bot restarts.
+
+use crate::{ Serialize, Deserialize, audit, bounties, challenges, api::PlayerView };
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fs;
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::Notify;
+
+pub const WEBHOOKS_FILENAME: &str = "webhooks.json";
+pub const QUEUE_FILENAME: &str = "webhook_queue.json";
+
+/// How many times a delivery is tried before giving up on it.
+pub const MAX_ATTEMPTS: u32 = 10;
+
+/// How long to wait before the first retry. Doubles after every failure.
+const BASE_BACKOFF_SECS: i64 = 10;
+
+/// The longest to ever wait between retries.
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+
+/// A single outgoing webhook.
+#[derive(Serialize,Deserialize,Clone)]
+pub struct WebhookConfig {
+
+    /// Where to send events.
+    pub url: String,
+
+    /// Used to sign every request, so the receiver can check it came from us.
+    pub secret: String,
+
+    /// Which event types to send. Empty means all of them.
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Achievements worth less than this (before multipliers) aren't sent.
+    #[serde(default)]
+    pub min_achievement_xp: i128,
+}
+
+impl WebhookConfig {
+
+    /// Whether this webhook wants to receive an event.
+    fn wants(&self, event_type: &str, event: &audit::Event) -> bool {
+        if !self.events.is_empty() && !self.events.iter().any(|x| x == event_type) {
+            return false
+        }
+        match event {
+            audit::Event::AchievementGranted { record, .. } => record.base_xp >= self.min_achievement_xp,
+            _ => true,
+        }
+    }
+}
+
+/// A request waiting to be sent.
+#[derive(Serialize,Deserialize,Clone)]
+struct Delivery {
+    url: String,
+    body: String,
+    signature: String,
+    attempts: u32,
+    next_attempt: DateTime<Utc>,
+}
+
+/// Deliveries waiting to be sent, shared with the background worker.
+static QUEUE: LazyLock<Mutex<Vec<Delivery>>> = LazyLock::new(|| Mutex::new(load_queue()));
+
+/// Wakes the worker up when something new is queued.
+static QUEUED: Notify = Notify::const_new();
+
+/// The name of an event, as used in [`WebhookConfig::events`] and in payloads.
+pub fn event_type(event: &audit::Event) -> &'static str {
+    match event {
+        audit::Event::AchievementGranted { .. } => "achievement",
+        audit::Event::AchievementRevoked { .. } => "achievement_revoked",
+        audit::Event::LevelChanged { old_lvl, new_lvl, .. } if new_lvl < old_lvl => "level_down",
+        audit::Event::LevelChanged { .. } => "level_up",
+        audit::Event::Prestiged { .. } => "prestige",
+        audit::Event::TitleChanged { .. } => "title_changed",
+        audit::Event::PerkBought { .. } => "perk_bought",
+        audit::Event::ItemBought { .. } => "item_bought",
+        audit::Event::ItemEquipped { .. } => "item_equipped",
+        audit::Event::TeamChanged { .. } => "team_changed",
+        audit::Event::TeamDisbanded { .. } => "team_disbanded",
+        audit::Event::ChallengeChanged { challenge, .. } => match challenge.state {
+            challenges::State::Pending => "challenge_created",
+            challenges::State::Accepted => "challenge_accepted",
+            challenges::State::Won => "challenge_won",
+            challenges::State::Declined => "challenge_declined",
+            challenges::State::CalledOff => "challenge_called_off",
+            challenges::State::Expired => "challenge_expired",
+        },
+        audit::Event::BountyChanged { change, .. } => match change {
+            bounties::Change::Posted => "bounty_posted",
+            bounties::Change::Claimed => "bounty_claimed",
+            bounties::Change::Paid => "bounty_paid",
+            bounties::Change::Cancelled => "bounty_cancelled",
+            bounties::Change::Expired => "bounty_expired",
+        },
+        audit::Event::AdminOverride { .. } => "admin_override",
+        audit::Event::ConfigChanged { .. } => "config_changed",
+        audit::Event::LocaleChanged { .. } => "locale_changed",
+    }
+}
+
+/// The player an event left behind, if it has one.
+fn event_player(event: &audit::Event) -> Option<PlayerView> {
+    let player = match event {
+        audit::Event::AchievementGranted { player, .. }
+        | audit::Event::AchievementRevoked { player, .. }
+        | audit::Event::Prestiged { player, .. }
+        | audit::Event::TitleChanged { player, .. }
+        | audit::Event::PerkBought { player, .. }
+        | audit::Event::ItemBought { player, .. }
+        | audit::Event::ItemEquipped { player, .. } => Some(player),
+        audit::Event::LevelChanged { player, .. }
+        | audit::Event::BountyChanged { player, .. }
+        | audit::Event::AdminOverride { player, .. } => player.as_ref(),
+        audit::Event::TeamChanged { .. }
+        | audit::Event::TeamDisbanded { .. }
+        | audit::Event::ChallengeChanged { .. }
+        | audit::Event::ConfigChanged { .. }
+        | audit::Event::LocaleChanged { .. } => None,
+    };
+    player.map(|p| PlayerView::new(p, None))
+}
+
+/// Signs a body with a secret, giving the value of the `X-Trophies-Signature` header.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Reads the configured webhooks. Returns nothing if there's no config file.
+fn load_config() -> Vec<WebhookConfig> {
+    let Ok(data) = fs::read_to_string(WEBHOOKS_FILENAME) else {
+        return vec![]
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Invalid {WEBHOOKS_FILENAME}, so no webhooks will be sent");
+        vec![]
+    })
+}
+
+fn load_queue() -> Vec<Delivery> {
+    let Ok(data) = fs::read_to_string(QUEUE_FILENAME) else {
+        return vec![]
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Invalid {QUEUE_FILENAME}, so queued webhooks have been lost");
+        vec![]
+    })
+}
+
+fn save_queue(queue: &[Delivery]) {
+    let result = serde_json::to_string(queue)
+        .map_err(std::io::Error::from)
+        .and_then(|j| fs::write(QUEUE_FILENAME, j));
+    if let Err(e) = result {
+        tracing::error!(error = %e, "Failed to save webhook queue");
+    }
+}
+
+/// Queues an audit event to be sent to every webhook that wants it.
+pub fn dispatch(entry: &audit::Entry) {
+    let event_type = event_type(&entry.event);
+    let hooks = load_config().into_iter().filter(|x| x.wants(event_type, &entry.event)).collect::<Vec<_>>();
+    if hooks.is_empty() {
+        return
+    }
+
+    let payload = serde_json::json!({
+        "event": event_type,
+        "timestamp": entry.timestamp,
+        "description": entry.event.describe(),
+        "data": entry.event,
+        "player": event_player(&entry.event),
+    });
+    let Ok(body) = serde_json::to_string(&payload) else {
+        tracing::error!(event_type, "Failed to serialise webhook payload");
+        return
+    };
+
+    let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    for hook in hooks {
+        queue.push(Delivery {
+            signature: sign(&hook.secret, &body),
+            url: hook.url,
+            body: body.clone(),
+            attempts: 0,
+            next_attempt: Utc::now(),
+        });
+    }
+    save_queue(&queue);
+    QUEUED.notify_one();
+}
+
+/// Tries to send a single delivery.
+async fn send(client: &reqwest::Client, delivery: &Delivery) -> Result<(), String> {
+    let response = client.post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header("X-Trophies-Signature", &delivery.signature)
+        .body(delivery.body.clone())
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+/// Starts sending queued webhooks in the background, forever.
+pub fn spawn_worker() {
+    tokio::spawn(async {
+        let client = reqwest::Client::new();
+        loop {
+            let now = Utc::now();
+            let due = QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .filter(|x| x.next_attempt <= now)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for delivery in due {
+                let result = send(&client, &delivery).await;
+
+                let mut queue = QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(index) = queue.iter().position(|x| x.url == delivery.url && x.body == delivery.body) else {
+                    continue
+                };
+                match result {
+                    Ok(()) => {
+                        tracing::debug!(url = delivery.url, "Webhook delivered");
+                        queue.remove(index);
+                    },
+                    Err(e) if delivery.attempts + 1 >= MAX_ATTEMPTS => {
+                        tracing::error!(url = delivery.url, error = e, "Giving up on webhook after {MAX_ATTEMPTS} attempts");
+                        queue.remove(index);
+                    },
+                    Err(e) => {
+                        let d = &mut queue[index];
+                        d.attempts += 1;
+                        let backoff = (BASE_BACKOFF_SECS << d.attempts.min(20)).min(MAX_BACKOFF_SECS);
+                        d.next_attempt = Utc::now() + Duration::seconds(backoff);
+                        tracing::warn!(url = delivery.url, error = e, attempts = d.attempts, retry_in_secs = backoff, "Webhook failed");
+                    },
+                }
+                save_queue(&queue);
+            }
+
+            // Sleep until the next retry is due, or something new is queued.
+            let next = QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|x| x.next_attempt)
+                .min();
+            let wait = next
+                .map(|x| (x - Utc::now()).to_std().unwrap_or_default())
+                .unwrap_or(std::time::Duration::from_secs(MAX_BACKOFF_SECS as u64));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {},
+                _ = QUEUED.notified() => {},
+            }
+        }
+    });
+}
@@ -0,0 +1,412 @@
+# English messages, and the fallback for every other language.
+#
+# See `src/i18n.rs` for how these are used. Arguments like `{ $name }` are
+# filled in by the bot; anything named `user` is already a mention.
+
+## General
+
+processing = Processing...
+timed-out = :warning: You didn't react in time, sorry!
+cancelled = Cancelled :)
+unknown-username = [Unknown Username]
+
+## Errors
+
+error-discord = I couldn't talk to Discord properly. Please try again in a moment.
+error-save-data = Something is wrong with my save data, so I couldn't do that. Please let an admin know!
+error-player-not-found = I couldn't find that player's data. Please try again.
+error-unknown-button = I didn't recognise that button. Please run the command again.
+error-feature-disabled = That's been turned off in this server.
+error-not-admin = Only admins can use that.
+error-not-owner = Players' data is shared by every server, so only the bot's owners can change it.
+
+## Levels
+
+level-title = User Data
+field-level = Level
+field-prestige = Prestige
+field-xp = XP
+field-coins = Coins
+coins = 🪙 { $coins }
+field-medals = Medals
+leaderboard-title = 🏆 | Leaderboard
+leaderboard-medals-title = 🏅 | Medal Table
+leaderboard-level = Lv. { $lvl } _{ $progress }_
+leaderboard-empty = Nobody's here yet.
+leaderboard-page = Page { $page } of { $pages }
+leaderboard-category-title = 🏆 | Leaderboard: { $category }
+leaderboard-category-medals-title = 🏅 | Medal Table: { $category }
+leaderboard-category-xp = { $xp } XP from { $achievements ->
+    [one] { $achievements } achievement
+   *[other] { $achievements } achievements
+}
+category-unknown = There's no category called "{ $category }" in this server.
+
+## Stats
+
+stats-title = 📊 | Stats
+stats-none = No achievements yet.
+stats-total = { $achievements ->
+    [one] { $achievements } achievement
+   *[other] { $achievements } achievements
+}, worth { $xp } XP altogether.
+stats-category = { $achievements ->
+    [one] { $achievements } achievement
+   *[other] { $achievements } achievements
+}
+    { $xp } XP
+    { $medals }
+stats-uncategorised = Uncategorised
+level-lost = { $name } lost a level! They are now at Lv. { $lvl }!
+level-gained = { $name } gained a level! They are now at Lv. { $lvl }!
+prestige-eligible = You are now eligible to Prestige! Use `/prestige preview` to see what you'd get.
+announce-level-title = ⬆️ | Level Up!
+announce-level = { $user } reached Lv. { $lvl }!
+
+compare-title = 🆚 | Head to Head
+compare-self = Pick two different players to compare.
+compare-no-title = None yet
+compare-player = Rank #{ $rank }
+    { $bar }
+    Prestige: { $prestige }
+    Title: { $title }
+    Lifetime XP: { $lifetime }
+    { $achievements ->
+        [one] { $achievements } achievement
+       *[other] { $achievements } achievements
+    }
+    { $medals }
+    { $base } XP from an achievement gives { $xp } XP
+field-catching-up = Catching up
+compare-tied = They're level on the leaderboard.
+compare-overtake = { $chaser } needs { $xp } more XP to overtake { $leader }. That's { $base } XP of achievements, after their multipliers.
+
+## Prestige
+
+prestige-level-too-low = { $first_time ->
+    [true] You need to be at least level { $lvl } to Prestige for the first time.
+   *[false] You need to be at least level { $lvl } to Prestige.
+}
+prestige-button-accept = Prestige
+prestige-button-cancel = Cancel Prestige
+prestige-max = Congratulations! { $name } has won the Achievements Game! It is literally impossible for your prestige to get any higher!
+prestige-done = { $first_time ->
+    [true] { $name } has Prestiged for the first time, and now has { $prestige } Prestige Points!
+   *[false] { $name } has Prestiged, and now has { $prestige } Prestige Points!
+}
+announce-prestige-title = ✨ | Prestige!
+announce-prestige = { $user } prestiged to { $prestige }, and is now known as "{ $title }"!
+
+## Titles
+
+title-none = You do not have a title to edit.
+title-empty = Your new title can't be empty.
+title-too-long = Your new title cannot be more than { $max } characters long.
+title-one-word = Your new title can only be one word long.
+title-invisible = Your new title can't have invisible or control characters in it.
+title-cluttered = Your new title has too many accents piled on top of each other.
+title-mention = Your new title can't mention anybody.
+title-banned = That word isn't allowed in titles in this server.
+title-bad-index = { $words ->
+    [one] Your title only has { $words } word.
+   *[other] Your title only has { $words } words.
+}
+title-changed = Your title is now "{ $title }".
+
+## Prestige previews
+
+prestige-confirm = Are you sure? Prestiging resets your level and XP.
+prestige-preview-title = ✨ | Prestige Preview
+prestige-preview-locked = You need to reach Lv. { $lvl } before you can Prestige. This is what prestiging then would give you.
+prestige-new-word = [your new word]
+field-next-prestige = Next Prestige at
+field-xp-per-level = XP per level
+field-title = Title
+field-xp-rate = XP from achievements
+field-prestige-waiting = If you wait
+prestige-change = { $old } → { $new } (× { $points } Prestige Points)
+prestige-threshold-change = Lv. { $old } → Lv. { $new }
+prestige-xp-threshold-change = { $old } → { $new }
+prestige-xp-rate = A { $base } XP achievement gives { $old } XP now, and { $new } XP after ({ $percent }% more)
+prestige-wait = Lv. { $lvl }: { $points } Prestige Points, for { $prestige } Prestige
+field-perk-points = Perk points
+prestige-perk-points-change = { $old } → { $new }
+
+## Perks
+
+perks-title = 🌟 | Perks
+perks-points = { $points ->
+    [one] You have { $points } perk point to spend.
+   *[other] You have { $points } perk points to spend.
+} You earn { $per_prestige } every time you Prestige.
+perk-rank = { $perk } (`{ $id }`) - { $rank }/{ $max }
+perk-cost = Next rank: { $cost ->
+    [one] { $cost } point
+   *[other] { $cost } points
+}
+perk-requires = Requires: { $requires }
+perk-unknown = There's no perk called "{ $perk }".
+perk-maxed = You already have every rank of { $perk }.
+perk-locked = You need { $requires } before you can buy { $perk }.
+perk-no-points = { $perk } costs { $cost ->
+    [one] { $cost } point
+   *[other] { $cost } points
+}, but you only have { $points }.
+perk-bought = You now have { $perk } rank { $rank }! { $points ->
+    [one] You have { $points } perk point left.
+   *[other] You have { $points } perk points left.
+}
+
+## Shop
+
+field-coins-earned = Coins Earned
+coins-earned = +🪙 { $earned } (🪙 { $coins } total)
+shop-title = 🛍️ | Shop
+shop-coins = You have 🪙 { $coins } to spend. Earn more from achievements and levelling up.
+shop-item = { $item } (`{ $id }`) - { $status }
+shop-status-owned = Owned
+shop-status-equipped = Equipped
+shop-unknown = There's no item called "{ $item }".
+shop-owned = You already own { $item }. Use `/shop equip` to wear it.
+shop-not-owned = You don't own { $item } yet. Buy it with `/shop buy`.
+shop-no-coins = { $item } costs 🪙 { $price }, but you only have 🪙 { $coins }.
+shop-too-many-badges = You can only wear { $max } badges at once. Unequip one first.
+shop-bought = You bought and equipped { $item }! You have 🪙 { $coins } left.
+shop-bought-unequipped = You bought { $item }! You have 🪙 { $coins } left. Unequip a badge to wear it.
+shop-equipped = You equipped { $item }.
+shop-unequipped = You unequipped { $item }.
+
+## Teams
+
+team-already-member = You're already on a team. Leave it with `/team leave` first.
+team-not-member = You're not on a team.
+team-not-owner = Only the team's owner can do that.
+team-invalid-name = Team names must be 1 to { $max } characters long, and can't contain mentions.
+team-invalid-tag = Team tags must be { $min } to { $max } letters or numbers.
+team-taken = There's already a team with that name or tag.
+team-unknown = There's no team called "{ $team }".
+team-full = { $team } already has { $max } members.
+team-invite-bot = Bots can't join teams.
+team-invite-member = { $user } is already on your team.
+team-not-invited = You haven't been invited to { $team }.
+team-kick-self = You can't kick yourself. Use `/team leave` instead.
+team-kick-not-member = { $user } isn't on your team, or invited to it.
+team-created = Created **{ $team }**! Invite people with `/team invite`.
+team-invited = { $user }, you've been invited to join **{ $team }**! Accept with `/team join`.
+team-joined = { $user } joined **{ $team }**!
+team-left = { $user } left **{ $team }**.
+team-left-owner = { $user } left **{ $team }**. { $owner } is the owner now.
+team-disbanded = You were the last member of **{ $team }**, so it's been disbanded.
+team-kicked = Removed { $user } from **{ $team }**.
+field-team-level = Level
+field-team-xp = Team XP
+field-team-members = Members ({ $count }/{ $max })
+team-founded = Founded { $date }
+team-leaderboard-title = 🏆 | Team Leaderboard
+team-leaderboard-empty = There aren't any teams yet.
+
+## Challenges
+
+challenge-self = You can't challenge yourself.
+challenge-bot = Bots can't take challenges.
+challenge-invalid-description = Descriptions must be 1 to { $max } characters long.
+hold-not-enough-xp = { $user } only has { $xp } XP in their current level, so can't put up { $held } XP.
+hold-not-positive = You have to put up at least 1 XP.
+challenge-sent = { $user }, { $challenger } has challenged you!
+challenge-title = ⚔️ | Challenge #{ $id }
+field-challenger = Challenger
+field-opponent = Opponent
+field-stake = Stake
+field-status = Status
+field-levels = Levels
+challenge-stake = { $stake } XP each ({ $pot } XP to the winner)
+challenge-status-pending = Waiting for { $user } to accept. Expires { $expires }.
+challenge-status-accepted = Accepted! Both players press who won, or call it off. Expires { $expires }.
+challenge-status-verdict = { $user } says: { $verdict }
+challenge-status-disputed = You don't agree, so a referee can decide. Otherwise, every stake is given back when it expires.
+challenge-status-won = 🏆 { $user } won { $pot } XP!
+challenge-status-referee = Decided by { $user }.
+challenge-status-declined = { $user } declined. The stake was given back.
+challenge-status-called-off = Called off. Every stake was given back.
+challenge-status-expired = Ran out of time. Every stake was given back.
+challenge-verdict-won = { $user } won
+challenge-verdict-call-off = call it off
+challenge-button-accept = Accept
+challenge-button-decline = Decline
+challenge-button-challenger = Challenger won
+challenge-button-target = Opponent won
+challenge-button-call-off = Call it off
+challenge-new-level = { $user } is now Lv. { $lvl }.
+challenge-unknown = That challenge doesn't exist any more.
+challenge-closed = That challenge is already over.
+challenge-not-yours = That's not your challenge.
+challenge-already-accepted = That challenge has already been accepted.
+challenge-not-accepted = That challenge hasn't been accepted yet.
+
+## Bounties
+
+bounty-server-only = Bounties only work inside a server.
+bounty-invalid-title = Titles must be 1 to { $max } characters long.
+bounty-invalid-days = Bounties can last from 1 to { $max } days.
+bounty-invalid-reward = Rewards must be 1 to { $max } XP in this server.
+bounty-invalid-proof = Proof must be 1 to { $max } characters long.
+bounty-too-many = You can only have { $max } bounties open at once.
+bounty-too-many-claims = That bounty already has { $max } claims.
+bounty-unknown = There's no bounty #{ $id } in this server.
+bounty-closed = Bounty #{ $id } is already closed.
+bounty-claim-own = You can't claim your own bounty.
+bounty-not-poster = Only whoever posted bounty #{ $id } can do that.
+bounty-not-claimed = { $user } hasn't claimed bounty #{ $id }.
+bounty-posted = { $user } posted a bounty!
+bounty-minted = { $user } minted a bounty!
+bounty-claimed = { $user } claimed bounty #{ $id }. { $poster }, use `/bounty approve` to pay them.
+bounty-paid = { $user } was paid for bounty #{ $id }!
+bounty-cancelled = Bounty #{ $id } was cancelled.
+bounty-cancelled-refund = Bounty #{ $id } was cancelled, and its { $reward } XP was given back to you.
+bounty-title = 🎯 | Bounty #{ $id }
+bounty-list-title = 🎯 | Open Bounties
+bounty-list-empty = There are no open bounties. Post one with `/bounty post`!
+bounty-summary = **#{ $id }** { $title } - { $reward } XP from { $poster }, { $claims ->
+    [one] 1 claim
+   *[other] { $claims } claims
+}, expires { $expires }
+bounty-choice = #{ $id } { $title } ({ $reward } XP)
+bounty-minted-by = Minted by { $user }
+bounty-reward = { $reward } XP
+bounty-no-claims = Nobody yet.
+bounty-status-open = Open. Expires { $expires }.
+bounty-status-paid = 🏆 Paid to { $user }.
+bounty-status-cancelled = Cancelled.
+bounty-status-expired = Nobody was paid in time.
+field-poster = Posted by
+field-reward = Reward
+field-claims = Claims ({ $count })
+
+## Achievements
+
+achievement-negative-other = You cannot remove points from somebody else...
+achievement-undo = Undo
+achievement-unlocked = { $medal } | Achievement Unlocked!
+field-achievement = Achievement
+field-xp-gained = XP Gained
+field-xp-total = XP Total
+field-xp-removed = XP Removed
+achievement-footer = Achievement #{ $id }
+achievement-retracted = ↩️ | Achievement Retracted
+achievement-retracted-footer = Achievement #{ $id } | Retracted by { $name }
+
+## Limits on granting XP
+
+limit-max-xp = Achievements can be worth at most { $max } XP in this server.
+limit-cooldown = { $remaining ->
+    [one] You need to wait { $remaining } more second before granting another achievement.
+   *[other] You need to wait { $remaining } more seconds before granting another achievement.
+}
+limit-granted = You can only grant { $cap } XP per day. You have { $left } XP left to grant today.
+limit-received = { $user } can only receive { $cap } XP per day. They can receive { $left } more XP today.
+limit-reciprocal = You and { $user } have been granting each other a lot of XP today. Try again tomorrow!
+
+## Language
+
+language-unknown = That isn't a supported language. Choose from: { $options }, or auto.
+language-unknown-value = `{ $value }` isn't a supported language. Choose from: { $options }, or auto.
+language-set = I'll reply to you in English from now on.
+language-auto = I'll reply to you in your server's language, or your Discord's, from now on.
+
+## Server settings
+
+config-none = None
+config-on = On
+config-off = Off
+config-auto = Automatic
+config-invalid-id = `{ $value }` isn't a channel, role or ID. Use `none` to clear it.
+config-invalid-number = `{ $value }` needs to be a whole number, 0 or more.
+config-invalid-bool = `{ $value }` needs to be `on` or `off`.
+config-invalid-colour = `{ $value }` needs to be a hex colour, like `#F1C40F`.
+config-medal-order = Silver medals can't need more XP ({ $silver }) than gold medals ({ $gold }).
+config-server-only = Settings can only be changed inside a server.
+config-set = `{ $key }` is now { $value }
+config-reset = `{ $key }` has been reset.
+config-reset-all = All settings have been reset.
+
+## Admin tools
+
+admin-set-xp = Set { $name }'s XP from { $old } to { $new }. They are now Lv. { $lvl } with { $xp } XP.
+admin-adjust-xp = Adjusted { $name }'s XP by { $amount }. They are now Lv. { $lvl } with { $xp } XP.
+admin-level-too-low = Level must be at least 1.
+admin-set-level = Set { $name }'s level from { $old } to { $new }.
+admin-prestige-too-low = Prestige must be a number, and at least 1.0.
+admin-set-prestige = Set { $name }'s prestige from { $old } to { $new }.
+admin-no-data = { $name } has no data.
+admin-reset = Reset { $name }'s progress.
+admin-title-too-short = { $words ->
+    [one] { $name } only has { $words } word in their title.
+   *[other] { $name } only has { $words } words in their title.
+}
+admin-remove-title = Removed "{ $removed }" from { $name }'s title.
+admin-set-title = { $name }'s title is now "{ $title }".
+admin-filter-empty = No words are banned from titles in this server.
+admin-filter-list = Words banned from titles: { $words }
+admin-filter-invalid = That word needs at least one letter or number in it.
+admin-filter-exists = That word is already banned.
+admin-filter-missing = That word isn't banned.
+admin-filter-full = A server can only ban { $max } words.
+admin-filter-added = That word is now banned from titles.
+admin-filter-removed = That word is no longer banned from titles.
+admin-categories-empty = This server doesn't have any categories yet.
+admin-categories-list = Categories: { $categories }
+admin-category-invalid = Category names can't be empty, "none", or longer than { $max } characters.
+admin-category-exists = That category already exists.
+admin-category-missing = There's no category with that name.
+admin-categories-full = A server can only have { $max } categories.
+admin-category-added = Achievements can now be granted under { $category }.
+admin-category-removed = Removed the { $category } category. Achievements already granted under it keep it.
+admin-delete = Deleted { $name }'s data.
+admin-no-achievement = There is no achievement #{ $id } in this server.
+admin-already-revoked = Achievement #{ $id } has already been revoked.
+admin-recipient-gone = The player who received that achievement no longer has any data.
+admin-revoke = Revoked achievement #{ $id } "{ $title }", removing { $xp } XP from { $user }.
+admin-no-achievements = { $name } has no achievements in this server.
+admin-history = Most recent achievements for { $name } in this server:
+admin-limits-current = Current limits:
+admin-limits-updated = Updated limits:
+admin-limits =
+    Cooldown: { $cooldown }s
+    Daily XP granted cap: { $granted }
+    Daily XP received cap: { $received }
+    Max XP per achievement: { $max }
+    Reciprocal grant limit: { $reciprocal }
+admin-reason-needed = You need to give a reason when changing that.
+admin-digest-current = Current digest: { $schedule }
+admin-digest-updated = Updated digest: { $schedule }
+admin-digest-bad-hour = The hour needs to be between 0 and 23 (UTC).
+admin-digest-bad-weekday = That isn't a day of the week.
+admin-digest-none = There's no digest set up yet - choose a period to start one.
+admin-digest-no-channel = You need to choose a channel for the digest.
+admin-export-failed = Failed to export players: { $error }
+admin-exported = { $count ->
+    [one] Exported { $count } player.
+   *[other] Exported { $count } players.
+}
+admin-file-type = The file needs to end in `.csv` or `.json`.
+admin-file-too-big = The file is too big - the limit is { $limit } KB.
+admin-import-failed = Nothing was imported. { $error }
+admin-import-dry-run = Dry run, so nothing was changed. Importing would give: { $summary }
+admin-imported = Imported players: { $summary }
+admin-migrate-dry-run = Dry run, so nothing was changed. Migrating would give: { $summary }
+admin-migrated = Migrated players: { $summary }
+
+## Digests
+
+digest-daily = 📰 Daily Digest
+digest-weekly = 📰 Weekly Digest
+digest-range = { $start } to { $end }
+digest-category = Only { $category } achievements are included.
+digest-gainers = 📈 Top XP gainers
+digest-levels = ⬆️ New levels
+digest-prestiges = ✨ Prestiges
+digest-achievements = 🏅 Notable achievements
+digest-gainer = { $user } - { $xp } XP
+digest-level = { $user } reached Lv. { $lvl }
+digest-prestige = { $user } prestiged to { $prestige }, and became "{ $title }"
+digest-achievement = **{ $title }** - { $user }, { $xp } XP from { $granter }
@@ -0,0 +1,728 @@
+# Spanish messages. Anything missing here is shown in English.
+
+## General
+
+processing = Procesando...
+timed-out = :warning: No reaccionaste a tiempo, ¡lo siento!
+cancelled = Cancelado :)
+unknown-username = [Usuario desconocido]
+
+## Errors
+
+error-discord = No pude comunicarme bien con Discord. Inténtalo de nuevo en un momento.
+error-save-data = Algo va mal con mis datos guardados, así que no pude hacerlo. ¡Avisa a un administrador!
+error-player-not-found = No encontré los datos de ese jugador. Inténtalo de nuevo.
+error-unknown-button = No reconocí ese botón. Vuelve a usar el comando.
+error-feature-disabled = Eso está desactivado en este servidor.
+error-not-admin = Solo los administradores pueden usar eso.
+error-not-owner = Los datos de los jugadores se comparten entre todos los servidores, así que solo los dueños del bot pueden cambiarlos.
+
+## Levels
+
+level-title = Datos del usuario
+field-level = Nivel
+field-prestige = Prestigio
+field-xp = XP
+field-coins = Monedas
+coins = 🪙 { $coins }
+field-medals = Medallas
+leaderboard-title = 🏆 | Clasificación
+leaderboard-medals-title = 🏅 | Medallero
+leaderboard-level = Nv. { $lvl } _{ $progress }_
+leaderboard-empty = Todavía no hay nadie.
+leaderboard-page = Página { $page } de { $pages }
+leaderboard-category-title = 🏆 | Clasificación: { $category }
+leaderboard-category-medals-title = 🏅 | Medallero: { $category }
+leaderboard-category-xp = { $xp } XP de { $achievements ->
+    [one] { $achievements } logro
+   *[other] { $achievements } logros
+}
+category-unknown = No hay ninguna categoría llamada "{ $category }" en este servidor.
+
+## Stats
+
+stats-title = 📊 | Estadísticas
+stats-none = Todavía no hay logros.
+stats-total = { $achievements ->
+    [one] { $achievements } logro
+   *[other] { $achievements } logros
+}, que valen { $xp } XP en total.
+stats-category = { $achievements ->
+    [one] { $achievements } logro
+   *[other] { $achievements } logros
+}
+    { $xp } XP
+    { $medals }
+stats-uncategorised = Sin categoría
+level-lost = ¡{ $name } ha perdido un nivel! Ahora está en el Nv. { $lvl }.
+level-gained = ¡{ $name } ha subido de nivel! Ahora está en el Nv. { $lvl }.
+prestige-eligible = ¡Ya puedes conseguir Prestigio! Usa `/prestige preview` para ver lo que conseguirías.
+announce-level-title = ⬆️ | ¡Subida de nivel!
+announce-level = ¡{ $user } ha llegado al Nv. { $lvl }!
+
+compare-title = 🆚 | Cara a cara
+compare-self = Elige a dos jugadores distintos para comparar.
+compare-no-title = Ninguno todavía
+compare-player = Puesto #{ $rank }
+    { $bar }
+    Prestigio: { $prestige }
+    Título: { $title }
+    XP total: { $lifetime }
+    { $achievements ->
+        [one] { $achievements } logro
+       *[other] { $achievements } logros
+    }
+    { $medals }
+    { $base } XP de un logro le dan { $xp } XP
+field-catching-up = Para alcanzarle
+compare-tied = Están empatados en la clasificación.
+compare-overtake = { $chaser } necesita { $xp } XP más para superar a { $leader }. Son { $base } XP de logros, después de sus multiplicadores.
+
+## Prestige
+
+prestige-level-too-low = { $first_time ->
+    [true] Necesitas al menos el nivel { $lvl } para conseguir Prestigio por primera vez.
+   *[false] Necesitas al menos el nivel { $lvl } para conseguir Prestigio.
+}
+prestige-button-accept = Prestigio
+prestige-button-cancel = Cancelar Prestigio
+prestige-max = ¡Enhorabuena! ¡{ $name } ha ganado el juego de los logros! ¡Es literalmente imposible que tu prestigio suba más!
+prestige-done = { $first_time ->
+    [true] ¡{ $name } ha conseguido Prestigio por primera vez, y ahora tiene { $prestige } puntos de Prestigio!
+   *[false] ¡{ $name } ha conseguido Prestigio, y ahora tiene { $prestige } puntos de Prestigio!
+}
+announce-prestige-title = ✨ | ¡Prestigio!
+announce-prestige = ¡{ $user } ha llegado a { $prestige } de prestigio, y ahora se le conoce como "{ $title }"!
+
+## Titles
+
+title-none = No tienes ningún título que editar.
+title-empty = Tu nuevo título no puede estar vacío.
+title-too-long = Tu nuevo título no puede tener más de { $max } caracteres.
+title-one-word = Tu nuevo título solo puede ser una palabra.
+title-invisible = Tu nuevo título no puede tener caracteres invisibles ni de control.
+title-cluttered = Tu nuevo título tiene demasiados acentos amontonados.
+title-mention = Tu nuevo título no puede mencionar a nadie.
+title-banned = Esa palabra no está permitida en los títulos de este servidor.
+title-bad-index = { $words ->
+    [one] Tu título solo tiene { $words } palabra.
+   *[other] Tu título solo tiene { $words } palabras.
+}
+title-changed = Ahora tu título es "{ $title }".
+
+## Prestige previews
+
+prestige-confirm = ¿Seguro? Conseguir Prestigio reinicia tu nivel y tu XP.
+prestige-preview-title = ✨ | Vista previa del Prestigio
+prestige-preview-locked = Necesitas llegar al Nv. { $lvl } para conseguir Prestigio. Esto es lo que conseguirías entonces.
+prestige-new-word = [tu nueva palabra]
+field-next-prestige = Próximo Prestigio en
+field-xp-per-level = XP por nivel
+field-title = Título
+field-xp-rate = XP de los logros
+field-prestige-waiting = Si esperas
+prestige-change = { $old } → { $new } (× { $points } puntos de Prestigio)
+prestige-threshold-change = Nv. { $old } → Nv. { $new }
+prestige-xp-threshold-change = { $old } → { $new }
+prestige-xp-rate = Un logro de { $base } XP da { $old } XP ahora, y { $new } XP después ({ $percent }% más)
+prestige-wait = Nv. { $lvl }: { $points } puntos de Prestigio, para { $prestige } de Prestigio
+field-perk-points = Puntos de ventaja
+prestige-perk-points-change = { $old } → { $new }
+
+## Perks
+
+perks-title = 🌟 | Ventajas
+perks-points = { $points ->
+    [one] Tienes { $points } punto de ventaja para gastar.
+   *[other] Tienes { $points } puntos de ventaja para gastar.
+} Ganas { $per_prestige } cada vez que consigues Prestigio.
+perk-rank = { $perk } (`{ $id }`) - { $rank }/{ $max }
+perk-cost = Siguiente rango: { $cost ->
+    [one] { $cost } punto
+   *[other] { $cost } puntos
+}
+perk-requires = Requiere: { $requires }
+perk-unknown = No hay ninguna ventaja llamada "{ $perk }".
+perk-maxed = Ya tienes todos los rangos de { $perk }.
+perk-locked = Necesitas { $requires } antes de poder comprar { $perk }.
+perk-no-points = { $perk } cuesta { $cost ->
+    [one] { $cost } punto
+   *[other] { $cost } puntos
+}, pero solo tienes { $points }.
+perk-bought = ¡Ahora tienes { $perk } de rango { $rank }! { $points ->
+    [one] Te queda { $points } punto de ventaja.
+   *[other] Te quedan { $points } puntos de ventaja.
+}
+
+## Shop
+
+field-coins-earned = Monedas ganadas
+coins-earned = +🪙 { $earned } (🪙 { $coins } en total)
+shop-title = 🛍️ | Tienda
+shop-coins = Tienes 🪙 { $coins } para gastar. Gana más con logros y subiendo de nivel.
+shop-item = { $item } (`{ $id }`) - { $status }
+shop-status-owned = Comprado
+shop-status-equipped = Equipado
+shop-unknown = No hay ningún artículo llamado "{ $item }".
+shop-owned = Ya tienes { $item }. Usa `/shop equip` para ponértelo.
+shop-not-owned = Todavía no tienes { $item }. Cómpralo con `/shop buy`.
+shop-no-coins = { $item } cuesta 🪙 { $price }, pero solo tienes 🪙 { $coins }.
+shop-too-many-badges = Solo puedes llevar { $max } insignias a la vez. Quítate una primero.
+shop-bought = ¡Has comprado y equipado { $item }! Te quedan 🪙 { $coins }.
+shop-bought-unequipped = ¡Has comprado { $item }! Te quedan 🪙 { $coins }. Quítate una insignia para llevarla.
+shop-equipped = Te has equipado { $item }.
+shop-unequipped = Te has quitado { $item }.
+
+## Teams
+
+team-already-member = Ya estás en un equipo. Sal primero con `/team leave`.
+team-not-member = No estás en ningún equipo.
+team-not-owner = Solo el dueño del equipo puede hacer eso.
+team-invalid-name = Los nombres de equipo deben tener entre 1 y { $max } caracteres, y no pueden tener menciones.
+team-invalid-tag = Las etiquetas de equipo deben tener entre { $min } y { $max } letras o números.
+team-taken = Ya hay un equipo con ese nombre o etiqueta.
+team-unknown = No hay ningún equipo llamado "{ $team }".
+team-full = { $team } ya tiene { $max } miembros.
+team-invite-bot = Los bots no pueden unirse a equipos.
+team-invite-member = { $user } ya está en tu equipo.
+team-not-invited = No te han invitado a { $team }.
+team-kick-self = No puedes expulsarte a ti mismo. Usa `/team leave`.
+team-kick-not-member = { $user } no está en tu equipo, ni invitado a él.
+team-created = ¡Has creado **{ $team }**! Invita a gente con `/team invite`.
+team-invited = ¡{ $user }, te han invitado a unirte a **{ $team }**! Acepta con `/team join`.
+team-joined = ¡{ $user } se ha unido a **{ $team }**!
+team-left = { $user } ha salido de **{ $team }**.
+team-left-owner = { $user } ha salido de **{ $team }**. Ahora { $owner } es el dueño.
+team-disbanded = Eras el último miembro de **{ $team }**, así que se ha disuelto.
+team-kicked = Has expulsado a { $user } de **{ $team }**.
+field-team-level = Nivel
+field-team-xp = XP del equipo
+field-team-members = Miembros ({ $count }/{ $max })
+team-founded = Fundado el { $date }
+team-leaderboard-title = 🏆 | Clasificación de equipos
+team-leaderboard-empty = Todavía no hay equipos.
+
+## Challenges
+
+challenge-self = No puedes retarte a ti mismo.
+challenge-bot = Los bots no pueden aceptar retos.
+challenge-invalid-description = Las descripciones deben tener entre 1 y { $max } caracteres.
+hold-not-enough-xp = { $user } solo tiene { $xp } XP en su nivel actual, así que no puede poner { $held } XP.
+hold-not-positive = Tienes que poner al menos 1 XP.
+challenge-sent = ¡{ $user }, { $challenger } te ha retado!
+challenge-title = ⚔️ | Reto #{ $id }
+field-challenger = Retador
+field-opponent = Rival
+field-stake = Apuesta
+field-status = Estado
+field-levels = Niveles
+challenge-stake = { $stake } XP cada uno ({ $pot } XP para el ganador)
+challenge-status-pending = Esperando a que { $user } acepte. Caduca { $expires }.
+challenge-status-accepted = ¡Aceptado! Los dos jugadores pulsan quién ganó, o lo cancelan. Caduca { $expires }.
+challenge-status-verdict = { $user } dice: { $verdict }
+challenge-status-disputed = No estáis de acuerdo, así que puede decidir un árbitro. Si no, se devuelven todas las apuestas cuando caduque.
+challenge-status-won = 🏆 ¡{ $user } ha ganado { $pot } XP!
+challenge-status-referee = Decidido por { $user }.
+challenge-status-declined = { $user } lo ha rechazado. Se ha devuelto la apuesta.
+challenge-status-called-off = Cancelado. Se han devuelto todas las apuestas.
+challenge-status-expired = Se acabó el tiempo. Se han devuelto todas las apuestas.
+challenge-verdict-won = ha ganado { $user }
+challenge-verdict-call-off = cancelarlo
+challenge-button-accept = Aceptar
+challenge-button-decline = Rechazar
+challenge-button-challenger = Ganó el retador
+challenge-button-target = Ganó el rival
+challenge-button-call-off = Cancelar
+challenge-new-level = { $user } ahora es Nv. { $lvl }.
+challenge-unknown = Ese reto ya no existe.
+challenge-closed = Ese reto ya ha terminado.
+challenge-not-yours = Ese reto no es tuyo.
+challenge-already-accepted = Ese reto ya ha sido aceptado.
+challenge-not-accepted = Ese reto todavía no ha sido aceptado.
+
+## Bounties
+
+bounty-server-only = Las recompensas solo funcionan dentro de un servidor.
+bounty-invalid-title = Los títulos deben tener de 1 a { $max } caracteres.
+bounty-invalid-days = Las recompensas pueden durar de 1 a { $max } días.
+bounty-invalid-reward = Las recompensas deben ser de 1 a { $max } XP en este servidor.
+bounty-invalid-proof = La prueba debe tener de 1 a { $max } caracteres.
+bounty-too-many = Solo puedes tener { $max } recompensas abiertas a la vez.
+bounty-too-many-claims = Esa recompensa ya tiene { $max } reclamaciones.
+bounty-unknown = No hay ninguna recompensa #{ $id } en este servidor.
+bounty-closed = La recompensa #{ $id } ya está cerrada.
+bounty-claim-own = No puedes reclamar tu propia recompensa.
+bounty-not-poster = Solo quien publicó la recompensa #{ $id } puede hacer eso.
+bounty-not-claimed = { $user } no ha reclamado la recompensa #{ $id }.
+bounty-posted = ¡{ $user } ha publicado una recompensa!
+bounty-minted = ¡{ $user } ha creado una recompensa!
+bounty-claimed = { $user } ha reclamado la recompensa #{ $id }. { $poster }, usa `/recompensa aprobar` para pagarle.
+bounty-paid = ¡{ $user } ha cobrado la recompensa #{ $id }!
+bounty-cancelled = Se ha cancelado la recompensa #{ $id }.
+bounty-cancelled-refund = Se ha cancelado la recompensa #{ $id }, y se te han devuelto sus { $reward } XP.
+bounty-title = 🎯 | Recompensa #{ $id }
+bounty-list-title = 🎯 | Recompensas abiertas
+bounty-list-empty = No hay recompensas abiertas. ¡Publica una con `/recompensa publicar`!
+bounty-summary = **#{ $id }** { $title } - { $reward } XP de { $poster }, { $claims ->
+    [one] 1 reclamación
+   *[other] { $claims } reclamaciones
+}, caduca { $expires }
+bounty-choice = #{ $id } { $title } ({ $reward } XP)
+bounty-minted-by = Creada por { $user }
+bounty-reward = { $reward } XP
+bounty-no-claims = Nadie todavía.
+bounty-status-open = Abierta. Caduca { $expires }.
+bounty-status-paid = 🏆 Pagada a { $user }.
+bounty-status-cancelled = Cancelada.
+bounty-status-expired = No se pagó a nadie a tiempo.
+field-poster = Publicada por
+field-reward = Recompensa
+field-claims = Reclamaciones ({ $count })
+
+## Achievements
+
+achievement-negative-other = No puedes quitarle puntos a otra persona...
+achievement-undo = Deshacer
+achievement-unlocked = { $medal } | ¡Logro desbloqueado!
+field-achievement = Logro
+field-xp-gained = XP ganada
+field-xp-total = XP total
+field-xp-removed = XP retirada
+achievement-footer = Logro #{ $id }
+achievement-retracted = ↩️ | Logro retirado
+achievement-retracted-footer = Logro #{ $id } | Retirado por { $name }
+
+## Limits on granting XP
+
+limit-max-xp = En este servidor, los logros pueden valer como máximo { $max } XP.
+limit-cooldown = { $remaining ->
+    [one] Tienes que esperar { $remaining } segundo más antes de conceder otro logro.
+   *[other] Tienes que esperar { $remaining } segundos más antes de conceder otro logro.
+}
+limit-granted = Solo puedes conceder { $cap } XP al día. Te quedan { $left } XP por conceder hoy.
+limit-received = { $user } solo puede recibir { $cap } XP al día. Hoy puede recibir { $left } XP más.
+limit-reciprocal = { $user } y tú os habéis concedido mucha XP hoy. ¡Inténtalo de nuevo mañana!
+
+## Language
+
+language-unknown = Ese idioma no está disponible. Elige entre: { $options }, o auto.
+language-unknown-value = `{ $value }` no es un idioma disponible. Elige entre: { $options }, o auto.
+language-set = A partir de ahora te responderé en español.
+language-auto = A partir de ahora te responderé en el idioma de tu servidor, o en el de tu Discord.
+
+## Server settings
+
+config-none = Ninguno
+config-on = Activado
+config-off = Desactivado
+config-auto = Automático
+config-invalid-id = `{ $value }` no es un canal, rol ni ID. Usa `none` para borrarlo.
+config-invalid-number = `{ $value }` tiene que ser un número entero, 0 o más.
+config-invalid-bool = `{ $value }` tiene que ser `on` u `off`.
+config-invalid-colour = `{ $value }` tiene que ser un color hexadecimal, como `#F1C40F`.
+config-medal-order = Las medallas de plata no pueden necesitar más XP ({ $silver }) que las de oro ({ $gold }).
+config-server-only = Los ajustes solo se pueden cambiar dentro de un servidor.
+config-set = `{ $key }` ahora es { $value }
+config-reset = Se ha restablecido `{ $key }`.
+config-reset-all = Se han restablecido todos los ajustes.
+
+## Admin tools
+
+admin-set-xp = La XP de { $name } ha pasado de { $old } a { $new }. Ahora está en el Nv. { $lvl } con { $xp } XP.
+admin-adjust-xp = La XP de { $name } ha cambiado en { $amount }. Ahora está en el Nv. { $lvl } con { $xp } XP.
+admin-level-too-low = El nivel tiene que ser al menos 1.
+admin-set-level = El nivel de { $name } ha pasado de { $old } a { $new }.
+admin-prestige-too-low = El prestigio tiene que ser un número, y al menos 1.0.
+admin-set-prestige = El prestigio de { $name } ha pasado de { $old } a { $new }.
+admin-no-data = { $name } no tiene datos.
+admin-reset = Se ha reiniciado el progreso de { $name }.
+admin-title-too-short = { $words ->
+    [one] { $name } solo tiene { $words } palabra en su título.
+   *[other] { $name } solo tiene { $words } palabras en su título.
+}
+admin-remove-title = Se ha quitado "{ $removed }" del título de { $name }.
+admin-set-title = Ahora el título de { $name } es "{ $title }".
+admin-filter-empty = No hay palabras prohibidas en los títulos de este servidor.
+admin-filter-list = Palabras prohibidas en los títulos: { $words }
+admin-filter-invalid = Esa palabra necesita al menos una letra o un número.
+admin-filter-exists = Esa palabra ya está prohibida.
+admin-filter-missing = Esa palabra no está prohibida.
+admin-filter-full = Un servidor solo puede prohibir { $max } palabras.
+admin-filter-added = Esa palabra ahora está prohibida en los títulos.
+admin-filter-removed = Esa palabra ya no está prohibida en los títulos.
+admin-categories-empty = Este servidor todavía no tiene categorías.
+admin-categories-list = Categorías: { $categories }
+admin-category-invalid = El nombre de una categoría no puede estar vacío, ser "none", ni tener más de { $max } caracteres.
+admin-category-exists = Esa categoría ya existe.
+admin-category-missing = No hay ninguna categoría con ese nombre.
+admin-categories-full = Un servidor solo puede tener { $max } categorías.
+admin-category-added = Ahora se pueden conceder logros en { $category }.
+admin-category-removed = Se ha quitado la categoría { $category }. Los logros que ya se concedieron en ella la conservan.
+admin-delete = Se han borrado los datos de { $name }.
+admin-no-achievement = No existe el logro #{ $id } en este servidor.
+admin-already-revoked = El logro #{ $id } ya se había revocado.
+admin-recipient-gone = El jugador que recibió ese logro ya no tiene datos.
+admin-revoke = Se ha revocado el logro #{ $id } "{ $title }", quitándole { $xp } XP a { $user }.
+admin-no-achievements = { $name } no tiene logros en este servidor.
+admin-history = Logros más recientes de { $name } en este servidor:
+admin-limits-current = Límites actuales:
+admin-limits-updated = Límites actualizados:
+admin-limits =
+    Espera entre logros: { $cooldown } s
+    XP máxima concedida al día: { $granted }
+    XP máxima recibida al día: { $received }
+    XP máxima por logro: { $max }
+    Límite de concesiones mutuas: { $reciprocal }
+admin-reason-needed = Tienes que dar un motivo para cambiar eso.
+admin-digest-current = Resumen actual: { $schedule }
+admin-digest-updated = Resumen actualizado: { $schedule }
+admin-digest-bad-hour = La hora tiene que estar entre 0 y 23 (UTC).
+admin-digest-bad-weekday = Eso no es un día de la semana.
+admin-digest-none = Todavía no hay ningún resumen configurado. Elige una frecuencia para empezar uno.
+admin-digest-no-channel = Tienes que elegir un canal para el resumen.
+admin-export-failed = No se pudieron exportar los jugadores: { $error }
+admin-exported = { $count ->
+    [one] Se ha exportado { $count } jugador.
+   *[other] Se han exportado { $count } jugadores.
+}
+admin-file-type = El archivo tiene que terminar en `.csv` o `.json`.
+admin-file-too-big = El archivo es demasiado grande: el límite es { $limit } KB.
+admin-import-failed = No se ha importado nada. { $error }
+admin-import-dry-run = Simulación, así que no ha cambiado nada. Importar daría: { $summary }
+admin-imported = Jugadores importados: { $summary }
+admin-migrate-dry-run = Simulación, así que no ha cambiado nada. Migrar daría: { $summary }
+admin-migrated = Jugadores migrados: { $summary }
+
+## Digests
+
+digest-daily = 📰 Resumen diario
+digest-weekly = 📰 Resumen semanal
+digest-range = Del { $start } al { $end }
+digest-category = Solo se incluyen los logros de { $category }.
+digest-gainers = 📈 Quién más XP ha ganado
+digest-levels = ⬆️ Nuevos niveles
+digest-prestiges = ✨ Prestigios
+digest-achievements = 🏅 Logros destacados
+digest-gainer = { $user } - { $xp } XP
+digest-level = { $user } ha llegado al Nv. { $lvl }
+digest-prestige = { $user } ha llegado a { $prestige } de prestigio, y ahora es "{ $title }"
+digest-achievement = **{ $title }** - { $user }, { $xp } XP de { $granter }
+
+## Slash commands
+# Names must be lowercase, with no spaces.
+
+cmd-register = registrar
+    .description = Vuelve a registrar los comandos de la aplicación en Discord.
+cmd-prestige = prestigio
+    .description = Reinicia tu progreso, con ventaja.
+cmd-prestige_start = empezar
+    .description = Consigue Prestigio ahora, después de ver lo que obtendrás.
+    .title = titulo
+    .title-description = Una palabra nueva para añadir a tu título.
+cmd-prestige_preview = vista_previa
+    .description = Mira lo que haría conseguir Prestigio, sin hacerlo.
+    .title = titulo
+    .title-description = Una palabra que podrías añadir a tu título
+cmd-level = nivel
+    .description = Consulta tu XP, nivel y prestigio.
+    .user = usuario
+    .user-description = El usuario a consultar
+cmd-leaderboard = clasificacion
+    .description = Mira quién va primero.
+    .sort = orden
+    .sort-description = Cómo ordenar a los jugadores (por defecto: nivel)
+    .page = pagina
+    .page-description = Qué página mostrar (por defecto: 1)
+    .category = categoria
+    .category-description = Contar solo los logros de esta categoría
+cmd-stats = estadisticas
+    .description = Mira tu XP y tus logros, separados por categoría.
+    .user = usuario
+    .user-description = El usuario a consultar
+cmd-compare = comparar
+    .description = Compara a dos jugadores cara a cara.
+    .user_a = usuario_a
+    .user_a-description = El primer jugador
+    .user_b = usuario_b
+    .user_b-description = El segundo jugador (por defecto: tú)
+cmd-achievement = logro
+    .description = Completa un logro y gana XP.
+    .title = titulo
+    .title-description = Título de tu logro
+    .xp-description = XP conseguida
+    .recipient = destinatario
+    .recipient-description = Quién recibe el logro
+    .category = categoria
+    .category-description = Qué tipo de logro es
+cmd-language = idioma
+    .description = Elige el idioma en el que te responde el bot.
+    .language = idioma
+    .language-description = Tu idioma, o auto para usar el de tu Discord
+cmd-admin = admin
+    .description = Herramientas de moderación para corregir los datos de la gente.
+cmd-admin_set_xp = fijar_xp
+    .description = Fija la XP de un jugador a una cantidad exacta.
+    .user = usuario
+    .user-description = El usuario elegido
+    .xp-description = La nueva XP
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_adjust_xp = ajustar_xp
+    .description = Suma o resta XP a un jugador, sin tener en cuenta su prestigio.
+    .user = usuario
+    .user-description = El usuario elegido
+    .amount = cantidad
+    .amount-description = XP a sumar (negativa para restar)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_set_level = fijar_nivel
+    .description = Fija el nivel de un jugador.
+    .user = usuario
+    .user-description = El usuario elegido
+    .level = nivel
+    .level-description = El nuevo nivel
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_set_prestige = fijar_prestigio
+    .description = Fija el multiplicador de prestigio de un jugador.
+    .user = usuario
+    .user-description = El usuario elegido
+    .prestige = prestigio
+    .prestige-description = El nuevo prestigio
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_reset = reiniciar
+    .description = Reinicia el progreso de un jugador.
+    .user = usuario
+    .user-description = El usuario elegido
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_remove_title = quitar_titulo
+    .description = Quita una palabra del título de un jugador.
+    .user = usuario
+    .user-description = El usuario elegido
+    .index = posicion
+    .index-description = Qué palabra quitar (1 es la primera)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-update_title = titulo
+    .description = Cambia una palabra de tu título.
+    .index = posicion
+    .index-description = Qué palabra cambiar (1 es la primera)
+    .word = palabra
+    .word-description = La nueva palabra
+cmd-perks = ventajas
+    .description = Gasta los puntos que ganas con el Prestigio en ventajas.
+cmd-perks_view = ver
+    .description = Mira tus puntos de ventaja, y todas las ventajas que puedes comprar.
+cmd-perks_buy = comprar
+    .description = Compra el siguiente rango de una ventaja.
+    .perk = ventaja
+    .perk-description = La ventaja que quieres comprar
+cmd-shop = tienda
+    .description = Gasta las monedas que ganas con logros y subiendo de nivel.
+cmd-shop_view = ver
+    .description = Mira tus monedas, y todo lo que hay en la tienda.
+cmd-shop_buy = comprar
+    .description = Compra un artículo de la tienda.
+    .item = articulo
+    .item-description = El artículo que quieres comprar
+cmd-shop_equip = equipar
+    .description = Equípate un artículo que tengas, o quítatelo.
+    .item = articulo
+    .item-description = El artículo que quieres equiparte o quitarte
+cmd-team = equipo
+    .description = Forma un equipo con tus amigos, y ganad XP juntos.
+cmd-team_create = crear
+    .description = Crea un equipo nuevo.
+    .name = nombre
+    .name-description = Cómo se llama el equipo
+    .tag = etiqueta
+    .tag-description = Una etiqueta corta para el equipo, como ABC
+cmd-team_invite = invitar
+    .description = Invita a alguien a tu equipo.
+    .user = usuario
+    .user-description = A quién invitar
+cmd-team_join = unirse
+    .description = Únete a un equipo al que te han invitado.
+    .team = equipo
+    .team-description = El nombre o la etiqueta del equipo
+cmd-team_leave = salir
+    .description = Sal de tu equipo.
+cmd-team_kick = expulsar
+    .description = Expulsa a alguien de tu equipo, o cancela su invitación.
+    .user = usuario
+    .user-description = A quién expulsar
+cmd-team_info = info
+    .description = Mira el nivel y los miembros de un equipo.
+    .team = equipo
+    .team-description = El nombre o la etiqueta del equipo (por defecto: tu equipo)
+cmd-team_leaderboard = clasificacion
+    .description = Mira qué equipos han ganado más XP.
+    .page = pagina
+    .page-description = Qué página mostrar (por defecto: 1)
+cmd-challenge = retar
+    .description = Reta a alguien a una apuesta de XP.
+    .user = usuario
+    .user-description = A quién retar
+    .xp = xp
+    .xp-description = Cuánta XP pone cada uno
+    .description-description = En qué consiste el reto
+cmd-bounty = recompensa
+    .description = Publica, reclama y paga recompensas.
+cmd-bounty_post = publicar
+    .description = Publica una recompensa, pagada con tu propia XP.
+    .title = titulo
+    .title-description = Qué hay que hacer
+    .xp = xp
+    .xp-description = La XP de la recompensa, apartada de tu nivel actual
+    .days = dias
+    .days-description = Cuántos días faltan para que caduque (por defecto: 7)
+cmd-bounty_claim = reclamar
+    .description = Reclama una recompensa.
+    .id = id
+    .id-description = La recompensa a reclamar
+    .proof = prueba
+    .proof-description = Cómo hiciste lo que pide
+cmd-bounty_view = ver
+    .description = Mira una recompensa y sus reclamaciones.
+    .id = id
+    .id-description = La recompensa a ver
+cmd-bounty_approve = aprobar
+    .description = Aprueba una reclamación de tu recompensa, y paga a quien la hizo.
+    .id = id
+    .id-description = Tu recompensa
+    .user = usuario
+    .user-description = A quién pagar
+cmd-bounty_cancel = cancelar
+    .description = Retira tu recompensa, y recupera su XP.
+    .id = id
+    .id-description = Tu recompensa
+cmd-bounties = recompensas
+    .description = Mira las recompensas abiertas de este servidor.
+    .page = pagina
+    .page-description = Qué página mostrar (por defecto: 1)
+cmd-admin_set_title = fijar_titulo
+    .description = Cambia una palabra del título de un jugador, ignorando las palabras prohibidas.
+    .user = usuario
+    .user-description = El usuario elegido
+    .word = palabra
+    .word-description = La nueva palabra
+    .index = posicion
+    .index-description = Qué palabra cambiar (por defecto: añadir una al final)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_title_filter = filtro_titulos
+    .description = Consulta o cambia las palabras prohibidas en los títulos.
+    .action = accion
+    .action-description = Añadir, quitar o ver las palabras prohibidas
+    .word = palabra
+    .word-description = La palabra a añadir o quitar
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_categories = categorias
+    .description = Consulta o cambia las categorías en las que se pueden conceder logros.
+    .action = accion
+    .action-description = Añadir, quitar o ver las categorías
+    .name = nombre
+    .name-description = La categoría a añadir o quitar
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_delete = borrar
+    .description = Borra por completo los datos de un jugador.
+    .user = usuario
+    .user-description = El usuario elegido
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_revoke = revocar
+    .description = Revoca un logro anterior.
+    .achievement_id = id_logro
+    .achievement_id-description = ID del logro, que aparece debajo de él
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_history = historial
+    .description = Muestra los logros más recientes de un jugador, con sus ID.
+    .user = usuario
+    .user-description = El usuario elegido
+cmd-admin_limits = limites
+    .description = Consulta o cambia los límites para conceder XP en este servidor.
+    .cooldown_secs = espera_segundos
+    .cooldown_secs-description = Segundos entre logros concedidos
+    .daily_granted_cap = maximo_concedido
+    .daily_granted_cap-description = La XP máxima que alguien puede conceder al día
+    .daily_received_cap = maximo_recibido
+    .daily_received_cap-description = La XP máxima que alguien puede recibir al día
+    .max_xp_per_achievement = maximo_por_logro
+    .max_xp_per_achievement-description = La XP máxima que puede valer un logro
+    .reciprocal_limit = limite_mutuo
+    .reciprocal_limit-description = Concesiones al día entre dos usuarios antes de parecer sospechoso
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_digest = resumen
+    .description = Consulta o cambia cuándo se publica el resumen de este servidor.
+    .period = frecuencia
+    .period-description = Cada cuánto publicarlo
+    .channel = canal
+    .channel-description = El canal donde publicarlo
+    .hour = hora
+    .hour-description = Hora del día a la que publicarlo, en UTC (por defecto 0)
+    .weekday = dia
+    .weekday-description = Día de la semana para los resúmenes semanales (por defecto el lunes)
+    .category = categoria
+    .category-description = Incluir solo los logros de esta categoría, o "none" para todos
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_export = exportar
+    .description = Exporta los datos de todos los jugadores a un archivo.
+    .format = formato
+    .format-description = Formato del archivo
+cmd-admin_import = importar
+    .description = Importa jugadores desde un archivo CSV o JSON.
+    .file = archivo
+    .file-description = Un archivo .csv o .json
+    .strategy = estrategia
+    .strategy-description = Qué hacer con los jugadores que ya existen
+    .dry_run = simulacion
+    .dry_run-description = Solo mostrar lo que cambiaría (por defecto: sí)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_migrate = migrar
+    .description = Trae jugadores desde la clasificación de otro bot de niveles.
+    .file = archivo
+    .file-description = La clasificación del otro bot, como archivo .csv o .json
+    .strategy = estrategia
+    .strategy-description = Qué hacer con los jugadores que ya existen (por defecto: omitir)
+    .dry_run = simulacion
+    .dry_run-description = Solo mostrar lo que cambiaría (por defecto: sí)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-admin_bounty = recompensa
+    .description = Publica una recompensa que no sale de la XP de nadie.
+    .title = titulo
+    .title-description = Qué hay que hacer
+    .xp = xp
+    .xp-description = La XP de la recompensa
+    .days = dias
+    .days-description = Cuántos días faltan para que caduque (por defecto: 7)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-config = ajustes
+    .description = Consulta o cambia los ajustes de este servidor.
+cmd-config_get = ver
+    .description = Muestra los ajustes de este servidor.
+    .key = ajuste
+    .key-description = Un solo ajuste a mostrar (por defecto: todos)
+cmd-config_set = cambiar
+    .description = Cambia un ajuste de este servidor.
+    .key = ajuste
+    .key-description = El ajuste a cambiar
+    .value = valor
+    .value-description = El nuevo valor. Usa "none" para quitar un canal o rol
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio
+cmd-config_reset = restablecer
+    .description = Restablece un ajuste, o todos, a su valor por defecto.
+    .key = ajuste
+    .key-description = El ajuste a restablecer (por defecto: todos)
+    .reason = motivo
+    .reason-description = Por qué se hace este cambio